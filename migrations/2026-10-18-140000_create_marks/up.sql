@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS marks (
+    puzzle_id TEXT NOT NULL,
+    position TEXT NOT NULL,
+    marks TEXT NOT NULL,
+    PRIMARY KEY (puzzle_id, position)
+)
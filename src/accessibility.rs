@@ -0,0 +1,102 @@
+// For those who can't see the board: the position and the moves in words, and a
+// text-to-speech command (like espeak-ng or say) to read them aloud.
+use std::process::Command;
+use std::sync::mpsc::{self, Sender};
+use std::sync::OnceLock;
+use std::thread;
+use chess::{Board, BoardStatus, ChessMove, Color, File, Piece};
+
+use crate::lang;
+
+const PIECES: [Piece; 6] = [Piece::King, Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight, Piece::Pawn];
+
+// The texts are said one after the other by the same thread, so they don't talk over each other
+static SPEECH: OnceLock<Sender<(String, String)>> = OnceLock::new();
+
+pub fn piece_name(piece: Piece, lang: &lang::Language) -> String {
+    let key = match piece {
+        Piece::King => "piece_name_king",
+        Piece::Queen => "piece_name_queen",
+        Piece::Rook => "piece_name_rook",
+        Piece::Bishop => "piece_name_bishop",
+        Piece::Knight => "piece_name_knight",
+        Piece::Pawn => "piece_name_pawn",
+    };
+    lang::tr(lang, key)
+}
+
+// One line per side, like "White: King g1. Rook a1, f1. Pawn a2, b2, c3."
+pub fn describe_position(board: &Board, lang: &lang::Language) -> String {
+    let mut sides = Vec::new();
+    for (color, side_key) in [(Color::White, "white"), (Color::Black, "black")] {
+        let mut groups = Vec::new();
+        for piece in PIECES {
+            let squares: Vec<String> = (*board.pieces(piece) & *board.color_combined(color)).map(|square| square.to_string()).collect();
+            if !squares.is_empty() {
+                groups.push(piece_name(piece, lang) + " " + &squares.join(", "));
+            }
+        }
+        sides.push(lang::tr(lang, side_key) + ": " + &groups.join(". ") + ".");
+    }
+    sides.join("\n")
+}
+
+// Like "Knight g1 to f3" or "Bishop c4 takes Pawn f7, check", from the board before the move
+pub fn describe_move(board: &Board, movement: ChessMove, lang: &lang::Language) -> String {
+    let (from, to) = (movement.get_source(), movement.get_dest());
+    let Some(piece) = board.piece_on(from) else {
+        return movement.to_string();
+    };
+    let file_distance = (from.get_file().to_index() as i32 - to.get_file().to_index() as i32).abs();
+    // An empty square taken diagonally by a pawn is en passant
+    let captured = board.piece_on(to).or((piece == Piece::Pawn && file_distance == 1).then_some(Piece::Pawn));
+    let mut description = if piece == Piece::King && file_distance == 2 {
+        let side = if to.get_file() == File::G { "castles_kingside" } else { "castles_queenside" };
+        piece_name(piece, lang) + " " + &lang::tr(lang, side)
+    } else if let Some(captured) = captured {
+        format!("{} {} {} {} {}", piece_name(piece, lang), from, lang::tr(lang, "move_takes"), piece_name(captured, lang), to)
+    } else {
+        format!("{} {} {} {}", piece_name(piece, lang), from, lang::tr(lang, "move_to"), to)
+    };
+    if let Some(promotion) = movement.get_promotion() {
+        description = description + ", " + &lang::tr(lang, "move_promotes") + " " + &piece_name(promotion, lang);
+    }
+    let after_move = board.make_move_new(movement);
+    if after_move.status() == BoardStatus::Checkmate {
+        description = description + ", " + &lang::tr(lang, "move_checkmate");
+    } else if after_move.checkers().popcnt() > 0 {
+        description = description + ", " + &lang::tr(lang, "move_check");
+    }
+    description
+}
+
+// The text is added as the last argument of the command, or replaces the {} in it
+// when there's one (like "espeak-ng -v fr {}"). It doesn't wait for the speech to end.
+pub fn speak(command: &str, text: &str) {
+    let sender = SPEECH.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<(String, String)>();
+        thread::spawn(move || {
+            for (command, text) in receiver {
+                run_speech_command(&command, &text);
+            }
+        });
+        sender
+    });
+    let _ = sender.send((command.to_owned(), text.to_owned()));
+}
+
+fn run_speech_command(command: &str, text: &str) {
+    let mut words = command.split_whitespace();
+    let Some(program) = words.next() else {
+        return;
+    };
+    let mut args: Vec<String> = words.map(String::from).collect();
+    if args.iter().any(|arg| arg.contains("{}")) {
+        args = args.iter().map(|arg| arg.replace("{}", text)).collect();
+    } else {
+        args.push(text.to_owned());
+    }
+    if let Err(e) = Command::new(program).args(args).status() {
+        eprintln!("Failed to run the speech command: {}", e);
+    }
+}
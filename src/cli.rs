@@ -0,0 +1,224 @@
+// The command line mode, to search and export without opening the window, e.g.:
+// offline-chess-puzzles search --theme fork --min 1200 --max 1600 --limit 50 --export pdf out.pdf
+// It uses the settings file like the app (language, themes, PDF layout, database location).
+use rand::{rng, seq::SliceRandom};
+
+use crate::{config, db, export, tui, openings::{Openings, Variation}, search_tab::{OpeningSide, SearchTab, TacticalThemes}};
+
+const USAGE: &str = "Usage: offline-chess-puzzles search [options]
+       offline-chess-puzzles solve [options]
+
+search lists or exports the puzzles, solve opens them in the terminal to be solved.
+
+Options:
+  --base <lichess|favorites|mine|custom>  Where to search (default: lichess)
+  --theme <tag>             Lichess theme tag, like fork or mateIn2 (default: all)
+  --opening <tag>           Lichess opening tag, like Sicilian_Defense (lichess and favorites only)
+  --side <any|white|black>  Side that played the opening (default: any)
+  --min <rating>            Minimum rating (default: 0)
+  --max <rating>            Maximum rating (default: 4000)
+  --min-popularity <value>  Minimum popularity, from -100 to 100 (default: -100)
+  --source <name>           Source of the puzzles, for --base mine (default: all)
+  --limit <number>          Maximum number of puzzles (default: search limit from the settings)
+  --pages <number>          Pages of diagrams in the PDF (default: from the settings)
+  --export <pdf|pgn|csv|html|anki> <path>
+                            Write the puzzles to the file, instead of listing them
+  --no-color                Draw the board without colors, for solve
+
+Without --export, the puzzles are listed as \"id<TAB>rating<TAB>themes<TAB>FEN\".";
+
+#[derive(PartialEq)]
+enum Base {
+    Lichess, Favorites, Mine, Custom,
+}
+
+struct SearchOptions {
+    base: Base,
+    theme: TacticalThemes,
+    opening: Openings,
+    side: OpeningSide,
+    min_rating: i32,
+    max_rating: i32,
+    min_popularity: i32,
+    source: Option<String>,
+    limit: usize,
+    pages: i32,
+    export: Option<(String, String)>,
+    color: bool,
+}
+
+// Called instead of opening the window when there are arguments. Returns the exit code.
+pub fn run(args: &[String]) -> i32 {
+    match args.first().map(String::as_str) {
+        Some("search") => match parse_options(&args[1..]) {
+            Ok(options) => search(options),
+            Err(error) => {
+                eprintln!("{}\n\n{}", error, USAGE);
+                2
+            }
+        },
+        Some("solve") => match parse_options(&args[1..]) {
+            Ok(options) => solve(options),
+            Err(error) => {
+                eprintln!("{}\n\n{}", error, USAGE);
+                2
+            }
+        },
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            0
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            2
+        }
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<SearchOptions, String> {
+    let mut options = SearchOptions {
+        base: Base::Lichess,
+        theme: TacticalThemes::All,
+        opening: Openings::Any,
+        side: OpeningSide::Any,
+        min_rating: 0,
+        max_rating: 4000,
+        min_popularity: -100,
+        source: None,
+        limit: config::SETTINGS.search_results_limit,
+        pages: config::SETTINGS.export_pgs,
+        export: None,
+        color: true,
+    };
+    let mut args = args.iter();
+    while let Some(option) = args.next() {
+        let mut value = || args.next().cloned().ok_or_else(|| format!("Missing value for {}", option));
+        match option.as_str() {
+            "--base" => {
+                options.base = match value()?.as_str() {
+                    "lichess" => Base::Lichess,
+                    "favorites" => Base::Favorites,
+                    "mine" => Base::Mine,
+                    "custom" => Base::Custom,
+                    other => return Err(format!("Unknown base: {}", other)),
+                }
+            }
+            "--theme" => {
+                let tag = value()?;
+                options.theme = TacticalThemes::from_tag_name(&tag).ok_or_else(|| format!("Unknown theme: {}", tag))?;
+            }
+            "--opening" => {
+                let tag = value()?;
+                options.opening = Openings::from_field_name(&tag).ok_or_else(|| format!("Unknown opening: {}", tag))?;
+            }
+            "--side" => {
+                options.side = match value()?.as_str() {
+                    "any" => OpeningSide::Any,
+                    "white" => OpeningSide::White,
+                    "black" => OpeningSide::Black,
+                    other => return Err(format!("Unknown side: {}", other)),
+                }
+            }
+            "--min" => options.min_rating = number(option, &value()?)?,
+            "--max" => options.max_rating = number(option, &value()?)?,
+            "--min-popularity" => options.min_popularity = number(option, &value()?)?,
+            "--limit" => options.limit = number(option, &value()?)?,
+            "--pages" => options.pages = number(option, &value()?)?,
+            "--source" => options.source = Some(value()?),
+            "--no-color" => options.color = false,
+            "--export" => {
+                let format = value()?;
+                if !["pdf", "pgn", "csv", "html", "anki"].contains(&format.as_str()) {
+                    return Err(format!("Unknown export format: {}", format));
+                }
+                options.export = Some((format, value()?));
+            }
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+    }
+    Ok(options)
+}
+
+fn number<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value.parse::<T>().map_err(|_| format!("Invalid number for {}: {}", option, value))
+}
+
+fn find_puzzles(runtime: &tokio::runtime::Runtime, options: &SearchOptions) -> Option<Vec<config::Puzzle>> {
+    match options.base {
+        Base::Lichess => runtime.block_on(SearchTab::search(options.min_rating, options.max_rating, options.min_popularity,
+            options.theme, options.opening, Variation::ANY, Some(options.side), options.limit)),
+        Base::Favorites => db::get_favorites(options.min_rating, options.max_rating, options.min_popularity,
+            options.theme, options.opening, Variation::ANY, Some(options.side), options.limit),
+        Base::Mine => db::get_my_puzzles(options.theme, options.source.clone(), options.limit),
+        Base::Custom => db::get_custom_puzzles(options.min_rating, options.max_rating, options.theme, options.limit),
+    }
+}
+
+fn new_runtime() -> Option<tokio::runtime::Runtime> {
+    match tokio::runtime::Builder::new_current_thread().build() {
+        Ok(runtime) => Some(runtime),
+        Err(error) => {
+            eprintln!("{}", error);
+            None
+        }
+    }
+}
+
+fn solve(options: SearchOptions) -> i32 {
+    let Some(runtime) = new_runtime() else {
+        return 1;
+    };
+    let Some(mut puzzles) = find_puzzles(&runtime, &options) else {
+        eprintln!("Error reading the puzzles");
+        return 1;
+    };
+    // Like in the app, so the same search doesn't always start with the same puzzles
+    puzzles.shuffle(&mut rng());
+    tui::run(puzzles, options.color)
+}
+
+fn search(options: SearchOptions) -> i32 {
+    let Some(runtime) = new_runtime() else {
+        return 1;
+    };
+    let Some(puzzles) = find_puzzles(&runtime, &options) else {
+        eprintln!("Error reading the puzzles");
+        return 1;
+    };
+
+    let Some((format, path)) = options.export else {
+        for puzzle in &puzzles {
+            println!("{}\t{}\t{}\t{}", puzzle.puzzle_id, puzzle.rating, puzzle.themes, puzzle.fen);
+        }
+        return 0;
+    };
+    let lang = &config::SETTINGS.lang;
+    let result = match format.as_str() {
+        "pdf" => {
+            let layout = export::PdfLayout {
+                diagrams_per_page: config::SETTINGS.pdf_diagrams_per_page,
+                square_size: config::SETTINGS.pdf_square_size,
+                page_size: config::SETTINGS.pdf_page_size,
+                landscape: config::SETTINGS.pdf_landscape,
+                answer_key: config::SETTINGS.pdf_answer_key,
+                piece_theme: config::SETTINGS.piece_theme,
+                board_theme: config::SETTINGS.board_theme,
+            };
+            export::to_pdf(&puzzles, options.pages, &layout, lang, path.clone())
+        }
+        "pgn" => export::to_pgn(&puzzles, lang, path.clone()),
+        "csv" => export::to_csv(&puzzles, path.clone()),
+        "html" => export::to_html(&puzzles, lang, path.clone(), &config::SETTINGS.piece_theme, &config::SETTINGS.board_theme),
+        _ => runtime.block_on(export::to_anki(puzzles, *lang, path.clone(), config::SETTINGS.piece_theme, config::SETTINGS.board_theme)),
+    };
+    match result {
+        Ok(written) => {
+            println!("{} puzzles exported to {}", written, path);
+            0
+        }
+        Err(file_path) => {
+            eprintln!("Error writing {}", file_path);
+            1
+        }
+    }
+}
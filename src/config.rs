@@ -1,4 +1,4 @@
-use crate::{styles, search_tab::TacticalThemes, search_tab::OpeningSide, lang, openings::{Openings, Variation}};
+use crate::{styles, export, shortcuts, search_tab::TacticalThemes, search_tab::OpeningSide, lang, openings::{Openings, Variation}};
 use once_cell::sync::Lazy;
 use chess::{Board, ChessMove, Piece, Square};
 use std::str::FromStr;
@@ -20,6 +20,8 @@ pub const PIECES_DIRECTORY: &str = "pieces/";
 pub const SETTINGS_FILE: &str = "settings.json";
 pub const ONE_PIECE_SOUND_FILE: &str = "1piece.ogg";
 pub const TWO_PIECES_SOUND_FILE: &str = "2pieces.ogg";
+// Stands for the opponent's move in puzzles that start without one (imported positions)
+pub const NULL_MOVE: &str = offline_chess_puzzles::session::NULL_MOVE;
 
 // Iced widget IDs need to be static
 pub static BTN_IDS: [&'static str; 64] = [
@@ -36,12 +38,37 @@ pub static BTN_IDS: [&'static str; 64] = [
 pub enum GameMode {
     Puzzle,
     Analysis,
+    PlayEngine,
+    Editor,
+}
+
+// Blindfold training, the pieces are still on the board for the moves, they just aren't drawn
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Blindfold {
+    #[default]
+    Off,
+    HideAll,
+    PawnsOnly,
+    // Shown for a few seconds after the puzzle is loaded, then all hidden
+    HideAfter,
+}
+
+impl Blindfold {
+    pub fn hides(&self, piece: Piece) -> bool {
+        match self {
+            Blindfold::Off => false,
+            Blindfold::PawnsOnly => piece != Piece::Pawn,
+            Blindfold::HideAll | Blindfold::HideAfter => true,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OfflinePuzzlesConfig {
     pub engine_path: Option<String>,
     pub engine_limit: String,
+    #[serde(default = "default_play_engine_limit")]
+    pub play_engine_limit: String,
     pub window_width: f32,
     pub window_height: f32,
     pub maximized: bool,
@@ -52,9 +79,43 @@ pub struct OfflinePuzzlesConfig {
     pub auto_load_next: bool,
     pub flip_board: bool,
     pub show_coordinates: bool,
+    #[serde(default = "default_true")]
+    pub show_eval_bar: bool,
+    #[serde(default = "default_engine_arrows")]
+    pub engine_arrows: usize,
+    #[serde(default = "default_verify_depth")]
+    pub verify_depth: u32,
+    #[serde(default = "default_verify_margin")]
+    pub verify_margin: i32,
     pub board_theme: styles::BoardTheme,
     pub lang: lang::Language,
     pub export_pgs: i32,
+    #[serde(default = "default_pdf_diagrams_per_page")]
+    pub pdf_diagrams_per_page: usize,
+    #[serde(default = "default_pdf_square_size")]
+    pub pdf_square_size: i32,
+    #[serde(default)]
+    pub pdf_page_size: export::PageSize,
+    #[serde(default)]
+    pub pdf_landscape: bool,
+    #[serde(default = "default_true")]
+    pub pdf_answer_key: bool,
+    #[serde(default)]
+    pub blindfold: Blindfold,
+    #[serde(default = "default_blindfold_seconds")]
+    pub blindfold_seconds: u64,
+    #[serde(default)]
+    pub key_bindings: shortcuts::KeyBindings,
+    #[serde(default)]
+    pub describe_position: bool,
+    #[serde(default)]
+    pub tts_command: String,
+    #[serde(default = "default_true")]
+    pub animate_moves: bool,
+    #[serde(default = "default_animation_ms")]
+    pub animation_ms: u64,
+    #[serde(default = "default_reply_delay_ms")]
+    pub reply_delay_ms: u64,
     pub last_min_rating: i32,
     pub last_max_rating: i32,
     pub last_min_popularity: i32,
@@ -69,6 +130,7 @@ impl ::std::default::Default for OfflinePuzzlesConfig {
         Self {
             engine_path: None,
             engine_limit: String::from("depth 40"),
+            play_engine_limit: default_play_engine_limit(),
             window_width: 1010.,
             window_height: 680.,
             maximized: false,
@@ -79,9 +141,26 @@ impl ::std::default::Default for OfflinePuzzlesConfig {
             auto_load_next: true,
             flip_board: false,
             show_coordinates: false,
+            show_eval_bar: true,
+            engine_arrows: 1,
+            verify_depth: default_verify_depth(),
+            verify_margin: default_verify_margin(),
             board_theme: styles::BoardTheme::default(),
             lang: lang::Language::English,
             export_pgs: 50,
+            pdf_diagrams_per_page: default_pdf_diagrams_per_page(),
+            pdf_square_size: default_pdf_square_size(),
+            pdf_page_size: export::PageSize::default(),
+            pdf_landscape: false,
+            pdf_answer_key: true,
+            blindfold: Blindfold::Off,
+            blindfold_seconds: default_blindfold_seconds(),
+            key_bindings: shortcuts::KeyBindings::default(),
+            describe_position: false,
+            tts_command: String::new(),
+            animate_moves: true,
+            animation_ms: default_animation_ms(),
+            reply_delay_ms: default_reply_delay_ms(),
             last_min_rating: 0,
             last_max_rating: 1000,
             last_min_popularity: 0,
@@ -93,6 +172,48 @@ impl ::std::default::Default for OfflinePuzzlesConfig {
     }
 }
 
+// Defaults for the fields added after the first releases, so older
+// settings files can still be loaded.
+fn default_true() -> bool {
+    true
+}
+
+fn default_engine_arrows() -> usize {
+    1
+}
+
+fn default_play_engine_limit() -> String {
+    String::from("movetime 1000")
+}
+
+fn default_verify_depth() -> u32 {
+    16
+}
+
+fn default_verify_margin() -> i32 {
+    50
+}
+
+fn default_pdf_diagrams_per_page() -> usize {
+    6
+}
+
+fn default_pdf_square_size() -> i32 {
+    25
+}
+
+fn default_blindfold_seconds() -> u64 {
+    5
+}
+
+fn default_animation_ms() -> u64 {
+    200
+}
+
+fn default_reply_delay_ms() -> u64 {
+    500
+}
+
 pub fn load_config() -> OfflinePuzzlesConfig {
     let config;
     let file = std::fs::File::open(SETTINGS_FILE);
@@ -119,6 +240,13 @@ fn piece_localized(lang: &lang::Language, piece: &str) -> String {
     }
 }
 
+// The SAN letters of the pieces in the language, for reading typed moves
+pub fn piece_letters(lang: &lang::Language) -> Vec<(char, Piece)> {
+    [("K", Piece::King), ("Q", Piece::Queen), ("R", Piece::Rook), ("B", Piece::Bishop), ("N", Piece::Knight)].iter()
+        .filter_map(|(english, piece)| piece_localized(lang, english).chars().next().map(|letter| (letter, *piece)))
+        .collect()
+}
+
 pub fn coord_to_san(board: &Board, coords: String, lang: &lang::Language) -> Option<String> {
     let (promotion_piece, coords) = if coords.len() > 4 {
         (coords[4..5].to_uppercase(), String::from(&coords[0..4]))
@@ -2,9 +2,10 @@ use diesel::sqlite::SqliteConnection;
 use diesel::prelude::*;
 use dotenvy::dotenv;
 use std::env;
+use std::collections::HashMap;
 
-use crate::models::NewFavorite;
-use crate::schema::favs;
+use crate::models::{NewFavorite, NewMyPuzzle, NewCustomPuzzle, NewMarks};
+use crate::schema::{favs, my_puzzles, custom_puzzles, marks};
 use crate::schema::favs::dsl::*;
 use crate::config::Puzzle;
 
@@ -19,6 +20,28 @@ pub fn establish_connection() -> SqliteConnection {
         .unwrap_or_else(|_| panic!("Error connecting to {}", database_url))
 }
 
+// The tables added after the first release, for databases created before they existed.
+// The migrations use "IF NOT EXISTS", so they can be run on every start.
+const SCHEMA: [&str; 3] = [
+    include_str!("../migrations/2026-10-18-120000_create_my_puzzles/up.sql"),
+    include_str!("../migrations/2026-10-18-130000_create_custom_puzzles/up.sql"),
+    include_str!("../migrations/2026-10-18-140000_create_marks/up.sql"),
+];
+
+pub fn ensure_schema() {
+    let mut conn = establish_connection();
+    for create_table in SCHEMA {
+        if let Err(e) = diesel::sql_query(create_table).execute(&mut conn) {
+            eprintln!("Error creating the tables: {}", e);
+        }
+    }
+}
+
+pub fn database_url() -> String {
+    dotenv().ok();
+    env::var("DATABASE_URL").unwrap_or_default()
+}
+
 pub fn get_favorites(min_rating: i32, max_rating: i32, min_popularity: i32, theme: TacticalThemes, opening: Openings, variation: Variation, op_side: Option<OpeningSide>, result_limit: usize) -> Option<Vec<Puzzle>> {
     let mut conn = establish_connection();
     let results;
@@ -73,6 +96,12 @@ pub fn get_favorites(min_rating: i32, max_rating: i32, min_popularity: i32, them
     results.ok()
 }
 
+// All of them, without the search filters
+pub fn get_all_favorites() -> Vec<Puzzle> {
+    let mut conn = establish_connection();
+    favs.load::<Puzzle>(&mut conn).unwrap_or_default()
+}
+
 pub fn is_favorite(id: &str) -> bool {
     let mut conn = establish_connection();
     let results = favs
@@ -113,3 +142,138 @@ pub fn toggle_favorite(puzzle: Puzzle) {
             .expect("Error saving new favorite");
     }
 }
+
+// The generated puzzles don't have a rating, popularity or opening,
+// so only the theme filter applies to them.
+// No source means puzzles from all of them.
+pub fn get_my_puzzles(theme: TacticalThemes, puzzle_source: Option<String>, result_limit: usize) -> Option<Vec<Puzzle>> {
+    let mut conn = establish_connection();
+    let theme_filter = String::from("%") + theme.get_tag_name() + "%";
+    let mut query = my_puzzles::table
+        .select((my_puzzles::puzzle_id, my_puzzles::fen, my_puzzles::moves, my_puzzles::rating,
+            my_puzzles::rd, my_puzzles::popularity, my_puzzles::nb_plays, my_puzzles::themes,
+            my_puzzles::game_url, my_puzzles::opening_tags))
+        .filter(my_puzzles::themes.like(theme_filter))
+        .into_boxed();
+    if let Some(puzzle_source) = puzzle_source {
+        query = query.filter(my_puzzles::source.eq(puzzle_source));
+    }
+    query
+        .limit(result_limit as i64)
+        .load::<Puzzle>(&mut conn)
+        .ok()
+}
+
+pub fn get_my_puzzles_sources() -> Vec<String> {
+    let mut conn = establish_connection();
+    my_puzzles::table
+        .select(my_puzzles::source)
+        .distinct()
+        .order(my_puzzles::source.asc())
+        .load::<String>(&mut conn)
+        .unwrap_or_default()
+}
+
+// Returns how many were actually added, puzzles already in the table are skipped.
+// None if they couldn't be saved.
+pub fn save_my_puzzles(puzzles: &[Puzzle], puzzle_source: &str) -> Option<usize> {
+    if puzzles.is_empty() {
+        return Some(0);
+    }
+    let mut conn = establish_connection();
+    let new_puzzles: Vec<NewMyPuzzle> = puzzles.iter().map(|puzzle| NewMyPuzzle {
+        puzzle_id: &puzzle.puzzle_id,
+        fen: &puzzle.fen,
+        moves: &puzzle.moves,
+        rating: puzzle.rating,
+        rd: puzzle.rating_deviation,
+        popularity: puzzle.popularity,
+        nb_plays: puzzle.nb_plays,
+        themes: &puzzle.themes,
+        game_url: &puzzle.game_url,
+        opening_tags: &puzzle.opening,
+        source: puzzle_source,
+    }).collect();
+
+    diesel::insert_or_ignore_into(my_puzzles::table)
+        .values(&new_puzzles)
+        .execute(&mut conn)
+        .ok()
+}
+
+// The puzzles made in the editor have a rating (set by their author), but no popularity or opening.
+pub fn get_custom_puzzles(min_rating: i32, max_rating: i32, theme: TacticalThemes, result_limit: usize) -> Option<Vec<Puzzle>> {
+    let mut conn = establish_connection();
+    let theme_filter = String::from("%") + theme.get_tag_name() + "%";
+    custom_puzzles::table
+        .select((custom_puzzles::puzzle_id, custom_puzzles::fen, custom_puzzles::moves, custom_puzzles::rating,
+            custom_puzzles::rd, custom_puzzles::popularity, custom_puzzles::nb_plays, custom_puzzles::themes,
+            custom_puzzles::game_url, custom_puzzles::opening_tags))
+        .filter(custom_puzzles::rating.between(min_rating, max_rating))
+        .filter(custom_puzzles::themes.like(theme_filter))
+        .limit(result_limit as i64)
+        .load::<Puzzle>(&mut conn)
+        .ok()
+}
+
+pub fn save_custom_puzzle(puzzle: &Puzzle) -> bool {
+    let mut conn = establish_connection();
+    let new_puzzle = NewCustomPuzzle {
+        puzzle_id: &puzzle.puzzle_id,
+        fen: &puzzle.fen,
+        moves: &puzzle.moves,
+        rating: puzzle.rating,
+        rd: puzzle.rating_deviation,
+        popularity: puzzle.popularity,
+        nb_plays: puzzle.nb_plays,
+        themes: &puzzle.themes,
+        game_url: &puzzle.game_url,
+        opening_tags: &puzzle.opening,
+    };
+
+    diesel::insert_into(custom_puzzles::table)
+        .values(&new_puzzle)
+        .execute(&mut conn)
+        .is_ok()
+}
+
+// The arrows and circles drawn on the positions of a puzzle (as PGN comments), by the position
+pub fn get_marks(id: &str) -> HashMap<String, String> {
+    let mut conn = establish_connection();
+    marks::table
+        .select((marks::position, marks::marks))
+        .filter(marks::puzzle_id.eq(id))
+        .load::<(String, String)>(&mut conn)
+        .unwrap_or_default()
+        .into_iter()
+        .collect()
+}
+
+// Of all puzzles, by the puzzle's id and the position
+pub fn get_all_marks() -> HashMap<(String, String), String> {
+    let mut conn = establish_connection();
+    marks::table
+        .select((marks::puzzle_id, marks::position, marks::marks))
+        .load::<(String, String, String)>(&mut conn)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(id, position, comment)| ((id, position), comment))
+        .collect()
+}
+
+// No marks left on the position removes it from the table
+pub fn save_marks(id: &str, position: &str, comment: &str) -> bool {
+    let mut conn = establish_connection();
+    if comment.is_empty() {
+        diesel::delete(marks::table)
+            .filter(marks::puzzle_id.eq(id))
+            .filter(marks::position.eq(position))
+            .execute(&mut conn)
+            .is_ok()
+    } else {
+        diesel::replace_into(marks::table)
+            .values(&NewMarks { puzzle_id: id, position, marks: comment })
+            .execute(&mut conn)
+            .is_ok()
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use base64::Engine as _;
+use chess::{Board, Color, Square, ALL_SQUARES};
+use offline_chess_puzzles::session::PuzzleSession;
+
+use crate::{config, styles};
+
+// In the SVG's own units, the size in pixels is only set by the width/height attributes
+const SQUARE_UNITS: usize = 100;
+pub const PNG_SIZE: u32 = 800;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiagramFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+impl DiagramFormat {
+    pub const ALL: [DiagramFormat; 2] = [DiagramFormat::Svg, DiagramFormat::Png];
+
+    fn extension(&self) -> &'static str {
+        match self {
+            DiagramFormat::Svg => "svg",
+            DiagramFormat::Png => "png",
+        }
+    }
+}
+
+impl std::fmt::Display for DiagramFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                DiagramFormat::Svg => "SVG",
+                DiagramFormat::Png => "PNG",
+            }
+        )
+    }
+}
+
+// Draws boards without the window, so it doesn't depend on the layout or the screen's scaling
+pub struct DiagramRenderer {
+    board_theme: styles::BoardTheme,
+    // See piece_images()
+    pieces: HashMap<String, String>,
+}
+
+impl DiagramRenderer {
+    pub fn new(piece_theme: &styles::PieceTheme, board_theme: &styles::BoardTheme) -> Result<Self, String> {
+        Ok(DiagramRenderer { board_theme: *board_theme, pieces: piece_images(piece_theme)? })
+    }
+
+    pub fn to_svg(&self, board: &Board, highlights: &[Square], white_at_bottom: bool) -> String {
+        let palette = self.board_theme.palette();
+        let board_size = 8 * SQUARE_UNITS;
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"{PNG_SIZE}\" height=\"{PNG_SIZE}\" viewBox=\"0 0 {board_size} {board_size}\">\n");
+        for square in ALL_SQUARES {
+            let rank = square.get_rank().to_index();
+            let file = square.get_file().to_index();
+            let (column, row) = if white_at_bottom {
+                (file, 7 - rank)
+            } else {
+                (7 - file, rank)
+            };
+            let (x, y) = (column * SQUARE_UNITS, row * SQUARE_UNITS);
+            let light_square = (rank + file) % 2 != 0;
+            let color = match (light_square, highlights.contains(&square)) {
+                (true, false) => palette.light_square,
+                (false, false) => palette.dark_square,
+                (true, true) => styles::SELECTED_LIGHT_SQUARE,
+                (false, true) => styles::SELECTED_DARK_SQUARE,
+            };
+            let [r, g, b, _] = color.into_rgba8();
+            svg.push_str(&format!("<rect x=\"{x}\" y=\"{y}\" width=\"{SQUARE_UNITS}\" height=\"{SQUARE_UNITS}\" fill=\"#{r:02x}{g:02x}{b:02x}\"/>\n"));
+            if let (Some(piece), Some(piece_color)) = (board.piece_on(square), board.color_on(square)) {
+                let name = String::from(if piece_color == Color::White { "w" } else { "b" }) + &piece.to_string(Color::White);
+                svg.push_str(&format!("<image x=\"{x}\" y=\"{y}\" width=\"{SQUARE_UNITS}\" height=\"{SQUARE_UNITS}\" xlink:href=\"{}\"/>\n", self.pieces[&name]));
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    // Saves as SVG or PNG, depending on the extension of the path
+    pub fn save(&self, path: &str, board: &Board, highlights: &[Square], white_at_bottom: bool) -> Result<(), String> {
+        let svg = self.to_svg(board, highlights, white_at_bottom);
+        let is_png = std::path::Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+        if is_png {
+            let png = svg_to_png(&svg).ok_or_else(|| String::from(path))?;
+            std::fs::write(path, png).map_err(|_| String::from(path))
+        } else {
+            std::fs::write(path, svg).map_err(|_| String::from(path))
+        }
+    }
+
+    // The puzzle as it's shown to the solver, with the solver's side at the bottom
+    pub fn save_puzzle(&self, path: &str, puzzle: &config::Puzzle) -> Result<(), String> {
+        let (board, highlights) = puzzle_position(puzzle).ok_or_else(|| String::from(&puzzle.puzzle_id))?;
+        let white_at_bottom = board.side_to_move() == Color::White;
+        self.save(path, &board, &highlights, white_at_bottom)
+    }
+}
+
+// The piece SVGs of the theme as data URIs, by file name ("wP", "bK"...)
+pub fn piece_images(piece_theme: &styles::PieceTheme) -> Result<HashMap<String, String>, String> {
+    // There are no SVGs for the font "theme", so we use the default one
+    let theme_dir = if *piece_theme == styles::PieceTheme::FontAlpha {
+        styles::PieceTheme::Cburnett.to_string()
+    } else {
+        piece_theme.to_string()
+    };
+    let mut pieces = HashMap::new();
+    for color in ["w", "b"] {
+        for piece in ["P", "N", "B", "R", "Q", "K"] {
+            let name = String::from(color) + piece;
+            let path = String::from(config::PIECES_DIRECTORY) + &theme_dir + "/" + &name + ".svg";
+            let data = std::fs::read(&path).map_err(|_| path)?;
+            pieces.insert(name, String::from("data:image/svg+xml;base64,") + &base64::engine::general_purpose::STANDARD.encode(data));
+        }
+    }
+    Ok(pieces)
+}
+
+// The position after the opponent's move, and that move's squares to highlight
+pub fn puzzle_position(puzzle: &config::Puzzle) -> Option<(Board, Vec<Square>)> {
+    let session = PuzzleSession::new(&puzzle.fen, &puzzle.moves)?;
+    let highlights = session.last_move().map_or(vec![], |movement| vec![movement.get_source(), movement.get_dest()]);
+    Some((session.board(), highlights))
+}
+
+fn svg_to_png(svg: &str) -> Option<Vec<u8>> {
+    // The pieces are embedded as data URIs, which the default options already resolve
+    let tree = resvg::usvg::Tree::from_str(svg, &resvg::usvg::Options::default()).ok()?;
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(PNG_SIZE, PNG_SIZE)?;
+    let scale = PNG_SIZE as f32 / tree.size().width();
+    resvg::render(&tree, resvg::tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    pixmap.encode_png().ok()
+}
+
+// One file per puzzle in the folder, named by their position in the list and id.
+// Returns how many were saved.
+pub async fn export_puzzles(puzzles: Vec<config::Puzzle>, folder: String, format: DiagramFormat, piece_theme: styles::PieceTheme, board_theme: styles::BoardTheme) -> Result<usize, String> {
+    let renderer = DiagramRenderer::new(&piece_theme, &board_theme)?;
+    let mut saved = 0;
+    for (index, puzzle) in puzzles.iter().enumerate() {
+        let file_name = format!("{:05}_{}.{}", index + 1, puzzle.puzzle_id, format.extension());
+        let path = std::path::Path::new(&folder).join(file_name);
+        if renderer.save_puzzle(&path.display().to_string(), puzzle).is_ok() {
+            saved += 1;
+        }
+    }
+    Ok(saved)
+}
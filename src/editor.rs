@@ -0,0 +1,574 @@
+use iced::widget::{Button, Checkbox, Column, Container, PickList, Radio, Row, Scrollable, Text, TextInput, column as col, row};
+use iced::widget::text::LineHeight;
+use iced::advanced::widget::Id as GenericId;
+use iced::{alignment, Alignment, Element, Length, Point, Rectangle, Task, Theme};
+use std::str::FromStr;
+
+use iced_aw::TabLabel;
+use chess::{Board, BoardStatus, ChessMove, Color, File, Piece, Rank, Square, ALL_FILES, ALL_SQUARES};
+
+use crate::{Message, Tab, config, db, lang, san_correct_ep, styles};
+use crate::lang::PickListWrapper;
+use crate::search_tab::TacticalThemes;
+
+const DEFAULT_RATING: &str = "1500";
+const PALETTE: [Piece; 6] = [Piece::King, Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight, Piece::Pawn];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastleSide {
+    King, Queen,
+}
+
+#[derive(Debug, Clone)]
+pub enum EditorMessage {
+    SelectBrush(Option<(Piece, Color)>),
+    DropNewPiece((Piece, Color), Point, Rectangle),
+    PlaceOnZones((Piece, Color), Vec<(GenericId, Rectangle)>),
+    ChangeFen(String),
+    LoadFen,
+    Analyze,
+    SelectSide(Color),
+    ToggleCastling(Color, CastleSide, bool),
+    SelectEnPassant(String),
+    ClearBoard,
+    InitialPosition,
+    EnterSolution,
+    BackToSetup,
+    UndoMove,
+    ToggleSetupMove(bool),
+    AddTheme(PickListWrapper<TacticalThemes>),
+    RemoveTheme(TacticalThemes),
+    ChangeRating(String),
+    SavePuzzle,
+}
+
+pub struct EditorTab {
+    pieces: [Option<(Piece, Color)>; 64],
+    side_to_move: Color,
+    // White kingside, white queenside, black kingside, black queenside
+    castling: [bool; 4],
+    en_passant: Option<File>,
+    // The piece placed when clicking on the board, None removes pieces
+    brush: Option<(Piece, Color)>,
+    // Set once the position is valid, while the solution is being played
+    pub start: Option<Board>,
+    pub solution: Vec<ChessMove>,
+    setup_move: bool,
+    themes: Vec<TacticalThemes>,
+    rating: String,
+    fen_input: String,
+    status: String,
+    pub lang: lang::Language,
+}
+
+impl EditorTab {
+    pub fn new() -> Self {
+        let mut editor = EditorTab {
+            pieces: [None; 64],
+            side_to_move: Color::White,
+            castling: [true; 4],
+            en_passant: None,
+            brush: Some((Piece::Pawn, Color::White)),
+            start: None,
+            solution: Vec::new(),
+            setup_move: false,
+            themes: Vec::new(),
+            rating: String::from(DEFAULT_RATING),
+            fen_input: String::new(),
+            status: String::new(),
+            lang: config::SETTINGS.lang,
+        };
+        editor.set_position(&Board::default());
+        editor
+    }
+
+    pub fn set_position(&mut self, board: &Board) {
+        for square in ALL_SQUARES {
+            self.pieces[square.to_index()] = board.piece_on(square).zip(board.color_on(square));
+        }
+        self.side_to_move = board.side_to_move();
+        let white_rights = board.castle_rights(Color::White);
+        let black_rights = board.castle_rights(Color::Black);
+        self.castling = [white_rights.has_kingside(), white_rights.has_queenside(), black_rights.has_kingside(), black_rights.has_queenside()];
+        self.en_passant = board.en_passant().map(|square| square.get_file());
+    }
+
+    pub fn is_setting_up(&self) -> bool {
+        self.start.is_none()
+    }
+
+    // The position after the solution moves played so far
+    pub fn current_board(&self) -> Option<Board> {
+        self.start.map(|start| self.solution.iter().fold(start, |board, chess_move| board.make_move_new(*chess_move)))
+    }
+
+    pub fn pieces(&self) -> [Option<(Piece, Color)>; 64] {
+        match self.current_board() {
+            Some(board) => {
+                let mut pieces = [None; 64];
+                for square in ALL_SQUARES {
+                    pieces[square.to_index()] = board.piece_on(square).zip(board.color_on(square));
+                }
+                pieces
+            } None => self.pieces,
+        }
+    }
+
+    pub fn place_piece(&mut self, square: Square) {
+        let index = square.to_index();
+        // Clicking again with the same piece takes it back
+        self.pieces[index] = if self.pieces[index] == self.brush {
+            None
+        } else {
+            self.brush
+        };
+    }
+
+    pub fn move_piece(&mut self, from: Square, to: Option<Square>) {
+        let piece = self.pieces[from.to_index()].take();
+        if let Some(to) = to {
+            self.pieces[to.to_index()] = piece;
+        }
+    }
+
+    pub fn add_move(&mut self, chess_move: ChessMove) -> bool {
+        match self.current_board() {
+            Some(board) if board.legal(chess_move) => {
+                self.solution.push(chess_move);
+                true
+            } _ => false,
+        }
+    }
+
+    pub fn fen(&self) -> String {
+        self.fen_with_side(self.side_to_move)
+    }
+
+    fn fen_with_side(&self, side: Color) -> String {
+        let mut fen = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                let square = Square::make_square(Rank::from_index(rank), File::from_index(file));
+                match self.pieces[square.to_index()] {
+                    Some((piece, color)) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        fen.push_str(&piece.to_string(color));
+                    } None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+        fen.push_str(if side == Color::White { " w " } else { " b " });
+        let castling: String = ['K', 'Q', 'k', 'q'].iter().zip(self.castling).filter(|(_, allowed)| *allowed).map(|(c, _)| *c).collect();
+        fen.push_str(if castling.is_empty() { "-" } else { &castling });
+        match self.en_passant {
+            Some(file) => {
+                // The square behind the pawn that just moved two squares
+                let rank = if side == Color::White { Rank::Sixth } else { Rank::Third };
+                fen.push(' ');
+                fen.push_str(&Square::make_square(rank, file).to_string());
+            } None => fen.push_str(" -"),
+        }
+        fen.push_str(" 0 1");
+        fen
+    }
+
+    // Positions chess::Board won't take are still set up in the editor when the FEN
+    // can be read, so the user sees what's wrong and can fix it there.
+    pub fn load_fen(&mut self, fen: &str) -> Result<(), &'static str> {
+        if let Ok(board) = Board::from_str(fen) {
+            self.set_position(&board);
+            return Ok(());
+        }
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() < 2 {
+            return Err("fen_error_format");
+        }
+        let mut pieces = [None; 64];
+        let ranks: Vec<&str> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err("fen_error_format");
+        }
+        for (rank_index, rank) in ranks.iter().enumerate() {
+            let mut file = 0;
+            for c in rank.chars() {
+                if let Some(empty) = c.to_digit(10) {
+                    file += empty as usize;
+                    continue;
+                }
+                let piece = match c.to_ascii_lowercase() {
+                    'p' => Piece::Pawn, 'n' => Piece::Knight, 'b' => Piece::Bishop,
+                    'r' => Piece::Rook, 'q' => Piece::Queen, 'k' => Piece::King,
+                    _ => return Err("fen_error_format"),
+                };
+                if file > 7 {
+                    return Err("fen_error_format");
+                }
+                let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+                pieces[Square::make_square(Rank::from_index(7 - rank_index), File::from_index(file)).to_index()] = Some((piece, color));
+                file += 1;
+            }
+            if file != 8 {
+                return Err("fen_error_format");
+            }
+        }
+        self.side_to_move = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err("fen_error_format"),
+        };
+        self.pieces = pieces;
+        let castling = fields.get(2).unwrap_or(&"-");
+        self.castling = [castling.contains('K'), castling.contains('Q'), castling.contains('k'), castling.contains('q')];
+        self.en_passant = fields.get(3).and_then(|square| Square::from_str(square).ok()).map(|square| square.get_file());
+        self.validate().map(|_| ())
+    }
+
+    fn count(&self, piece: Piece, color: Color) -> usize {
+        self.pieces.iter().filter(|square| **square == Some((piece, color))).count()
+    }
+
+    fn piece_at(&self, rank: Rank, file: File) -> Option<(Piece, Color)> {
+        self.pieces[Square::make_square(rank, file).to_index()]
+    }
+
+    // Returns the position, or the translation key of what's wrong with it
+    pub fn validate(&self) -> Result<Board, &'static str> {
+        if self.count(Piece::King, Color::White) != 1 || self.count(Piece::King, Color::Black) != 1 {
+            return Err("editor_error_kings");
+        }
+        for file in ALL_FILES {
+            for rank in [Rank::First, Rank::Eighth] {
+                if self.piece_at(rank, file).is_some_and(|(piece, _)| piece == Piece::Pawn) {
+                    return Err("editor_error_pawns");
+                }
+            }
+        }
+        let castling_squares = [
+            (Rank::First, File::H, Color::White), (Rank::First, File::A, Color::White),
+            (Rank::Eighth, File::H, Color::Black), (Rank::Eighth, File::A, Color::Black),
+        ];
+        for ((rank, rook_file, color), allowed) in castling_squares.iter().zip(self.castling) {
+            if allowed && (self.piece_at(*rank, File::E) != Some((Piece::King, *color)) ||
+                    self.piece_at(*rank, *rook_file) != Some((Piece::Rook, *color))) {
+                return Err("editor_error_castling");
+            }
+        }
+        if let Some(file) = self.en_passant {
+            // The pawn that just moved two squares, with the squares it went through empty
+            let (pawn_rank, passed_rank, start_rank) = if self.side_to_move == Color::White {
+                (Rank::Fifth, Rank::Sixth, Rank::Seventh)
+            } else {
+                (Rank::Fourth, Rank::Third, Rank::Second)
+            };
+            if self.piece_at(pawn_rank, file) != Some((Piece::Pawn, !self.side_to_move)) ||
+                    self.piece_at(passed_rank, file).is_some() || self.piece_at(start_rank, file).is_some() {
+                return Err("editor_error_en_passant");
+            }
+        }
+        if Board::from_str(&self.fen_with_side(!self.side_to_move)).is_ok_and(|board| board.checkers().popcnt() != 0) {
+            return Err("editor_error_check");
+        }
+        let board = Board::from_str(&self.fen()).map_err(|_| "editor_error_invalid")?;
+        if board.status() != BoardStatus::Ongoing {
+            return Err("editor_error_game_over");
+        }
+        Ok(board)
+    }
+
+    fn new_puzzle(&self, start: Board, rating: i32) -> config::Puzzle {
+        let mut moves: Vec<String> = self.solution.iter().map(|chess_move| chess_move.to_string()).collect();
+        if !self.setup_move {
+            moves.insert(0, String::from(config::NULL_MOVE));
+        }
+        let themes: Vec<&str> = self.themes.iter().map(|theme| theme.get_tag_name()).collect();
+        config::Puzzle {
+            puzzle_id: String::from("custom") + &chrono::Local::now().format("%Y%m%d%H%M%S%3f").to_string(),
+            fen: san_correct_ep(start.to_string()),
+            moves: moves.join(" "),
+            rating,
+            rating_deviation: 0,
+            popularity: 0,
+            nb_plays: 0,
+            themes: themes.join(" "),
+            game_url: String::new(),
+            opening: String::new(),
+        }
+    }
+
+    fn save(&self) -> Result<String, &'static str> {
+        let Some(start) = self.start else {
+            return Err("editor_error_no_solution");
+        };
+        // Puzzles end on the solver's move, after the opponent's move if there's one
+        let solver_moves = if self.setup_move { self.solution.len().saturating_sub(1) } else { self.solution.len() };
+        if solver_moves == 0 {
+            return Err("editor_error_no_solution");
+        } else if solver_moves % 2 == 0 {
+            return Err("editor_error_solution_end");
+        }
+        let rating = self.rating.parse::<i32>().map_err(|_| "editor_error_rating")?;
+        if !(0..=3500).contains(&rating) {
+            return Err("editor_error_rating");
+        }
+        let puzzle = self.new_puzzle(start, rating);
+        if db::save_custom_puzzle(&puzzle) {
+            Ok(puzzle.puzzle_id)
+        } else {
+            Err("editor_error_saving")
+        }
+    }
+
+    pub fn update(&mut self, message: EditorMessage) -> Task<Message> {
+        match message {
+            EditorMessage::SelectBrush(brush) => {
+                self.brush = brush;
+            } EditorMessage::DropNewPiece(piece, point, _bounds) => {
+                return iced_drop::zones_on_point(
+                    move |zones| Message::Editor(EditorMessage::PlaceOnZones(piece, zones)),
+                    point,
+                    None,
+                    None,
+                );
+            } EditorMessage::PlaceOnZones(piece, zones) => {
+                let square = zones.first().and_then(|(id, _)|
+                    ALL_SQUARES.into_iter().find(|square| GenericId::new(config::BTN_IDS[square.to_index()]) == *id));
+                if let Some(square) = square {
+                    self.pieces[square.to_index()] = Some(piece);
+                }
+            } EditorMessage::ChangeFen(fen) => {
+                self.fen_input = fen;
+            } EditorMessage::LoadFen => {
+                self.status = match self.load_fen(self.fen_input.trim()) {
+                    Ok(()) => String::new(),
+                    Err(key) => lang::tr(&self.lang, key),
+                };
+            } EditorMessage::Analyze => {
+                match self.validate() {
+                    Ok(board) => {
+                        self.status.clear();
+                        return Task::done(Message::AnalyzePosition(board));
+                    } Err(key) => self.status = lang::tr(&self.lang, key),
+                }
+            } EditorMessage::SelectSide(side) => {
+                self.side_to_move = side;
+                self.en_passant = None;
+            } EditorMessage::ToggleCastling(color, castle_side, allowed) => {
+                let index = match (color, castle_side) {
+                    (Color::White, CastleSide::King) => 0,
+                    (Color::White, CastleSide::Queen) => 1,
+                    (Color::Black, CastleSide::King) => 2,
+                    (Color::Black, CastleSide::Queen) => 3,
+                };
+                self.castling[index] = allowed;
+            } EditorMessage::SelectEnPassant(file) => {
+                self.en_passant = ALL_FILES.into_iter().find(|f| file_name(*f) == file);
+            } EditorMessage::ClearBoard => {
+                self.pieces = [None; 64];
+                self.castling = [false; 4];
+                self.en_passant = None;
+                self.status.clear();
+            } EditorMessage::InitialPosition => {
+                self.set_position(&Board::default());
+                self.status.clear();
+            } EditorMessage::EnterSolution => {
+                match self.validate() {
+                    Ok(board) => {
+                        self.start = Some(board);
+                        self.solution.clear();
+                        self.status = lang::tr(&self.lang, "editor_play_solution");
+                    } Err(key) => self.status = lang::tr(&self.lang, key),
+                }
+            } EditorMessage::BackToSetup => {
+                self.start = None;
+                self.solution.clear();
+                self.status.clear();
+            } EditorMessage::UndoMove => {
+                self.solution.pop();
+            } EditorMessage::ToggleSetupMove(setup_move) => {
+                self.setup_move = setup_move;
+            } EditorMessage::AddTheme(theme) => {
+                if theme.item != TacticalThemes::All && !self.themes.contains(&theme.item) {
+                    self.themes.push(theme.item);
+                }
+            } EditorMessage::RemoveTheme(theme) => {
+                self.themes.retain(|t| *t != theme);
+            } EditorMessage::ChangeRating(rating) => {
+                self.rating = rating;
+            } EditorMessage::SavePuzzle => {
+                self.status = match self.save() {
+                    Ok(puzzle_id) => lang::tr(&self.lang, "editor_saved") + &puzzle_id,
+                    Err(key) => lang::tr(&self.lang, key),
+                };
+            }
+        }
+        Task::none()
+    }
+
+    fn solution_text(&self) -> String {
+        let Some(mut board) = self.start else {
+            return String::new();
+        };
+        let mut moves = Vec::new();
+        for chess_move in &self.solution {
+            moves.push(config::coord_to_san(&board, chess_move.to_string(), &self.lang).unwrap_or_default());
+            board = board.make_move_new(*chess_move);
+        }
+        moves.join(" ")
+    }
+
+    fn palette(&self) -> Column<'_, EditorMessage> {
+        let mut palette = Column::new().spacing(5).align_x(Alignment::Center);
+        for color in [Color::White, Color::Black] {
+            let mut palette_row = Row::new().spacing(5).align_y(Alignment::Center);
+            for piece in PALETTE {
+                // Same letters the "Chess Alpha" font board uses
+                let mut text = match (color, piece) {
+                    (Color::White, Piece::Pawn) => "p", (Color::White, Piece::Rook) => "r",
+                    (Color::White, Piece::Knight) => "h", (Color::White, Piece::Bishop) => "b",
+                    (Color::White, Piece::Queen) => "q", (Color::White, Piece::King) => "k",
+                    (Color::Black, Piece::Pawn) => "o", (Color::Black, Piece::Rook) => "t",
+                    (Color::Black, Piece::Knight) => "j", (Color::Black, Piece::Bishop) => "n",
+                    (Color::Black, Piece::Queen) => "w", (Color::Black, Piece::King) => "l",
+                }.to_string();
+                if self.brush == Some((piece, color)) {
+                    text = text.to_uppercase();
+                }
+                // Pieces can be clicked to place them with clicks, or dragged to the board
+                palette_row = palette_row.push(
+                    iced_drop::droppable(
+                        Container::new(
+                            Text::new(text).font(config::CHESS_ALPHA).size(45).align_y(Alignment::Center).line_height(LineHeight::Absolute(45.into()))
+                        ).width(45).height(45).style(styles::container_style_paper)
+                    ).drag_center(true)
+                    .on_drop(move |point, rect| EditorMessage::DropNewPiece((piece, color), point, rect))
+                    .on_click(EditorMessage::SelectBrush(Some((piece, color))))
+                );
+            }
+            palette = palette.push(palette_row);
+        }
+        let remove_label = if self.brush.is_none() {
+            String::from("[") + &lang::tr(&self.lang, "editor_remove") + "]"
+        } else {
+            lang::tr(&self.lang, "editor_remove")
+        };
+        palette.push(Button::new(Text::new(remove_label)).padding(5).on_press(EditorMessage::SelectBrush(None)))
+    }
+
+    fn castling_row(&self, color: Color, label: &str, king_index: usize) -> Row<'_, EditorMessage> {
+        row![
+            Text::new(lang::tr(&self.lang, label)).width(60),
+            Checkbox::new(self.castling[king_index]).label("O-O").on_toggle(move |allowed| EditorMessage::ToggleCastling(color, CastleSide::King, allowed)).size(20),
+            Checkbox::new(self.castling[king_index + 1]).label("O-O-O").on_toggle(move |allowed| EditorMessage::ToggleCastling(color, CastleSide::Queen, allowed)).size(20),
+        ].spacing(10).align_y(Alignment::Center)
+    }
+}
+
+fn file_name(file: File) -> String {
+    String::from((b'a' + file.to_index() as u8) as char)
+}
+
+impl Tab for EditorTab {
+    type Message = Message;
+
+    fn title(&self) -> String {
+        lang::tr(&self.lang, "editor")
+    }
+
+    fn tab_label(&self) -> TabLabel {
+        TabLabel::Text(self.title())
+    }
+
+    fn content(&self) -> Element<'_, Message> {
+        let mut col_editor = if self.is_setting_up() {
+            let mut en_passant_files = vec![String::from("-")];
+            en_passant_files.extend(ALL_FILES.iter().map(|file| file_name(*file)));
+            let en_passant = self.en_passant.map_or(String::from("-"), file_name);
+            col![
+                self.palette(),
+                row![
+                    Button::new(Text::new(lang::tr(&self.lang, "editor_clear"))).padding(5).on_press(EditorMessage::ClearBoard),
+                    Button::new(Text::new(lang::tr(&self.lang, "editor_initial"))).padding(5).on_press(EditorMessage::InitialPosition),
+                ].spacing(10),
+                row![
+                    Text::new(lang::tr(&self.lang, "fen")),
+                    TextInput::new(&self.fen(), &self.fen_input).on_input(EditorMessage::ChangeFen).on_submit(EditorMessage::LoadFen),
+                    Button::new(Text::new(lang::tr(&self.lang, "load_fen"))).padding(5).on_press(EditorMessage::LoadFen),
+                ].spacing(5).align_y(Alignment::Center),
+                row![
+                    Text::new(lang::tr(&self.lang, "editor_side_to_move")),
+                    Radio::new(lang::tr(&self.lang, "white"), Color::White, Some(self.side_to_move), EditorMessage::SelectSide),
+                    Radio::new(lang::tr(&self.lang, "black"), Color::Black, Some(self.side_to_move), EditorMessage::SelectSide),
+                ].spacing(10).align_y(Alignment::Center),
+                Text::new(lang::tr(&self.lang, "editor_castling")),
+                self.castling_row(Color::White, "white", 0),
+                self.castling_row(Color::Black, "black", 2),
+                row![
+                    Text::new(lang::tr(&self.lang, "editor_en_passant")),
+                    PickList::new(en_passant_files, Some(en_passant), EditorMessage::SelectEnPassant)
+                        .style(styles::pick_list_style).menu_style(styles::menu_style),
+                ].spacing(5).align_y(Alignment::Center),
+                row![
+                    Button::new(Text::new(lang::tr(&self.lang, "editor_analyze"))).padding(5).on_press(EditorMessage::Analyze),
+                    Button::new(Text::new(lang::tr(&self.lang, "editor_enter_solution"))).padding(5).on_press(EditorMessage::EnterSolution),
+                ].spacing(10),
+            ].spacing(10).align_x(Alignment::Center)
+        } else {
+            let mut col_themes = Column::new().spacing(3).align_x(Alignment::Center);
+            for theme in &self.themes {
+                col_themes = col_themes.push(
+                    Button::new(Text::new(lang::tr(&self.lang, theme.get_tr_key()) + " x")).padding(3).on_press(EditorMessage::RemoveTheme(*theme))
+                );
+            }
+            let btn_undo = if self.solution.is_empty() {
+                Button::new(Text::new(lang::tr(&self.lang, "editor_undo"))).padding(5)
+            } else {
+                Button::new(Text::new(lang::tr(&self.lang, "editor_undo"))).padding(5).on_press(EditorMessage::UndoMove)
+            };
+            col![
+                row![
+                    Text::new(lang::tr(&self.lang, "editor_setup_move")),
+                    Checkbox::new(self.setup_move).on_toggle(EditorMessage::ToggleSetupMove).size(20),
+                ].spacing(5).align_y(Alignment::Center),
+                Text::new(lang::tr(&self.lang, "editor_solution")),
+                Text::new(self.solution_text()),
+                row![
+                    btn_undo,
+                    Button::new(Text::new(lang::tr(&self.lang, "editor_back_to_setup"))).padding(5).on_press(EditorMessage::BackToSetup),
+                ].spacing(10),
+                row![
+                    Text::new(lang::tr(&self.lang, "editor_themes")),
+                    PickList::new(
+                        PickListWrapper::get_themes(self.lang),
+                        None::<PickListWrapper<TacticalThemes>>,
+                        EditorMessage::AddTheme
+                    ).placeholder(lang::tr(&self.lang, "editor_add_theme")).style(styles::pick_list_style).menu_style(styles::menu_style),
+                ].spacing(5).align_y(Alignment::Center),
+                col_themes,
+                row![
+                    Text::new(lang::tr(&self.lang, "editor_rating")),
+                    TextInput::new(&self.rating, &self.rating).on_input(EditorMessage::ChangeRating).width(80),
+                ].spacing(5).align_y(Alignment::Center),
+                Button::new(Text::new(lang::tr(&self.lang, "editor_save"))).padding(5).on_press(EditorMessage::SavePuzzle),
+            ].spacing(10).align_x(Alignment::Center)
+        };
+        if !self.status.is_empty() {
+            col_editor = col_editor.push(Text::new(&self.status));
+        }
+
+        let content: Element<EditorMessage, Theme, iced::Renderer> = Container::new(Scrollable::new(col_editor.padding([0, 30])))
+            .align_x(alignment::Horizontal::Center).height(Length::Fill)
+            .into();
+
+        content.map(Message::Editor)
+    }
+}
@@ -3,22 +3,152 @@ use iced::stream;
 use iced::futures::sink::SinkExt;
 use iced::Subscription;
 
+use std::collections::VecDeque;
+use std::path::Path;
 use std::process::Stdio;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
 use tokio::sync::mpsc::{self, Receiver};
-use tokio::process::{Command, Child};
-use tokio::io::{BufReader, AsyncWriteExt, AsyncBufReadExt};
+use tokio::process::{Command, Child, ChildStdout};
+use tokio::io::{BufReader, Lines, AsyncWriteExt, AsyncBufReadExt};
 
 use tokio::time::timeout;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::Message;
+use crate::{Message, lang};
 
 pub const STOP_COMMAND: &str = "STOP";
 pub const EXIT_APP_COMMAND: &str = "EXIT";
+// Anything after this prefix goes straight to the engine's stdin (from the UCI console)
+pub const RAW_COMMAND_PREFIX: &str = "RAW ";
+
+// How many times in a row we bring the engine back after a crash before giving up.
+const MAX_RESTARTS: usize = 3;
+// An engine that keeps running this long after a restart gets the whole restart budget back.
+const RESTART_GRACE: Duration = Duration::from_secs(30);
+// Mate scores are converted to centipawns this big (minus the distance to mate),
+// so they can be compared with the regular evals.
+pub const MATE_SCORE: i32 = 100000;
+// The engine may stay quiet for a while on deep searches on a slow machine
+const SEARCH_TIMEOUT: u64 = 60000;
+const LOG_CAPACITY: usize = 1000;
+
+static ENGINE_LOG: Lazy<Mutex<VecDeque<LogEntry>>> = Lazy::new(|| {
+    Mutex::new(VecDeque::with_capacity(LOG_CAPACITY))
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogSource {
+    Gui,
+    Engine,
+    Stderr,
+    Supervisor,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub source: LogSource,
+    pub line: String,
+}
+
+impl LogEntry {
+    pub fn format(&self) -> String {
+        let prefix = match self.source {
+            LogSource::Gui => ">",
+            LogSource::Engine => "<",
+            LogSource::Stderr => "!",
+            LogSource::Supervisor => "*",
+        };
+        format!("{} {} {}", self.time.format("%H:%M:%S%.3f"), prefix, self.line)
+    }
+}
+
+pub fn raw_command(command: &str) -> String {
+    String::from(RAW_COMMAND_PREFIX) + command.trim()
+}
+
+pub fn log_line(source: LogSource, line: &str) {
+    if let Ok(mut log) = ENGINE_LOG.lock() {
+        if log.len() == LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(LogEntry { time: chrono::Local::now(), source, line: line.trim_end().to_string() });
+    }
+}
+
+pub fn engine_log() -> Vec<LogEntry> {
+    match ENGINE_LOG.lock() {
+        Ok(log) => log.iter().cloned().collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub fn clear_engine_log() {
+    if let Ok(mut log) = ENGINE_LOG.lock() {
+        log.clear();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineError {
+    NotFound(String),
+    Spawn(String),
+    Handshake,
+    Io(String),
+    Crashed(String),
+    GaveUp,
+}
+
+impl EngineError {
+    pub fn tr_key(&self) -> &'static str {
+        match self {
+            EngineError::NotFound(_) => "engine_error_not_found",
+            EngineError::Spawn(_) => "engine_error_spawn",
+            EngineError::Handshake => "engine_error_handshake",
+            EngineError::Io(_) => "engine_error_io",
+            EngineError::Crashed(_) => "engine_error_crashed",
+            EngineError::GaveUp => "engine_error_gave_up",
+        }
+    }
+
+    pub fn localized(&self, lang: &lang::Language) -> String {
+        match self {
+            EngineError::NotFound(details) | EngineError::Spawn(details) |
+            EngineError::Io(details) | EngineError::Crashed(details) => {
+                lang::tr(lang, self.tr_key()) + details
+            }
+            _ => lang::tr(lang, self.tr_key())
+        }
+    }
+}
+
+impl std::fmt::Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EngineError::NotFound(path) => write!(f, "engine not found: {path}"),
+            EngineError::Spawn(e) => write!(f, "error calling engine: {e}"),
+            EngineError::Handshake => write!(f, "engine took too long to start"),
+            EngineError::Io(e) => write!(f, "error communicating with engine: {e}"),
+            EngineError::Crashed(status) => write!(f, "engine process ended unexpectedly: {status}"),
+            EngineError::GaveUp => write!(f, "engine crashed too many times, giving up"),
+        }
+    }
+}
+
+pub type EngineOutput = Lines<BufReader<ChildStdout>>;
+
+// Result of a single search, with the score relative to the side to move
+#[derive(Debug, Clone, Default)]
+pub struct SearchResult {
+    pub best_move: Option<String>,
+    pub score: i32,
+    pub pv: Vec<String>,
+}
 
 pub enum EngineState {
     Start,
-    Thinking(Child, String, Receiver<String>),
+    Thinking(Child, EngineOutput, Receiver<String>),
     TurnedOff,
 }
 
@@ -35,6 +165,165 @@ pub struct Engine {
     pub position: String,
 }
 
+pub async fn send_command(child: &mut Child, command: &str) -> Result<(), EngineError> {
+    log_line(LogSource::Gui, command);
+    match child.stdin.as_mut() {
+        Some(stdin) => {
+            stdin.write_all((String::from(command) + "\n").as_bytes()).await.map_err(|e| EngineError::Io(e.to_string()))
+        } None => Err(EngineError::Io(String::from("stdin is closed")))
+    }
+}
+
+pub async fn wait_for(lines: &mut EngineOutput, token: &str) -> Result<(), EngineError> {
+    loop {
+        let read_timeout = timeout(Duration::from_millis(7000),
+            lines.next_line()
+        ).await;
+        match read_timeout {
+            Ok(Ok(Some(line))) => {
+                log_line(LogSource::Engine, &line);
+                if line.contains(token) {
+                    return Ok(());
+                }
+            }
+            Ok(Ok(None)) => return Err(EngineError::Crashed(String::from("output closed during startup"))),
+            Ok(Err(e)) => return Err(EngineError::Io(e.to_string())),
+            Err(_) => return Err(EngineError::Handshake),
+        }
+    }
+}
+
+async fn handshake(child: &mut Child, lines: &mut EngineOutput) -> Result<(), EngineError> {
+    send_command(child, "uci").await?;
+    wait_for(lines, "uciok").await?;
+    send_command(child, "ucinewgame").await?;
+    send_command(child, "isready").await?;
+    wait_for(lines, "readyok").await
+}
+
+// Starts the engine process and waits until it's ready to receive commands.
+pub async fn spawn_engine(engine_path: &str) -> Result<(Child, EngineOutput), EngineError> {
+    if !Path::new(engine_path).exists() {
+        return Err(EngineError::NotFound(String::from(engine_path)));
+    }
+    let mut cmd = Command::new(engine_path);
+    cmd.kill_on_drop(true).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    #[cfg(target_os = "windows")]
+    //"CREATE_NO_WINDOW" flag
+    // https://learn.microsoft.com/en-us/windows/win32/procthread/process-creation-flags
+    cmd.creation_flags(0x08000000);
+    let mut child = cmd.spawn().map_err(|e| EngineError::Spawn(e.to_string()))?;
+    let stdout = child.stdout.take().ok_or(EngineError::Io(String::from("stdout is closed")))?;
+    // Nobody waits on stderr, so it's just copied to the log as it comes.
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(async move {
+            let mut err_lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = err_lines.next_line().await {
+                log_line(LogSource::Stderr, &line);
+            }
+        });
+    }
+    let mut lines = BufReader::new(stdout).lines();
+    if let Err(e) = handshake(&mut child, &mut lines).await {
+        quit_engine(&mut child).await;
+        return Err(e);
+    }
+    Ok((child, lines))
+}
+
+async fn start_engine(engine_path: &str, position: &str, search_up_to: &str) -> Result<(Child, EngineOutput), EngineError> {
+    let (mut child, lines) = spawn_engine(engine_path).await?;
+    let analysis = async {
+        send_command(&mut child, "setoption name UCI_AnalyseMode value true").await?;
+        send_command(&mut child, &(String::from("position fen ") + position)).await?;
+        send_command(&mut child, &(String::from("go ") + search_up_to)).await
+    }.await;
+    if let Err(e) = analysis {
+        quit_engine(&mut child).await;
+        return Err(e);
+    }
+    Ok((child, lines))
+}
+
+pub async fn quit_engine(child: &mut Child) {
+    // The engine may already be gone, so failing to write here is fine.
+    let _ = send_command(child, "stop").await;
+    let _ = send_command(child, "quit").await;
+    let terminate_timeout = timeout(Duration::from_millis(1000),
+        child.wait()
+    ).await;
+    if let Err(e) = terminate_timeout {
+        eprintln!("Error: {e}");
+        eprintln!("Engine didn't quit, killing the process now... ");
+        let kill_result = timeout(Duration::from_millis(500),
+            child.kill()
+        ).await;
+        if let Err(e) = kill_result {
+            eprintln!("Error killing the engine process: {e}");
+        }
+    }
+}
+
+pub fn score_to_string(score: i32) -> String {
+    if score > MATE_SCORE / 2 {
+        format!("#{}", MATE_SCORE - score)
+    } else if score < -MATE_SCORE / 2 {
+        format!("#-{}", MATE_SCORE + score)
+    } else {
+        format!("{:.2}", score as f32 / 100.)
+    }
+}
+
+// Runs a fixed depth search, when searchmove is used the search is restricted to that move.
+pub async fn search(child: &mut Child, lines: &mut EngineOutput, fen: &str, depth: u32, searchmove: Option<&str>) -> Result<SearchResult, EngineError> {
+    send_command(child, &(String::from("position fen ") + fen)).await?;
+    let go = match searchmove {
+        Some(searchmove) => format!("go depth {depth} searchmoves {searchmove}"),
+        None => format!("go depth {depth}"),
+    };
+    send_command(child, &go).await?;
+    let mut result = SearchResult::default();
+    loop {
+        let read_timeout = timeout(Duration::from_millis(SEARCH_TIMEOUT),
+            lines.next_line()
+        ).await;
+        let line = match read_timeout {
+            Ok(Ok(Some(line))) => line,
+            Ok(Ok(None)) => return Err(EngineError::Crashed(String::from("output closed during the search"))),
+            Ok(Err(e)) => return Err(EngineError::Io(e.to_string())),
+            Err(_) => return Err(EngineError::Io(String::from("search timed out"))),
+        };
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.first() == Some(&"bestmove") {
+            result.best_move = tokens.get(1).filter(|m| **m != "(none)").map(|m| m.to_string());
+            return Ok(result);
+        }
+        // Bounds are just partial results of a search that failed high/low
+        if tokens.contains(&"lowerbound") || tokens.contains(&"upperbound") {
+            continue;
+        }
+        if let Some(index) = tokens.iter().position(|&x| x == "score") {
+            let value = tokens.get(index + 2).and_then(|v| v.parse::<i32>().ok());
+            match (tokens.get(index + 1), value) {
+                (Some(&"cp"), Some(cp)) => result.score = cp,
+                (Some(&"mate"), Some(mate)) if mate > 0 => result.score = MATE_SCORE - mate,
+                (Some(&"mate"), Some(mate)) => result.score = -MATE_SCORE - mate,
+                _ => (),
+            }
+            if let Some(pv_index) = tokens.iter().position(|&x| x == "pv") {
+                result.pv = tokens[(pv_index + 1)..].iter().map(|m| m.to_string()).collect();
+            }
+        }
+    }
+}
+
+fn exit_status(child: &mut Child) -> String {
+    match child.try_wait() {
+        Ok(Some(status)) => status.to_string(),
+        _ => String::from("no output"),
+    }
+}
+
 impl Engine {
 
     pub fn new(path: Option<String>, limit: String, position: String) -> Self {
@@ -59,6 +348,14 @@ impl Engine {
             async move |mut output| {
 
                 let mut state = EngineState::Start;
+                // When a new position is sent while the engine is still searching, the "stop"
+                // makes it report a bestmove for the old position, which we need to skip.
+                let mut searching = false;
+                let mut stale_bestmoves = 0;
+                // The last position sent, so a restarted engine picks up where the old one died.
+                let mut position = engine.position.clone();
+                let mut restarts = 0;
+                let mut started_at = Instant::now();
 
                 loop {
                     match &mut state {
@@ -66,150 +363,139 @@ impl Engine {
                         EngineState::Start => {
 
                             let (sender, receiver) = mpsc::channel(100);
-                            let mut cmd = Command::new(engine.engine_path.clone());
-                            cmd.kill_on_drop(true).stdin(Stdio::piped()).stdout(Stdio::piped());
-                            #[cfg(target_os = "windows")]
-                            //"CREATE_NO_WINDOW" flag
-                            // https://learn.microsoft.com/en-us/windows/win32/procthread/process-creation-flags
-                            cmd.creation_flags(0x08000000);
-                            let mut child = cmd.spawn().expect("Error calling engine");
-                            let pos = String::from("position fen ") + &engine.position + &String::from("\n");
-                            let limit = String::from("go ") + &engine.search_up_to + "\n";
-                            let mut uciok = false;
-                            let mut readyok = false;
-                            child.stdin.as_mut().unwrap().write_all(b"uci\n").await.expect("Error communicating with engine");
-                            let mut reader = BufReader::new(child.stdout.as_mut().unwrap());
-                            let mut buf_str = String::new();
-                            loop {
-                                let uciok_timeout = timeout(Duration::from_millis(7000),
-                                    reader.read_line(&mut buf_str)
-                                ).await;
-                                if uciok_timeout.is_err() {
-                                    break;
-                                } else if buf_str.contains("uciok") {
-                                    uciok = true;
-                                    break;
-                                }
-                            }
-                            if uciok {
-                                child.stdin.as_mut().unwrap().write_all(b"ucinewgame\n").await.expect("Error communicating with engine");
-                                child.stdin.as_mut().unwrap().write_all(b"isready\n").await.expect("Error communicating with engine");
-                                buf_str = String::new();
-                                loop {
-                                    let readyok_timeout = timeout(Duration::from_millis(7000),
-                                        reader.read_line(&mut buf_str)
-                                    ).await;
-                                    if readyok_timeout.is_err() {
-                                        break;
-                                    } else if buf_str.contains("readyok") {
-                                        readyok = true;
-                                        break;
-                                    }
-                                }
-                                if readyok {
-                                    child.stdin.as_mut().unwrap().write_all(b"setoption name UCI_AnalyseMode value true\n").await.expect("Error communicating with engine");
-                                    child.stdin.as_mut().unwrap().write_all(pos.as_bytes()).await.expect("Error communicating with engine");
-                                    child.stdin.as_mut().unwrap().write_all(limit.as_bytes()).await.expect("Error communicating with engine");
-
+                            match start_engine(&engine.engine_path, &position, &engine.search_up_to).await {
+                                Ok((child, lines)) => {
+                                    searching = true;
+                                    stale_bestmoves = 0;
+                                    started_at = Instant::now();
                                     output.send(Message::EngineReady(sender)).await.expect("Error on the mpsc channel in the engine subscription");
-                                    state = EngineState::Thinking(child, engine.search_up_to.to_string(), receiver);
-                                    continue;
+                                    state = EngineState::Thinking(child, lines, receiver);
+                                } Err(e) => {
+                                    eprintln!("Error starting the engine: {e}");
+                                    log_line(LogSource::Supervisor, &e.to_string());
+                                    // Only the first start gives up right away, a failed respawn is one more crash
+                                    if restarts == 0 {
+                                        output.send(Message::EngineError(e)).await.expect("Error on the mpsc channel in the engine subscription");
+                                        output.send(Message::EngineStopped(false)).await.expect("Error on the mpsc channel in the engine subscription");
+                                        state = EngineState::TurnedOff;
+                                    } else {
+                                        restarts += 1;
+                                        if restarts > MAX_RESTARTS {
+                                            output.send(Message::EngineError(EngineError::GaveUp)).await.expect("Error on the mpsc channel in the engine subscription");
+                                            output.send(Message::EngineStopped(false)).await.expect("Error on the mpsc channel in the engine subscription");
+                                            state = EngineState::TurnedOff;
+                                        } else {
+                                            log_line(LogSource::Supervisor, "restarting the engine");
+                                            output.send(Message::EngineError(e)).await.expect("Error on the mpsc channel in the engine subscription");
+                                        }
+                                    }
                                 }
                             }
-                            eprintln!("Engine took too long to start, aborting...");
-                            child.stdin.as_mut().unwrap().write_all(b"stop\n").await.expect("Error communicating with engine");
-                            child.stdin.as_mut().unwrap().write_all(b"quit\n").await.expect("Error communicating with engine");
-                            let terminate_timeout = timeout(Duration::from_millis(1000),
-                                child.wait()
-                            ).await;
-                            if let Err(e) = terminate_timeout {
-                                eprintln!("Error: {e}");
-                                eprintln!("Engine didn't quit, killing the process now... ");
-                                let kill_result = timeout(Duration::from_millis(500),
-                                    child.kill()
-                                ).await;
-                                if let Err(e) = kill_result {
-                                    eprintln!("Error killing the engine process: {e}");
-                                }
+                        } EngineState::Thinking(child, lines, receiver) => {
+                            if restarts > 0 && started_at.elapsed() > RESTART_GRACE {
+                                restarts = 0;
                             }
-                            output.send(Message::EngineStopped(false)).await.expect("Error on the mpsc channel in the engine subscription");
-                            state = EngineState::TurnedOff;
-                        } EngineState::Thinking(child, search_up_to, receiver) => {
+                            let mut failure = None;
                             let msg = receiver.try_recv();
                             if let Ok(msg) = msg {
-                                if msg == STOP_COMMAND || msg == EXIT_APP_COMMAND {
-                                    child.stdin.as_mut().unwrap().write_all(b"stop\n").await.expect("Error communicating with engine");
-                                    child.stdin.as_mut().unwrap().write_all(b"quit\n").await.expect("Error communicating with engine");
-                                    let terminate_timeout = timeout(Duration::from_millis(1000),
-                                        child.wait()
-                                    ).await;
-                                    if let Err(e) = terminate_timeout {
-                                        eprintln!("Error: {e}");
-                                        eprintln!("Engine didn't quit, killing the process now... ");
-                                        let kill_result = timeout(Duration::from_millis(500),
-                                            child.kill()
-                                        ).await;
-                                        if let Err(e) = kill_result {
-                                            eprintln!("Error killing the engine process: {e}");
-                                        }
-                                    }
+                                // A "quit" typed in the console would look like a crash, so it just turns the engine off
+                                if msg == STOP_COMMAND || msg == EXIT_APP_COMMAND || msg == raw_command("quit") {
+                                    quit_engine(child).await;
                                     output.send(Message::EngineStopped(msg == EXIT_APP_COMMAND)).await.expect("Error on the mpsc channel in the engine subscription");
                                     state = EngineState::TurnedOff;
                                     continue;
+                                } else if let Some(command) = msg.strip_prefix(RAW_COMMAND_PREFIX) {
+                                    if let Err(e) = send_command(child, command).await {
+                                        failure = Some(e);
+                                    }
+                                    if command.split_whitespace().next() == Some("go") {
+                                        searching = true;
+                                    }
                                 } else {
-                                    let pos = String::from("position fen ") + &msg + &String::from("\n");
-                                    let limit = String::from("go ") + &search_up_to + "\n";
-                                    child.stdin.as_mut().unwrap().write_all(b"stop\n").await.expect("Error communicating with engine");
-                                    //child.stdin.as_mut().unwrap().write_all(b"setoption name UCI_AnalyseMode value true\n").await.expect("Error communicating with engine");
-                                    //child.stdin.as_mut().unwrap().write_all(b"ucinewgame\n").await.expect("Error communicating with engine");
-                                    child.stdin.as_mut().unwrap().write_all(pos.as_bytes()).await.expect("Error communicating with engine");
-                                    child.stdin.as_mut().unwrap().write_all(limit.as_bytes()).await.expect("Error communicating with engine");
+                                    position = msg;
+                                    let new_search = async {
+                                        send_command(child, "stop").await?;
+                                        send_command(child, &(String::from("position fen ") + &position)).await?;
+                                        send_command(child, &(String::from("go ") + &engine.search_up_to)).await
+                                    }.await;
+                                    if let Err(e) = new_search {
+                                        failure = Some(e);
+                                    }
+                                    if searching {
+                                        stale_bestmoves += 1;
+                                    }
+                                    searching = true;
                                 }
                             }
-                            let mut buf_str = String::new();
                             let mut eval = None;
                             let mut best_move = None;
+                            let mut pv = None;
 
-                            if let Some(out) = child.stdout.as_mut() {
-                                let mut reader = BufReader::new(out);
-                                loop {
-                                    let read_timeout = timeout(Duration::from_millis(50),
-                                        reader.read_line(&mut buf_str)
-                                    ).await;
-                                    if let Ok(Ok(read_result)) = read_timeout {
-                                        if read_result == 0 {
-                                            break;
+                            while failure.is_none() {
+                                let read_timeout = timeout(Duration::from_millis(50),
+                                    lines.next_line()
+                                ).await;
+                                let line = match read_timeout {
+                                    Ok(Ok(Some(line))) => line,
+                                    Ok(Ok(None)) => {
+                                        failure = Some(EngineError::Crashed(exit_status(child)));
+                                        break;
+                                    } Ok(Err(e)) => {
+                                        failure = Some(EngineError::Io(e.to_string()));
+                                        break;
+                                    } Err(_) => break,
+                                };
+                                log_line(LogSource::Engine, &line);
+                                let vector: Vec<&str> = line.split_whitespace().collect::<Vec<&str>>();
+                                if vector.first() == Some(&"bestmove") {
+                                    searching = false;
+                                    if stale_bestmoves > 0 {
+                                        stale_bestmoves -= 1;
+                                    } else if let Some(engine_move) = vector.get(1) {
+                                        output.send(Message::EngineBestMove(engine_move.to_string())).await.expect("Error on the mpsc channel in the engine subscription");
+                                    }
+                                }
+                                if let Some(index) = vector.iter().position(|&x| x == "score") {
+                                    let eval_num = vector.get(index+2).and_then(|e| e.parse::<f32>().ok());
+                                    if let Some(e) = eval_num {
+                                        if vector.get(index+1) == Some(&"mate") {
+                                            eval = Some(String::from("Mate in ") + &e.to_string());
+                                        } else {
+                                            eval = Some(format!("{:.2}",(e / 100.)));
                                         }
-                                        let vector: Vec<&str> = buf_str.split_whitespace().collect::<Vec<&str>>();
-                                        if let Some(index) = vector.iter().position(|&x| x == "score") {
-                                            let eval_num = vector.get(index+2).unwrap().parse::<f32>().ok();
-                                            if let Some(e) = eval_num {
-                                                if vector.get(index+1).unwrap() == &"mate" {
-                                                    eval = Some(String::from("Mate in ") + &e.to_string());
-                                                } else {
-                                                    eval = Some(format!("{:.2}",(e / 100.)));
-                                                }
-                                            }
-                                            for i in (index + 3)..vector.len() {
-                                                if let Some(token) = vector.get(i) {
-                                                    if token == &"pv" {
-                                                        // I thought we could just unwrap, but at least Koivisto sometimes
-                                                        // returns lines with nothing in the pv
-                                                        if let Some(best) = vector.get(i+1) {
-                                                            best_move = Some(best.to_string());
-                                                            break;
-                                                        }
-                                                    }
+                                    }
+                                    for i in (index + 3)..vector.len() {
+                                        if let Some(token) = vector.get(i) {
+                                            if token == &"pv" {
+                                                // I thought we could just unwrap, but at least Koivisto sometimes
+                                                // returns lines with nothing in the pv
+                                                if let Some(best) = vector.get(i+1) {
+                                                    best_move = Some(best.to_string());
+                                                    pv = Some(vector[(i+1)..].iter().map(|m| m.to_string()).collect::<Vec<String>>());
+                                                    break;
                                                 }
                                             }
                                         }
-                                        buf_str.clear();
-                                    } else {
-                                        break;
                                     }
                                 }
                             }
-                            output.send(Message::UpdateEval((eval, best_move))).await.expect("Error on the mpsc channel in the engine subscription");
+                            output.send(Message::UpdateEval((eval, best_move, pv))).await.expect("Error on the mpsc channel in the engine subscription");
+
+                            if let Some(error) = failure {
+                                eprintln!("Engine error: {error}");
+                                log_line(LogSource::Supervisor, &error.to_string());
+                                quit_engine(child).await;
+                                restarts += 1;
+                                if restarts > MAX_RESTARTS {
+                                    output.send(Message::EngineError(EngineError::GaveUp)).await.expect("Error on the mpsc channel in the engine subscription");
+                                    output.send(Message::EngineStopped(false)).await.expect("Error on the mpsc channel in the engine subscription");
+                                    state = EngineState::TurnedOff;
+                                } else {
+                                    log_line(LogSource::Supervisor, "restarting the engine");
+                                    output.send(Message::EngineError(error)).await.expect("Error on the mpsc channel in the engine subscription");
+                                    state = EngineState::Start;
+                                }
+                            }
                         } EngineState::TurnedOff => {
                             tokio::time::sleep(std::time::Duration::from_millis(10)).await;
                         }
@@ -219,4 +505,142 @@ impl Engine {
         )
     }
 }
- 
\ No newline at end of file
+ 
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use iced::futures::StreamExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    // The log is shared, and writing a script while another test forks can make it "busy"
+    static LOCK: Mutex<()> = Mutex::new(());
+    const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    const GOOD_ENGINE: &str = r#"echo "fake engine starting" >&2
+while read -r line; do
+    case "$line" in
+        uci) echo "id name Fake"; echo "uciok" ;;
+        isready) echo "readyok" ;;
+        go*) echo "info depth 1 score cp 35 pv e2e4 e7e5"; echo "bestmove e2e4" ;;
+        quit) exit 0 ;;
+    esac
+done"#;
+
+    const CRASHING_ENGINE: &str = r#"while read -r line; do
+    case "$line" in
+        uci) echo "uciok" ;;
+        isready) echo "readyok" ;;
+        go*) exit 1 ;;
+    esac
+done"#;
+
+    const MUTE_ENGINE: &str = r#"while read -r line; do :; done"#;
+
+    // Starts once, and then fails to start again
+    const NO_RESPAWN_ENGINE: &str = r#"[ -e "$0.started" ] && exit 1
+touch "$0.started"
+while read -r line; do
+    case "$line" in
+        uci) echo "uciok" ;;
+        isready) echo "readyok" ;;
+        go*) exit 1 ;;
+    esac
+done"#;
+
+    fn fake_engine(name: &str, body: &str) -> String {
+        let path = std::env::temp_dir().join(format!("ocp-fake-engine-{}-{}.sh", name, std::process::id()));
+        std::fs::write(&path, String::from("#!/bin/sh\n") + body + "\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+    }
+
+    // The errors sent by the engine subscription until it turns itself off
+    fn engine_errors(path: String) -> Vec<EngineError> {
+        let engine = Engine::new(Some(path), String::from("depth 1"), String::from(START_FEN));
+        block_on(async {
+            let errors = Engine::engine_stream(&engine)
+                .take_while(|msg| std::future::ready(!matches!(msg, Message::EngineStopped(_))))
+                .filter_map(|msg| std::future::ready(match msg {
+                    Message::EngineError(e) => Some(e),
+                    _ => None,
+                }))
+                .collect::<Vec<EngineError>>();
+            timeout(Duration::from_secs(60), errors).await.unwrap()
+        })
+    }
+
+    #[test]
+    fn answers_and_logs_stderr() {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let path = fake_engine("good", GOOD_ENGINE);
+        let engine = Engine::new(Some(path), String::from("depth 1"), String::from(START_FEN));
+        let best_move = block_on(async {
+            let mut best_moves = std::pin::pin!(Engine::engine_stream(&engine)
+                .filter_map(|msg| std::future::ready(match msg {
+                    Message::EngineBestMove(best_move) => Some(best_move),
+                    _ => None,
+                })));
+            timeout(Duration::from_secs(10), best_moves.next()).await.unwrap()
+        });
+        assert_eq!(best_move.as_deref(), Some("e2e4"));
+        // stderr is read by its own task, so it may need a moment
+        let logged = (0..100).any(|_| {
+            let logged = engine_log().iter().any(|entry| entry.source == LogSource::Stderr && entry.line == "fake engine starting");
+            if !logged {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            logged
+        });
+        assert!(logged);
+    }
+
+    #[test]
+    fn restarts_then_gives_up() {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let errors = engine_errors(fake_engine("crashing", CRASHING_ENGINE));
+        assert_eq!(errors.len(), MAX_RESTARTS + 1);
+        assert!(errors[..MAX_RESTARTS].iter().all(|e| matches!(e, EngineError::Crashed(_) | EngineError::Io(_))));
+        assert_eq!(errors.last(), Some(&EngineError::GaveUp));
+    }
+
+    #[test]
+    fn failed_respawns_are_retried() {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let path = fake_engine("no-respawn", NO_RESPAWN_ENGINE);
+        let marker = path.clone() + ".started";
+        let _ = std::fs::remove_file(&marker);
+        let errors = engine_errors(path);
+        let _ = std::fs::remove_file(&marker);
+        assert_eq!(errors.len(), MAX_RESTARTS + 1);
+        assert_eq!(errors.last(), Some(&EngineError::GaveUp));
+    }
+
+    #[test]
+    fn missing_engine() {
+        let result = block_on(start_engine("/nonexistent/engine", START_FEN, "depth 1"));
+        assert!(matches!(result, Err(EngineError::NotFound(path)) if path == "/nonexistent/engine"));
+    }
+
+    #[test]
+    fn engine_without_uciok() {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let path = fake_engine("mute", MUTE_ENGINE);
+        let result = block_on(start_engine(&path, START_FEN, "depth 1"));
+        assert!(matches!(result, Err(EngineError::Handshake)));
+    }
+
+    #[test]
+    fn log_is_capped() {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        for i in 0..(LOG_CAPACITY + 10) {
+            log_line(LogSource::Gui, &format!("line {i}"));
+        }
+        let log = engine_log();
+        assert_eq!(log.len(), LOG_CAPACITY);
+        assert_eq!(log.last().map(|entry| entry.line.as_str()), Some(format!("line {}", LOG_CAPACITY + 9).as_str()));
+    }
+}
@@ -1,17 +1,102 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
 use std::str::FromStr;
 use lopdf::dictionary;
-use lopdf::{Document, Object, Stream};
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
 use lopdf::content::{Content, Operation};
 use chrono;
-use chess::{Board, ChessMove, Color, Piece, Square};
+use chess::{Board, BoardStatus, ChessMove, Color, MoveGen, Piece, Square};
 
-use crate::{config, PuzzleTab, lang};
+use offline_chess_puzzles::session;
+
+use crate::{config, db, diagram, overlay, styles, PuzzleTab, lang, verify::VerifyIssue};
+
+// Space left around the page contents, in points
+const PDF_MARGIN: i32 = 36;
+// Room above each diagram for the "White to move..." label
+const DIAGRAM_LABEL_HEIGHT: i32 = 15;
+
+// Diagrams per page that make a full grid
+pub const DIAGRAMS_PER_PAGE: [usize; 7] = [1, 2, 4, 6, 8, 9, 12];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum PageSize {
+    #[default]
+    A4,
+    Letter,
+}
+
+impl PageSize {
+    pub const ALL: [PageSize; 2] = [PageSize::A4, PageSize::Letter];
+
+    // Width and height in points, in portrait
+    fn dimensions(&self) -> (i32, i32) {
+        match self {
+            PageSize::A4 => (595, 842),
+            PageSize::Letter => (612, 792),
+        }
+    }
+}
+
+impl std::fmt::Display for PageSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PageSize::A4 => "A4",
+                PageSize::Letter => "Letter",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PdfLayout {
+    pub diagrams_per_page: usize,
+    // Size of a board square, in points (the board is 8 times that)
+    pub square_size: i32,
+    pub page_size: PageSize,
+    pub landscape: bool,
+    pub answer_key: bool,
+    pub piece_theme: styles::PieceTheme,
+    pub board_theme: styles::BoardTheme,
+}
+
+impl PdfLayout {
+    fn page_dimensions(&self) -> (i32, i32) {
+        let (width, height) = self.page_size.dimensions();
+        if self.landscape {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    // Columns and rows of diagrams
+    fn grid(&self) -> (i32, i32) {
+        let (columns, rows) = match self.diagrams_per_page {
+            1 => (1, 1),
+            2 => (1, 2),
+            4 => (2, 2),
+            8 => (2, 4),
+            9 => (3, 3),
+            12 => (3, 4),
+            _ => (2, 3),
+        };
+        if self.landscape {
+            (rows, columns)
+        } else {
+            (columns, rows)
+        }
+    }
+}
 
 // This is basically all copy-pasted from the lopdf example, I left the comments
-// as they might be useful.
-pub fn to_pdf(puzzles: &Vec<config::Puzzle>, number_of_pages: i32, lang: &lang::Language, path: String) {
-    let font_data = std::fs::read("font/Alpha.ttf").unwrap();
+// as they might be useful. Returns how many puzzles were written.
+pub fn to_pdf(puzzles: &Vec<config::Puzzle>, number_of_pages: i32, layout: &PdfLayout, lang: &lang::Language, path: String) -> Result<usize, String> {
+    let lang = &pdf_language(lang);
+    let font_data = std::fs::read("font/Alpha.ttf").map_err(|_| String::from("font/Alpha.ttf"))?;
     // Load the font data from a file
 
     // Create a stream object for the font data
@@ -64,46 +149,61 @@ pub fn to_pdf(puzzles: &Vec<config::Puzzle>, number_of_pages: i32, lang: &lang::
         // basefont is postscript name of font for type1 font.
         // See PDF reference document for more details
         "BaseFont" => "Arial",
+        // The accented letters of the translations are in the Latin-1 part of it, see win_ansi()
+        "Encoding" => "WinAnsiEncoding",
     });
     // font dictionaries need to be added into resource dictionaries
     // in order to be used.
     // Resource dictionaries can contain more than just fonts,
     // but normally just contains fonts
     // Only one resource dictionary is allowed per page tree root
-    let resources_id = doc.add_object(dictionary! {
+    let (page_width, page_height) = layout.page_dimensions();
+    let (columns, rows) = layout.grid();
+    let per_page = (columns * rows) as usize;
+    let num_of_puzzles_to_print = puzzles.len().min(per_page * number_of_pages.max(0) as usize);
+    let num_of_pages = num_of_puzzles_to_print.div_ceil(per_page);
+
+    // Each diagram gets a cell of the grid, the board is shrunk if the chosen size doesn't fit in it
+    let cell_width = (page_width - 2 * PDF_MARGIN) / columns;
+    let cell_height = (page_height - 2 * PDF_MARGIN) / rows;
+    let square_size = layout.square_size.min(cell_width / 8).min((cell_height - DIAGRAM_LABEL_HEIGHT) / 8).max(1);
+
+    // The font is still used for the "paper" theme, and if the theme's SVGs can't be read
+    let xobjects = if layout.piece_theme == styles::PieceTheme::FontAlpha {
+        None
+    } else {
+        piece_xobjects(&mut doc, &layout.piece_theme, square_size)
+    };
+    let style = if xobjects.is_some() {
+        let palette = layout.board_theme.palette();
+        DiagramStyle::Themed { light: palette.light_square, dark: palette.dark_square }
+    } else {
+        DiagramStyle::Font
+    };
+
+    let mut resources = dictionary! {
         // fonts are actually triplely nested dictionaries. Fun!
         "Font" => dictionary! {
             "Chess Alpha" => font_id,
             "Regular" => regular_font_id,
         },
-    });
-
-    let num_of_puzzles_to_print;
-    let num_of_pages;
-    if (6 * number_of_pages) as usize > puzzles.len() {
-        num_of_puzzles_to_print = puzzles.len();
-        num_of_pages = (puzzles.len() as f32 / 6.0).ceil() as usize;
-    } else {
-        num_of_puzzles_to_print = (6 * number_of_pages) as usize;
-        num_of_pages = number_of_pages as usize;
     };
+    if let Some(xobjects) = xobjects {
+        resources.set("XObject", xobjects);
+    }
+    let resources_id = doc.add_object(resources);
 
-    //let number_of_pages: i64 = 100;//(puzzles.len() / 6).try_into().unwrap();
     let mut page_ids = vec![];
     let mut puzzle_index = 0;
     for _ in 0..num_of_pages {
         let mut ops: Vec<Operation> = vec![];
-        let mut pos_x = 750;
-        let mut pos_y = 75;
-        for i in 0..6 {
-            if puzzle_index == puzzles.len() { break };
-            ops.append(&mut gen_diagram_operations(puzzle_index + 1, &puzzles[puzzle_index], pos_x, pos_y, lang));
-            if i % 2 == 0 {
-                pos_y = 325;
-            } else {
-                pos_y = 75;
-                pos_x -= 250;
-            };
+        for i in 0..per_page as i32 {
+            if puzzle_index == num_of_puzzles_to_print { break };
+            let cell_x = PDF_MARGIN + (i % columns) * cell_width;
+            let cell_top = page_height - PDF_MARGIN - (i / columns) * cell_height;
+            let pos_x = cell_x + (cell_width - 8 * square_size) / 2;
+            let pos_y = cell_top - DIAGRAM_LABEL_HEIGHT - square_size;
+            ops.append(&mut gen_diagram_operations(puzzle_index + 1, &puzzles[puzzle_index], pos_x, pos_y, square_size, &style, lang));
             puzzle_index += 1;
         }
 
@@ -113,108 +213,38 @@ pub fn to_pdf(puzzles: &Vec<config::Puzzle>, number_of_pages: i32, lang: &lang::
         // Reference the PDF reference for more details on these operators and operands.
         // Note, the operators and operands are specified in a reverse order than they
         // actually appear in the PDF file itself.
-        let content = Content {
-            operations: ops,
-        };
-
-        // Streams are a dictionary followed by a sequence of bytes. What that sequence of bytes
-        // represents depends on context
-        // The stream dictionary is set internally to lopdf and normally doesn't
-        // need to be manually nanipulated. It contains keys such as
-        // Length, Filter, DecodeParams, etc
-        //
-        // content is a stream of encoded content data.
-        let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
-
-        // Page is a dictionary that represents one page of a PDF file.
-        // It has a type, parent and contents
-        //let page_id = doc.add_object(dictionary! {
-        page_ids.push(doc.add_object(dictionary! {
-            "Type" => "Page",
-            "Parent" => pages_id,
-            "Contents" => content_id,
-        }).into());
+        page_ids.push(add_page(&mut doc, pages_id, ops));
     }
 
-    let mut ops: Vec<Operation> = vec![];
-    let mut pos_x = 800;
-    let pos_y = 75;
-    for (puzzle_number, puzzle) in puzzles.iter().enumerate().take(num_of_puzzles_to_print) {
-        // need to start by making the 1st move in the list, because it's only then that
-        // the puzzle starts.
-        let mut board = Board::from_str(&puzzle.fen).unwrap();
-        let mut puzzle_moves: VecDeque<&str> = puzzles[puzzle_number].moves.split_whitespace().collect();
-        let movement = ChessMove::new(
-            Square::from_str(&String::from(&puzzle_moves[0][..2])).unwrap(),
-            Square::from_str(&String::from(&puzzle_moves[0][2..4])).unwrap(), PuzzleTab::check_promotion(puzzle_moves[0]));
-        board = board.make_move_new(movement);
-
-        let mut solution = (puzzle_number + 1).to_string() + ") ";
-        // Remove the opponent's first move, it's not part of the solution.
-        puzzle_moves.pop_front();
-
-        let mut half_move_number = 1;
-        let mut move_label = 1;
-        if board.side_to_move() == Color::Black {
-            solution.push_str(" 1. ... ");
-            half_move_number = 2;
-            move_label = 2;
-        }
-        for chess_move in puzzle_moves {
-            if half_move_number % 2 == 0 {
-                solution.push(' ');
-                solution.push_str(&config::coord_to_san(&board, String::from(chess_move), lang).unwrap());
-            } else {
-                solution.push(' ');
-                solution.push_str(&move_label.to_string());
-                solution.push_str(". ");
-                solution.push_str(&config::coord_to_san(&board, String::from(chess_move), lang).unwrap());
-                move_label += 1;
+    if layout.answer_key {
+        // Roughly how many characters of the regular font fit in a line
+        let max_chars = ((page_width - 2 * PDF_MARGIN) / 6) as usize;
+        let mut ops = text_operations(lang::tr(lang, "pdf_answer_key"), 14, PDF_MARGIN, page_height - PDF_MARGIN);
+        let mut pos_y = page_height - PDF_MARGIN - 30;
+        for (puzzle_number, puzzle) in puzzles.iter().enumerate().take(num_of_puzzles_to_print) {
+            let solution = (puzzle_number + 1).to_string() + ") " + &solution_san(puzzle, lang);
+            let mut lines: Vec<(String, i32, i32)> = wrap_text(&solution, max_chars).into_iter().map(|line| (line, 12, PDF_MARGIN)).collect();
+            let details = puzzle_details(puzzle, lang);
+            if !details.is_empty() {
+                lines.extend(wrap_text(&details, max_chars).into_iter().map(|line| (line, 10, PDF_MARGIN + 15)));
             }
-            half_move_number += 1;
-            // Apply move, so we have the updated board to generate the SAN for the next move.
-            let movement = ChessMove::new(
-                Square::from_str(&String::from(&chess_move[..2])).unwrap(),
-                Square::from_str(&String::from(&chess_move[2..4])).unwrap(), PuzzleTab::check_promotion(chess_move));
-            board = board.make_move_new(movement);
-        }
-        ops.append(&mut vec![
-                Operation::new("BT", vec![]),
-                Operation::new("Tf", vec!["Regular".into(), 12.into()]),
-                Operation::new("rg", vec![0.into(),0.into(),0.into()]),
-                Operation::new("Td", vec![pos_y.into(), pos_x.into()]),
-                Operation::new("Tj", vec![Object::string_literal(solution)]),
-                Operation::new("ET", vec![]),
-        ]);
-        pos_x -= 18;
-
-        // We need a page break
-        if pos_x < 18 {
-            pos_x = 800;
-
-            let content = Content {
-                operations: ops,
-            };
-
-            let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
-            page_ids.push(doc.add_object(dictionary! {
-                "Type" => "Page",
-                "Parent" => pages_id,
-                "Contents" => content_id,
-            }).into());
-            ops = vec![];
+            // Keep the whole entry on the same page
+            if pos_y - 16 * (lines.len() as i32) < PDF_MARGIN {
+                page_ids.push(add_page(&mut doc, pages_id, ops));
+                ops = vec![];
+                pos_y = page_height - PDF_MARGIN;
+            }
+            for (line, size, pos_x) in lines {
+                ops.append(&mut text_operations(line, size, pos_x, pos_y));
+                pos_y -= 16;
+            }
+            pos_y -= 6;
         }
+        page_ids.push(add_page(&mut doc, pages_id, ops));
+    }
+    if page_ids.is_empty() {
+        page_ids.push(add_page(&mut doc, pages_id, vec![]));
     }
-    let content = Content {
-        operations: ops,
-    };
-
-    let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
-    page_ids.push(doc.add_object(dictionary! {
-        "Type" => "Page",
-        "Parent" => pages_id,
-        "Contents" => content_id,
-    }).into());
 
     // Again, pages is the root of the page tree. The ID was already created
     // at the top of the page, since we needed it to assign to the parent element of the page
@@ -235,7 +265,7 @@ pub fn to_pdf(puzzles: &Vec<config::Puzzle>, number_of_pages: i32, lang: &lang::
         "Resources" => resources_id,
         // a rectangle that defines the boundaries of the physical or digital media. This is the
         // "Page Size"
-        "MediaBox" => vec![0.into(), 0.into(), 600.into(), 850.into()],
+        "MediaBox" => vec![0.into(), 0.into(), page_width.into(), page_height.into()],
     };
 
     // using insert() here, instead of add_object() since the id is already known.
@@ -254,36 +284,213 @@ pub fn to_pdf(puzzles: &Vec<config::Puzzle>, number_of_pages: i32, lang: &lang::
     doc.compress();
 
     // Store file in current working directory.
-    let _ = doc.save(path);
+    doc.save(&path).map_err(|_| path.clone())?;
+    Ok(num_of_puzzles_to_print)
+}
+
+// Streams are a dictionary followed by a sequence of bytes. What that sequence of bytes
+// represents depends on context
+// The stream dictionary is set internally to lopdf and normally doesn't
+// need to be manually nanipulated. It contains keys such as
+// Length, Filter, DecodeParams, etc
+//
+// Page is a dictionary that represents one page of a PDF file.
+// It has a type, parent and contents
+fn add_page(doc: &mut Document, pages_id: ObjectId, operations: Vec<Operation>) -> Object {
+    let content = Content {
+        operations,
+    };
+    let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+    doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "Contents" => content_id,
+    }).into()
+}
+
+fn text_operations(text: String, size: i32, pos_x: i32, pos_y: i32) -> Vec<Operation> {
+    vec![
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec!["Regular".into(), size.into()]),
+        Operation::new("rg", vec![0.into(),0.into(),0.into()]),
+        Operation::new("Td", vec![pos_x.into(), pos_y.into()]),
+        Operation::new("Tj", vec![Object::string_literal(win_ansi(&text))]),
+        Operation::new("ET", vec![]),
+    ]
+}
+
+// WinAnsiEncoding has the same codes as Latin-1 for its characters (the rest are shown as "?")
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars().map(|c| u8::try_from(c as u32).unwrap_or(b'?')).collect()
+}
+
+// The texts of the PDF in the language, unless it's written with characters that the
+// regular font's encoding doesn't have (like Chinese), then they're in English.
+fn pdf_language(lang: &lang::Language) -> lang::Language {
+    const KEYS: [&str; 12] = ["pdf_answer_key", "pdf_black_to_move", "pdf_white_to_move", "pdf_black_to_play",
+        "pdf_white_to_play", "rating", "themes", "king", "queen", "rook", "bishop", "knight"];
+    if KEYS.iter().all(|key| lang::tr(lang, key).chars().all(|c| (c as u32) < 0x100)) {
+        *lang
+    } else {
+        lang::Language::English
+    }
+}
+
+// The solver's moves in SAN, with the move numbers
+fn solution_san(puzzle: &config::Puzzle, lang: &lang::Language) -> String {
+    // need to start by making the 1st move in the list, because it's only then that
+    // the puzzle starts.
+    let mut board = Board::from_str(&puzzle.fen).unwrap();
+    let mut puzzle_moves: VecDeque<&str> = puzzle.moves.split_whitespace().collect();
+    if puzzle_moves[0] != config::NULL_MOVE {
+        let movement = ChessMove::new(
+            Square::from_str(&String::from(&puzzle_moves[0][..2])).unwrap(),
+            Square::from_str(&String::from(&puzzle_moves[0][2..4])).unwrap(), PuzzleTab::check_promotion(puzzle_moves[0]));
+        board = board.make_move_new(movement);
+    }
+    // Remove the opponent's first move, it's not part of the solution.
+    puzzle_moves.pop_front();
+
+    let mut solution = String::new();
+    let mut half_move_number = 1;
+    let mut move_label = 1;
+    if board.side_to_move() == Color::Black {
+        solution.push_str("1. ...");
+        half_move_number = 2;
+        move_label = 2;
+    }
+    for chess_move in puzzle_moves {
+        if !solution.is_empty() {
+            solution.push(' ');
+        }
+        if half_move_number % 2 != 0 {
+            solution.push_str(&move_label.to_string());
+            solution.push_str(". ");
+            move_label += 1;
+        }
+        solution.push_str(&config::coord_to_san(&board, String::from(chess_move), lang).unwrap());
+        half_move_number += 1;
+        // Apply move, so we have the updated board to generate the SAN for the next move.
+        let movement = ChessMove::new(
+            Square::from_str(&String::from(&chess_move[..2])).unwrap(),
+            Square::from_str(&String::from(&chess_move[2..4])).unwrap(), PuzzleTab::check_promotion(chess_move));
+        board = board.make_move_new(movement);
+    }
+    solution
+}
+
+// Rating and themes, leaving out what imported puzzles don't have
+fn puzzle_details(puzzle: &config::Puzzle, lang: &lang::Language) -> String {
+    let mut details = Vec::new();
+    if puzzle.rating > 0 {
+        details.push(lang::tr(lang, "rating") + &puzzle.rating.to_string());
+    }
+    if !puzzle.themes.is_empty() {
+        details.push(lang::tr(lang, "themes") + " " + &puzzle.themes);
+    }
+    details.join("   ")
+}
+
+// Breaks the text at the spaces, so the lines have at most max_chars (unless a word is longer than that)
+fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// How the boards are drawn: as text with the Chess Alpha font, or with the colours of
+// the board theme and the pieces of an SVG theme (rasterized, see piece_xobjects())
+enum DiagramStyle {
+    Font,
+    Themed { light: iced::Color, dark: iced::Color },
 }
 
-fn gen_diagram_operations(index: usize, puzzle: &config::Puzzle, start_x:i32, start_y:i32, lang: &lang::Language) -> Vec<Operation> {
+// The pieces of the SVG theme as images, named like their files ("wP", "bK"...), to be used
+// by all the diagrams. They're rendered for around 300 DPI at the size of the squares.
+fn piece_xobjects(doc: &mut Document, piece_theme: &styles::PieceTheme, square_size: i32) -> Option<Dictionary> {
+    let pixels = (square_size as u32 * 300 / 72).max(32);
+    let mut xobjects = Dictionary::new();
+    for color in ["w", "b"] {
+        for piece in ["P", "N", "B", "R", "Q", "K"] {
+            let name = String::from(color) + piece;
+            let svg_path = String::from(config::PIECES_DIRECTORY) + &piece_theme.to_string() + "/" + &name + ".svg";
+            let svg_data = std::fs::read(svg_path).ok()?;
+            let tree = resvg::usvg::Tree::from_data(&svg_data, &resvg::usvg::Options::default()).ok()?;
+            let mut pixmap = resvg::tiny_skia::Pixmap::new(pixels, pixels)?;
+            let scale = pixels as f32 / tree.size().width().max(tree.size().height());
+            resvg::render(&tree, resvg::tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+
+            // The pixmap is premultiplied RGBA, while the PDF wants the colours and the
+            // transparency (soft mask) in separate images
+            let mut rgb = Vec::with_capacity((pixels * pixels * 3) as usize);
+            let mut alpha = Vec::with_capacity((pixels * pixels) as usize);
+            for pixel in pixmap.pixels() {
+                let pixel = pixel.demultiply();
+                rgb.extend_from_slice(&[pixel.red(), pixel.green(), pixel.blue()]);
+                alpha.push(pixel.alpha());
+            }
+            let smask_id = doc.add_object(Stream::new(dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => pixels as i64,
+                "Height" => pixels as i64,
+                "ColorSpace" => "DeviceGray",
+                "BitsPerComponent" => 8,
+            }, alpha));
+            let image_id = doc.add_object(Stream::new(dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => pixels as i64,
+                "Height" => pixels as i64,
+                "ColorSpace" => "DeviceRGB",
+                "BitsPerComponent" => 8,
+                "SMask" => smask_id,
+            }, rgb));
+            xobjects.set(name, image_id);
+        }
+    }
+    Some(xobjects)
+}
+
+// The diagram with its label, with the top rank of the board starting at pos_x, pos_y
+// (the baseline of the text, or the bottom of the squares)
+fn gen_diagram_operations(index: usize, puzzle: &config::Puzzle, pos_x: i32, pos_y: i32, square_size: i32, style: &DiagramStyle, lang: &lang::Language) -> Vec<Operation> {
     let mut board = Board::from_str(&puzzle.fen).unwrap();
     let puzzle_moves: Vec<&str> = puzzle.moves.split_whitespace().collect();
-    let movement = ChessMove::new(
-        Square::from_str(&String::from(&puzzle_moves[0][..2])).unwrap(),
-        Square::from_str(&String::from(&puzzle_moves[0][2..4])).unwrap(), PuzzleTab::check_promotion(puzzle_moves[0]));
 
-    let (is_white, last_move) = if board.side_to_move() == Color::White {
-        (false, index.to_string() + &lang::tr(lang, "pdf_black_to_move") + &config::coord_to_san(&board, String::from(&puzzle_moves[0][0..4]), lang).unwrap())
+    let (is_white, last_move) = if puzzle_moves[0] == config::NULL_MOVE {
+        // No opponent's move to show, the solver is the one to move in the FEN
+        if board.side_to_move() == Color::White {
+            (true, index.to_string() + &lang::tr(lang, "pdf_white_to_play"))
+        } else {
+            (false, index.to_string() + &lang::tr(lang, "pdf_black_to_play"))
+        }
     } else {
-        (true, index.to_string() + &lang::tr(lang, "pdf_white_to_move") + &config::coord_to_san(&board, String::from(&puzzle_moves[0][0..4]), lang).unwrap())
+        let movement = ChessMove::new(
+            Square::from_str(&String::from(&puzzle_moves[0][..2])).unwrap(),
+            Square::from_str(&String::from(&puzzle_moves[0][2..4])).unwrap(), PuzzleTab::check_promotion(puzzle_moves[0]));
+        let label = if board.side_to_move() == Color::White {
+            (false, index.to_string() + &lang::tr(lang, "pdf_black_to_move") + &config::coord_to_san(&board, String::from(&puzzle_moves[0][0..4]), lang).unwrap())
+        } else {
+            (true, index.to_string() + &lang::tr(lang, "pdf_white_to_move") + &config::coord_to_san(&board, String::from(&puzzle_moves[0][0..4]), lang).unwrap())
+        };
+        board = board.make_move_new(movement);
+        label
     };
-    board = board.make_move_new(movement);
-
-    let mut ops = vec![
-            Operation::new("BT", vec![]),
-            Operation::new("Tf", vec!["Regular".into(), 10.into()]),
-            Operation::new("rg", vec![0.into(),0.into(),0.into()]),
-            Operation::new("Td", vec![start_y.into(), (start_x + 30).into()]),
-            Operation::new("Tj", vec![Object::string_literal(last_move)]),
-            Operation::new("ET", vec![]),
 
-            Operation::new("BT", vec![]),
-            Operation::new("Tf", vec!["Chess Alpha".into(), 25.into()]),
-            Operation::new("rg", vec![0.into(),0.into(),0.into()]),
-            Operation::new("Td", vec![start_y.into(), start_x.into()]),
-            ];
+    let mut ops = text_operations(last_move, 10, pos_x, pos_y + square_size + 5);
 
     let ranks;
     let files;
@@ -294,12 +501,27 @@ fn gen_diagram_operations(index: usize, puzzle: &config::Puzzle, start_x:i32, st
         ranks = (0..8).collect::<Vec<i32>>();
         files = (0..8).rev().collect::<Vec<i32>>();
     };
+    match style {
+        DiagramStyle::Font => ops.append(&mut font_board_operations(&board, &ranks, &files, pos_x, pos_y, square_size)),
+        DiagramStyle::Themed { light, dark } => ops.append(&mut themed_board_operations(&board, &ranks, &files, pos_x, pos_y, square_size, *light, *dark)),
+    }
+    ops
+}
+
+// The position as text with the Chess Alpha font, one rank per line
+fn font_board_operations(board: &Board, ranks: &[i32], files: &[i32], pos_x: i32, pos_y: i32, square_size: i32) -> Vec<Operation> {
+    let mut ops = vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["Chess Alpha".into(), square_size.into()]),
+            Operation::new("rg", vec![0.into(),0.into(),0.into()]),
+            Operation::new("Td", vec![pos_x.into(), pos_y.into()]),
+            ];
     for rank in ranks {
         let mut rank_string = String::new();
-        for file in &files {
+        for file in files {
             let mut new_piece;
             let light_square = (rank + file) % 2 != 0;
-            let square = chess::Square::make_square(chess::Rank::from_index(rank as usize),chess::File::from_index(*file as usize));
+            let square = chess::Square::make_square(chess::Rank::from_index(*rank as usize),chess::File::from_index(*file as usize));
             let (piece, color) =
                 (board.piece_on(square),
                 board.color_on(square));
@@ -338,16 +560,55 @@ fn gen_diagram_operations(index: usize, puzzle: &config::Puzzle, start_x:i32, st
             rank_string.push(new_piece);
         }
         ops.push(Operation::new("Tj", vec![Object::string_literal(rank_string)]));
-        ops.push(Operation::new("Td", vec![0.into(), Object::Integer(-25)]));
+        ops.push(Operation::new("Td", vec![0.into(), (-square_size).into()]));
     }
     ops.push(Operation::new("ET", vec![]));
     ops
 }
 
-pub fn to_pgn(puzzles: &Vec<config::Puzzle>, lang: &lang::Language, path: String) {
+// The squares as filled rectangles in the board theme colours, and the pieces as the images
+// added by piece_xobjects(), scaled to the square size (with the "cm" matrix) and drawn with "Do"
+fn themed_board_operations(board: &Board, ranks: &[i32], files: &[i32], pos_x: i32, pos_y: i32, square_size: i32, light: iced::Color, dark: iced::Color) -> Vec<Operation> {
+    let mut ops = vec![];
+    for (row, rank) in ranks.iter().enumerate() {
+        for (column, file) in files.iter().enumerate() {
+            let x = pos_x + column as i32 * square_size;
+            let y = pos_y - row as i32 * square_size;
+            let square_color = if (rank + file) % 2 != 0 { light } else { dark };
+            ops.push(Operation::new("rg", vec![square_color.r.into(), square_color.g.into(), square_color.b.into()]));
+            ops.push(Operation::new("re", vec![x.into(), y.into(), square_size.into(), square_size.into()]));
+            ops.push(Operation::new("f", vec![]));
+
+            let square = chess::Square::make_square(chess::Rank::from_index(*rank as usize),chess::File::from_index(*file as usize));
+            if let (Some(piece), Some(color)) = (board.piece_on(square), board.color_on(square)) {
+                let name = String::from(if color == Color::White { "w" } else { "b" }) + &piece.to_string(Color::White);
+                ops.push(Operation::new("q", vec![]));
+                ops.push(Operation::new("cm", vec![square_size.into(), 0.into(), 0.into(), square_size.into(), x.into(), y.into()]));
+                ops.push(Operation::new("Do", vec![Object::Name(name.into_bytes())]));
+                ops.push(Operation::new("Q", vec![]));
+            }
+        }
+    }
+    // A thin border, or the light squares on the edges would blend with the paper
+    ops.push(Operation::new("RG", vec![0.into(),0.into(),0.into()]));
+    ops.push(Operation::new("w", vec![0.5.into()]));
+    ops.push(Operation::new("re", vec![pos_x.into(), (pos_y - 7 * square_size).into(), (8 * square_size).into(), (8 * square_size).into()]));
+    ops.push(Operation::new("S", vec![]));
+    ops
+}
+
+// Returns how many puzzles were written.
+pub fn to_pgn(puzzles: &Vec<config::Puzzle>, lang: &lang::Language, path: String) -> Result<usize, String> {
     let mut pgn_content = String::new();
+    let all_marks = db::get_all_marks();
 
     for (_puzzle_index, puzzle) in puzzles.iter().enumerate() {
+        // The arrows and circles drawn on a position go in a comment after the move that leads to it
+        let marks_comment = |board: &Board| {
+            all_marks.get(&(puzzle.puzzle_id.clone(), overlay::position_key(board)))
+                .map(|comment| format!(" {{{}}}", comment))
+                .unwrap_or_default()
+        };
         // Start with a board from the FEN
         let mut board = Board::from_str(&puzzle.fen).unwrap();
 
@@ -378,29 +639,48 @@ pub fn to_pgn(puzzles: &Vec<config::Puzzle>, lang: &lang::Language, path: String
 
         // Process the first move (opponent's move that sets up the puzzle)
         let first_move = puzzle_moves[0];
-        let movement = ChessMove::new(
-            Square::from_str(&String::from(&first_move[..2])).unwrap(),
-            Square::from_str(&String::from(&first_move[2..4])).unwrap(), 
-            PuzzleTab::check_promotion(first_move)
-        );
+        let mut comment;
+        if first_move == config::NULL_MOVE {
+            // Imported positions have no such move, the solution starts right away
+            comment = marks_comment(&board);
+            pgn_content.push_str(comment.trim_start());
+            if !is_white_to_move {
+                if !comment.is_empty() {
+                    pgn_content.push(' ');
+                }
+                pgn_content.push_str(&format!("{}...", move_number));
+                comment.clear();
+            }
+        } else {
+            let movement = ChessMove::new(
+                Square::from_str(&String::from(&first_move[..2])).unwrap(),
+                Square::from_str(&String::from(&first_move[2..4])).unwrap(), 
+                PuzzleTab::check_promotion(first_move)
+            );
 
-        let san_move = config::coord_to_san(&board, String::from(first_move), lang).unwrap();
+            let san_move = config::coord_to_san(&board, String::from(first_move), lang).unwrap();
 
-        if is_white_to_move {
-            pgn_content.push_str(&format!("{}. {}", move_number, san_move));
-        } else {
-            pgn_content.push_str(&format!("{}... {}", move_number, san_move));
-            move_number += 1;
-        }
+            if is_white_to_move {
+                pgn_content.push_str(&format!("{}. {}", move_number, san_move));
+            } else {
+                pgn_content.push_str(&format!("{}... {}", move_number, san_move));
+                move_number += 1;
+            }
 
-        // Apply the move to the board
-        board = board.make_move_new(movement);
-        is_white_to_move = !is_white_to_move;
+            // Apply the move to the board
+            board = board.make_move_new(movement);
+            is_white_to_move = !is_white_to_move;
+            comment = marks_comment(&board);
+            pgn_content.push_str(&comment);
+        }
 
         // Process the rest of the moves (the actual puzzle solution)
         for chess_move in puzzle_moves.iter().skip(1) {
             if is_white_to_move {
                 pgn_content.push_str(&format!(" {}. ", move_number));
+            } else if !comment.is_empty() {
+                // After a comment the number of Black's move is written again
+                pgn_content.push_str(&format!(" {}... ", move_number));
             } else {
                 pgn_content.push_str(" ");
             }
@@ -415,6 +695,8 @@ pub fn to_pgn(puzzles: &Vec<config::Puzzle>, lang: &lang::Language, path: String
                 PuzzleTab::check_promotion(chess_move)
             );
             board = board.make_move_new(movement);
+            comment = marks_comment(&board);
+            pgn_content.push_str(&comment);
 
             if !is_white_to_move {
                 move_number += 1;
@@ -427,6 +709,192 @@ pub fn to_pgn(puzzles: &Vec<config::Puzzle>, lang: &lang::Language, path: String
     }
 
     // Write to file
-    std::fs::write(path, pgn_content).expect("Unable to write PGN file");
+    std::fs::write(&path, pgn_content).map_err(|_| path.clone())?;
+    Ok(puzzles.len())
 }
 
+// A text file that Anki can import as notes (Front, Back and the themes as tags), and
+// next to it a "<name>.media" folder with the diagrams, whose files go in Anki's
+// collection.media folder. Returns how many notes were written.
+pub async fn to_anki(puzzles: Vec<config::Puzzle>, lang: lang::Language, path: String, piece_theme: styles::PieceTheme, board_theme: styles::BoardTheme) -> Result<usize, String> {
+    let renderer = diagram::DiagramRenderer::new(&piece_theme, &board_theme)?;
+    let path = Path::new(&path).with_extension("txt");
+    let media_dir = path.with_extension("media");
+    std::fs::create_dir_all(&media_dir).map_err(|_| media_dir.display().to_string())?;
+
+    // The header lines tell Anki how to read the file, so there's nothing to set when importing
+    let mut notes = String::from("#separator:tab\n#html:true\n#tags column:3\n");
+    let mut written = 0;
+    for puzzle in &puzzles {
+        let Some((board, _)) = diagram::puzzle_position(puzzle) else {
+            continue;
+        };
+        // The media files of all decks share the same folder, so the names must be unique
+        let image_name = String::from("ocp_") + &puzzle.puzzle_id + ".png";
+        if renderer.save_puzzle(&media_dir.join(&image_name).display().to_string(), puzzle).is_err() {
+            continue;
+        }
+        let side_to_move = if board.side_to_move() == Color::White {
+            lang::tr(&lang, "white_to_move")
+        } else {
+            lang::tr(&lang, "black_to_move")
+        };
+        let front = format!("<img src=\"{}\"><br>{}", image_name, side_to_move);
+        let mut back = vec![solution_san(puzzle, &lang)];
+        if puzzle.rating > 0 {
+            back.push(lang::tr(&lang, "rating") + &puzzle.rating.to_string());
+        }
+        if !puzzle.themes.is_empty() {
+            back.push(lang::tr(&lang, "themes") + " " + &puzzle.themes);
+        }
+        // Only the lichess puzzles have a game, and a page on the site
+        if !puzzle.game_url.is_empty() {
+            let link = String::from("https://lichess.org/training/") + &puzzle.puzzle_id;
+            back.push(format!("<a href=\"{}\">{}</a>", link, link));
+        }
+        notes.push_str(&format!("{}\t{}\t{}\n", front, back.join("<br>"), puzzle.themes));
+        written += 1;
+    }
+    std::fs::write(&path, notes).map_err(|_| path.display().to_string())?;
+    Ok(written)
+}
+
+// What the HTML page needs of each puzzle, see export_template.html
+#[derive(Serialize)]
+struct HtmlPuzzle {
+    // The position after the opponent's move, which is the one shown
+    fen: String,
+    last_move: Option<String>,
+    // Starting with the solver's move
+    solution: Vec<String>,
+    // For each of the solver's moves, the ones to accept: the solution's and any mate,
+    // like verify_and_make_move() does. They're found here so the page doesn't need a move generator.
+    accepted: Vec<Vec<String>>,
+    rating: i32,
+    themes: String,
+}
+
+fn html_puzzle(puzzle: &config::Puzzle) -> Option<HtmlPuzzle> {
+    let (mut board, _) = diagram::puzzle_position(puzzle)?;
+    let fen = board.to_string();
+    let moves: Vec<&str> = puzzle.moves.split_whitespace().collect();
+    let last_move = moves.first().filter(|setup_move| **setup_move != config::NULL_MOVE).map(|setup_move| String::from(*setup_move));
+    let solution: Vec<String> = moves.iter().skip(1).map(|chess_move| String::from(*chess_move)).collect();
+    let mut accepted = Vec::new();
+    for (ply, uci_move) in solution.iter().enumerate() {
+        let chess_move = session::uci_to_move(&board, uci_move)?;
+        if ply % 2 == 0 {
+            let mut moves = vec![uci_move.clone()];
+            for mate in MoveGen::new_legal(&board).filter(|legal_move| board.make_move_new(*legal_move).status() == BoardStatus::Checkmate) {
+                if mate != chess_move {
+                    moves.push(mate.to_string());
+                }
+            }
+            accepted.push(moves);
+        }
+        board = board.make_move_new(chess_move);
+    }
+    if solution.is_empty() {
+        return None;
+    }
+    Some(HtmlPuzzle { fen, last_move, solution, accepted, rating: puzzle.rating, themes: puzzle.themes.clone() })
+}
+
+// A single page that can be solved in any browser, offline. The puzzles, pieces and
+// texts are put in the template as JSON. Returns how many puzzles it has.
+pub fn to_html(puzzles: &[config::Puzzle], lang: &lang::Language, path: String, piece_theme: &styles::PieceTheme, board_theme: &styles::BoardTheme) -> Result<usize, String> {
+    let pieces = diagram::piece_images(piece_theme)?;
+    let html_puzzles: Vec<HtmlPuzzle> = puzzles.iter().filter_map(html_puzzle).collect();
+    let palette = board_theme.palette();
+    let hex = |color: iced::Color| {
+        let [r, g, b, _] = color.into_rgba8();
+        format!("#{r:02x}{g:02x}{b:02x}")
+    };
+    let colors = HashMap::from([
+        ("light", hex(palette.light_square)),
+        ("dark", hex(palette.dark_square)),
+        ("selected_light", hex(styles::SELECTED_LIGHT_SQUARE)),
+        ("selected_dark", hex(styles::SELECTED_DARK_SQUARE)),
+    ]);
+    let texts: HashMap<&str, String> = ["white_to_move", "black_to_move", "correct_move", "correct_puzzle", "wrong_move_white_play",
+        "wrong_move_black_play", "all_puzzles_done", "previous", "next", "hint", "redo", "promotion_piece", "rating", "themes"]
+        .iter().map(|key| (*key, lang::tr(lang, key))).collect();
+
+    // "</" would end the script element early if some text had "</script>"
+    let json = |value: serde_json::Result<String>| value.map(|json| json.replace("</", "<\\/")).map_err(|_| path.clone());
+    let html = include_str!("export_template.html")
+        .replace("/*PUZZLES*/", &json(serde_json::to_string(&html_puzzles))?)
+        .replace("/*PIECES*/", &json(serde_json::to_string(&pieces))?)
+        .replace("/*COLORS*/", &json(serde_json::to_string(&colors))?)
+        .replace("/*TEXTS*/", &json(serde_json::to_string(&texts))?);
+    std::fs::write(&path, html).map_err(|_| path.clone())?;
+    Ok(html_puzzles.len())
+}
+
+pub fn game_to_pgn(start: &Board, moves: &[ChessMove], result: &str, lang: &lang::Language, path: String) -> Result<(), String> {
+    let mut pgn_content = String::new();
+    let mut board = *start;
+    let fen = crate::san_correct_ep(board.to_string());
+
+    pgn_content.push_str("[Event \"Game vs engine\"]\n");
+    pgn_content.push_str("[Site \"Offline Chess Puzzles\"]\n");
+    pgn_content.push_str(&format!("[Date \"{}\"]\n", chrono::Local::now().format("%Y.%m.%d")));
+    pgn_content.push_str(&format!("[White \"{}\"]\n", if board.side_to_move() == Color::White { "Player" } else { "Engine" }));
+    pgn_content.push_str(&format!("[Black \"{}\"]\n", if board.side_to_move() == Color::Black { "Player" } else { "Engine" }));
+    pgn_content.push_str(&format!("[Result \"{}\"]\n", result));
+    pgn_content.push_str(&format!("[FEN \"{}\"]\n", fen));
+    pgn_content.push_str("[SetUp \"1\"]\n\n");
+
+    // The move number is the last field of the FEN
+    let mut move_number = fen.split_whitespace().last().and_then(|n| n.parse::<usize>().ok()).unwrap_or(1);
+    for (index, chess_move) in moves.iter().enumerate() {
+        let coords = chess_move.to_string();
+        let san_move = config::coord_to_san(&board, coords.clone(), lang).ok_or(coords)?;
+        if board.side_to_move() == Color::White {
+            if index > 0 {
+                pgn_content.push(' ');
+            }
+            pgn_content.push_str(&format!("{}. {}", move_number, san_move));
+        } else {
+            if index == 0 {
+                pgn_content.push_str(&format!("{}... {}", move_number, san_move));
+            } else {
+                pgn_content.push_str(&format!(" {}", san_move));
+            }
+            move_number += 1;
+        }
+        board = board.make_move_new(*chess_move);
+    }
+    if !moves.is_empty() {
+        pgn_content.push(' ');
+    }
+    pgn_content.push_str(result);
+    pgn_content.push_str("\n\n");
+
+    std::fs::write(&path, pgn_content).map_err(|_| path.clone())
+}
+
+// With the same columns as the lichess database, so the file can be used as puzzle_db_location.
+// Returns how many puzzles were written.
+pub fn to_csv(puzzles: &[config::Puzzle], path: String) -> Result<usize, String> {
+    // The header is written by hand so an empty file can still be read as a puzzle database
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_path(&path).map_err(|_| path.clone())?;
+    writer.write_record(["PuzzleId", "FEN", "Moves", "Rating", "RatingDeviation", "Popularity", "NbPlays", "Themes", "GameUrl", "OpeningTags"]).map_err(|_| path.clone())?;
+    for puzzle in puzzles {
+        writer.serialize(puzzle).map_err(|_| path.clone())?;
+    }
+    writer.flush().map_err(|_| path.clone())?;
+    Ok(puzzles.len())
+}
+
+// Returns how many issues were written.
+pub fn verify_report_to_csv(report: &[VerifyIssue], path: String) -> Result<usize, String> {
+    // The header is written by hand so an empty report still has it
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_path(&path).map_err(|_| path.clone())?;
+    writer.write_record(["PuzzleId", "Issue", "FEN", "Ply", "SolutionMove", "SolutionEval", "EngineMove", "EngineEval"]).map_err(|_| path.clone())?;
+    for issue in report {
+        writer.serialize(issue).map_err(|_| path.clone())?;
+    }
+    writer.flush().map_err(|_| path.clone())?;
+    Ok(report.len())
+}
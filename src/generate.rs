@@ -0,0 +1,207 @@
+use iced::futures::Stream;
+use iced::futures::channel::mpsc;
+use iced::stream;
+use iced::futures::sink::SinkExt;
+use iced::Subscription;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use chess::{Board, BoardStatus, ChessMove, Color};
+use tokio::process::Child;
+
+use offline_chess_puzzles::session;
+
+use crate::{Message, config, db, lang, pgn, san_correct_ep};
+use crate::eval::{self, EngineError, EngineOutput, SearchResult, MATE_SCORE};
+use crate::pgn::PgnGame;
+
+// How much a move needs to lose (in centipawns) to count as a mistake
+const SWING_THRESHOLD: i32 = 200;
+// From how much advantage on we consider the position won
+const WINNING_THRESHOLD: i32 = 200;
+// Longest solution taken from the engine line (counting the replies)
+const MAX_SOLUTION_PLIES: usize = 7;
+
+#[derive(Debug, Clone)]
+pub enum GenerateError {
+    Pgn(String),
+    Engine(EngineError),
+    Database,
+}
+
+impl GenerateError {
+    pub fn localized(&self, lang: &lang::Language) -> String {
+        match self {
+            GenerateError::Pgn(path) => lang::tr(lang, "error_reading_pgn") + path,
+            GenerateError::Engine(e) => e.localized(lang),
+            GenerateError::Database => lang::tr(lang, "error_saving_my_puzzles") + &db::database_url(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct GenerateJob {
+    pub id: usize,
+    pub engine_path: String,
+    pub depth: u32,
+    pub pgn_path: String,
+    // Only this player's mistakes are used, all of them if it's empty
+    pub player: String,
+}
+
+impl GenerateJob {
+    pub fn run(self) -> Subscription<Message> {
+        Subscription::run_with(
+            self,
+            GenerateJob::generate_stream,
+        )
+    }
+
+    fn generate_stream(job: &GenerateJob) -> impl Stream<Item = Message> {
+        let job = job.clone();
+        stream::channel(100,
+            async move |mut output| {
+                let result = job.generate_all(&mut output).await;
+                let _ = output.send(Message::GenerateFinished(result)).await;
+            }
+        )
+    }
+
+    async fn generate_all(&self, output: &mut mpsc::Sender<Message>) -> Result<usize, GenerateError> {
+        let text = std::fs::read_to_string(&self.pgn_path).map_err(|_| GenerateError::Pgn(self.pgn_path.clone()))?;
+        let games = pgn::parse_pgn(&text);
+        let source = Path::new(&self.pgn_path).file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        let (mut child, mut lines) = eval::spawn_engine(&self.engine_path).await.map_err(GenerateError::Engine)?;
+        let mut added = 0;
+        for (index, game) in games.iter().enumerate() {
+            match self.game_puzzles(&mut child, &mut lines, game).await {
+                // Saved after each game, so the work isn't lost if the job is cancelled
+                Ok(puzzles) => match db::save_my_puzzles(&puzzles, &source) {
+                    Some(saved) => added += saved,
+                    None => {
+                        eval::quit_engine(&mut child).await;
+                        return Err(GenerateError::Database);
+                    }
+                },
+                Err(e) => {
+                    eval::quit_engine(&mut child).await;
+                    return Err(GenerateError::Engine(e));
+                }
+            }
+            let _ = output.send(Message::GenerateProgress(index + 1, games.len(), added)).await;
+        }
+        eval::quit_engine(&mut child).await;
+        Ok(added)
+    }
+
+    // None means the player didn't play this game
+    fn player_side(&self, game: &PgnGame) -> Option<Option<Color>> {
+        if self.player.is_empty() {
+            Some(None)
+        } else if game.header("White").is_some_and(|white| white.eq_ignore_ascii_case(&self.player)) {
+            Some(Some(Color::White))
+        } else if game.header("Black").is_some_and(|black| black.eq_ignore_ascii_case(&self.player)) {
+            Some(Some(Color::Black))
+        } else {
+            None
+        }
+    }
+
+    async fn game_puzzles(&self, child: &mut Child, lines: &mut EngineOutput, game: &PgnGame) -> Result<Vec<config::Puzzle>, EngineError> {
+        let mut puzzles = Vec::new();
+        let (Some(side), Some((start, moves))) = (self.player_side(game), game.chess_moves()) else {
+            return Ok(puzzles);
+        };
+        let mut boards = vec![start];
+        for chess_move in &moves {
+            boards.push(boards[boards.len() - 1].make_move_new(*chess_move));
+        }
+        eval::send_command(child, "ucinewgame").await?;
+        eval::send_command(child, "isready").await?;
+        eval::wait_for(lines, "readyok").await?;
+        let mut results = Vec::with_capacity(boards.len());
+        for board in &boards {
+            let result = match board.status() {
+                BoardStatus::Checkmate => SearchResult { score: -MATE_SCORE, ..Default::default() },
+                BoardStatus::Stalemate => SearchResult::default(),
+                BoardStatus::Ongoing => eval::search(child, lines, &san_correct_ep(board.to_string()), self.depth, None).await?,
+            };
+            results.push(result);
+        }
+
+        for (ply, played) in moves.iter().enumerate() {
+            if side.is_some_and(|side| side != boards[ply].side_to_move()) {
+                continue;
+            }
+            // Both from the point of view of who played the move
+            let before = results[ply].score;
+            let after = -results[ply + 1].score;
+            if results[ply].best_move == Some(played.to_string()) || before - after < SWING_THRESHOLD {
+                continue;
+            }
+            // A missed win is a puzzle for the player from the same position, while
+            // a blunder becomes a puzzle for the opponent right after it.
+            let (puzzle_ply, theme) = if before >= WINNING_THRESHOLD && after < WINNING_THRESHOLD {
+                (ply, "missedWin")
+            } else if before > -WINNING_THRESHOLD && after <= -WINNING_THRESHOLD {
+                (ply + 1, "blunder")
+            } else {
+                continue;
+            };
+            // The move before the puzzle position is needed as the opponent's move
+            if puzzle_ply == 0 || boards[puzzle_ply].status() != BoardStatus::Ongoing {
+                continue;
+            }
+            let solution = solution_line(&boards[puzzle_ply], &results[puzzle_ply].pv);
+            if !solution.is_empty() {
+                puzzles.push(new_puzzle(game, &boards[puzzle_ply - 1], moves[puzzle_ply - 1], &solution, theme));
+            }
+        }
+        Ok(puzzles)
+    }
+}
+
+// Follows the engine line while the solver's moves are forcing (captures, checks
+// and promotions), always ending on one of the solver's moves.
+fn solution_line(board: &Board, pv: &[String]) -> Vec<String> {
+    let mut line = Vec::new();
+    let mut board = *board;
+    for (index, uci_move) in pv.iter().take(MAX_SOLUTION_PLIES).enumerate() {
+        let Some(chess_move) = session::uci_to_move(&board, uci_move) else {
+            break;
+        };
+        let is_capture = board.piece_on(chess_move.get_dest()).is_some();
+        board = board.make_move_new(chess_move);
+        line.push(uci_move.clone());
+        let is_forcing = is_capture || chess_move.get_promotion().is_some() || board.checkers().popcnt() != 0;
+        if index % 2 == 0 && (board.status() != BoardStatus::Ongoing || !is_forcing) {
+            break;
+        }
+    }
+    if line.len() % 2 == 0 {
+        line.pop();
+    }
+    line
+}
+
+fn new_puzzle(game: &PgnGame, board: &Board, opponent_move: ChessMove, solution: &[String], theme: &str) -> config::Puzzle {
+    let fen = san_correct_ep(board.to_string());
+    let moves = opponent_move.to_string() + " " + &solution.join(" ");
+    let mut hasher = DefaultHasher::new();
+    fen.hash(&mut hasher);
+    moves.hash(&mut hasher);
+    let game_url = game.header("Site").filter(|site| site.starts_with("http")).unwrap_or_default();
+    config::Puzzle {
+        puzzle_id: format!("my{:x}", hasher.finish()),
+        fen,
+        moves,
+        rating: 0,
+        rating_deviation: 0,
+        popularity: 0,
+        nb_plays: 0,
+        themes: String::from("myGames ") + theme,
+        game_url: String::from(game_url),
+        opening: String::new(),
+    }
+}
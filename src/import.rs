@@ -0,0 +1,256 @@
+use std::path::Path;
+use std::str::FromStr;
+use chess::Board;
+
+use offline_chess_puzzles::session;
+
+use crate::{config, db, lang, pgn, san_correct_ep};
+use crate::pgn::PgnGame;
+
+// Where export::to_pgn points the Site tag, followed by the puzzle id
+const EXPORTED_SITE_PREFIX: &str = "https://lichess.org/training/";
+
+// Imported positions have no opponent move before them, so the
+// "moves" field starts with the UCI null move in its place.
+pub fn no_setup_moves(solution: &[String]) -> String {
+    std::iter::once(String::from(config::NULL_MOVE)).chain(solution.iter().cloned()).collect::<Vec<String>>().join(" ")
+}
+
+fn new_puzzle(puzzle_id: String, fen: String, moves: String, themes: String) -> config::Puzzle {
+    config::Puzzle {
+        puzzle_id,
+        fen,
+        moves,
+        rating: 0,
+        rating_deviation: 0,
+        popularity: 0,
+        nb_plays: 0,
+        themes,
+        game_url: String::new(),
+        opening: String::new(),
+    }
+}
+
+// Each game is a puzzle: the starting position ([FEN] header) and the moves are the
+// solution, starting with the solver's move (and without a last move of the opponent). Games written by export::to_pgn are
+// read back as they were. Returns the puzzles and how many games were skipped.
+pub fn pgn_puzzles(text: &str, source: &str, lang: &lang::Language) -> (Vec<config::Puzzle>, usize) {
+    let mut puzzles = Vec::new();
+    let mut skipped = 0;
+    for (index, game) in pgn::parse_pgn(text).iter().enumerate() {
+        if is_exported_puzzle(game) {
+            match exported_puzzle(game, lang) {
+                Some(puzzle) => puzzles.push(puzzle),
+                None => skipped += 1,
+            }
+            continue;
+        }
+        let Some((start, moves)) = game.chess_moves() else {
+            skipped += 1;
+            continue;
+        };
+        // A partially understood solution would be wrong, so it's all or nothing
+        if moves.is_empty() || moves.len() != game.moves.len() {
+            skipped += 1;
+            continue;
+        }
+        let mut solution: Vec<String> = moves.iter().map(|chess_move| chess_move.to_string()).collect();
+        // Our puzzles end on the solver's move, a last move of the opponent would never be played
+        if solution.len() % 2 == 0 {
+            solution.pop();
+        }
+        let themes = game.header("Themes").or(game.header("PuzzleThemes")).unwrap_or_default();
+        puzzles.push(new_puzzle(
+            format!("{}-{}", source, index + 1),
+            san_correct_ep(start.to_string()),
+            no_setup_moves(&solution),
+            String::from(themes),
+        ));
+    }
+    (puzzles, skipped)
+}
+
+pub fn is_exported_puzzle(game: &PgnGame) -> bool {
+    game.header("PuzzleRating").is_some()
+}
+
+// Reads back a game written by export::to_pgn, with the same id, ratings and themes.
+pub fn exported_puzzle(game: &PgnGame, lang: &lang::Language) -> Option<config::Puzzle> {
+    let fen = game.header("FEN")?;
+    let board = Board::from_str(fen).ok()?;
+    // "e.p." comes after en passant captures as if it was a move of its own
+    let sans: Vec<&String> = game.moves.iter().filter(|san| *san != "e.p.").collect();
+    let mut moves = localized_moves(&board, &sans, lang)?;
+    // Our puzzles always end on the solver's move, so an odd number of moves
+    // means there was no opponent's move before it (like in the imported ones).
+    if moves.len() % 2 == 1 {
+        moves.insert(0, String::from(config::NULL_MOVE));
+    }
+    let number = |name: &str| game.header(name).and_then(|value| value.parse::<i32>().ok()).unwrap_or(0);
+    let text = |name: &str| String::from(game.header(name).unwrap_or_default());
+    let site = text("Site");
+    Some(config::Puzzle {
+        puzzle_id: site.strip_prefix(EXPORTED_SITE_PREFIX).map_or_else(|| site.clone(), String::from),
+        fen: String::from(fen),
+        moves: moves.join(" "),
+        rating: number("PuzzleRating"),
+        rating_deviation: number("PuzzleRatingDeviation"),
+        popularity: number("PuzzlePopularity"),
+        nb_plays: number("PuzzleNbPlays"),
+        themes: text("PuzzleThemes"),
+        game_url: text("GameID"),
+        opening: text("Opening"),
+    })
+}
+
+// The moves are exported with the piece letters of the app's language, which isn't
+// necessarily the one used now, so we try the current one first and then the others.
+fn localized_moves(board: &Board, sans: &[&String], lang: &lang::Language) -> Option<Vec<String>> {
+    std::iter::once(*lang).chain(lang::Language::ALL).find_map(|lang| {
+        let mut board = *board;
+        let mut moves = Vec::new();
+        for san in sans {
+            let chess_move = session::san_to_move(&board, &english_san(san, &lang))?;
+            board = board.make_move_new(chess_move);
+            moves.push(chess_move.to_string());
+        }
+        Some(moves)
+    })
+}
+
+fn english_san(san: &str, lang: &lang::Language) -> String {
+    const PIECES: [(&str, char); 5] = [("king", 'K'), ("queen", 'Q'), ("rook", 'R'), ("bishop", 'B'), ("knight", 'N')];
+    let letters: Vec<(String, char)> = PIECES.iter().map(|(key, english)| (lang::tr(lang, key), *english)).collect();
+    let mut english = String::new();
+    let mut previous = None;
+    for c in san.chars() {
+        // Piece letters are at the start, or after the "=" of a promotion
+        if previous.is_none() || previous == Some('=') {
+            english.push(letters.iter().find(|(letter, _)| letter.starts_with(c)).map_or(c, |(_, english)| *english));
+        } else {
+            english.push(c);
+        }
+        previous = Some(c);
+    }
+    english
+}
+
+// EPD lines are the first four FEN fields followed by "opcode operands;" pairs.
+// Only records with a single "bm" can become puzzles: the "am" (avoid move) ones have
+// no solution, and a puzzle has only one right move (besides mates).
+pub fn epd_puzzles(text: &str, source: &str) -> (Vec<config::Puzzle>, usize) {
+    let mut puzzles = Vec::new();
+    let mut skipped = 0;
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.splitn(5, ' ').collect();
+        if fields.len() < 4 {
+            skipped += 1;
+            continue;
+        }
+        let mut best_moves = Vec::new();
+        let mut id = None;
+        let mut halfmove_clock = "0";
+        let mut fullmove_number = "1";
+        for operation in fields.get(4).unwrap_or(&"").split(';') {
+            let operation = operation.trim();
+            let (opcode, operands) = operation.split_once(' ').unwrap_or((operation, ""));
+            let operands = operands.trim();
+            match opcode {
+                "bm" => best_moves = operands.split_whitespace().collect(),
+                "id" => id = Some(operands.trim_matches('"')),
+                "hmvc" => halfmove_clock = operands,
+                "fmvn" => fullmove_number = operands,
+                _ => (),
+            }
+        }
+        let fen = fields[..4].join(" ") + " " + halfmove_clock + " " + fullmove_number;
+        let Ok(board) = Board::from_str(&fen) else {
+            skipped += 1;
+            continue;
+        };
+        let [best_move] = best_moves[..] else {
+            skipped += 1;
+            continue;
+        };
+        let Some(best_move) = session::san_to_move(&board, best_move) else {
+            skipped += 1;
+            continue;
+        };
+        let puzzle_id = match id {
+            Some(id) if !id.is_empty() => format!("{source}-{id}"),
+            _ => format!("{}-{}", source, line_number + 1),
+        };
+        puzzles.push(new_puzzle(puzzle_id, san_correct_ep(board.to_string()), no_setup_moves(&[best_move.to_string()]), String::new()));
+    }
+    (puzzles, skipped)
+}
+
+// Reads a PGN or EPD file (by its extension) and saves its puzzles under the given source,
+// or under the file name if it's empty. Returns the source, how many were added and how many skipped.
+pub fn import_file(path: &str, source: &str, lang: &lang::Language) -> Result<(String, usize, usize), String> {
+    let text = std::fs::read_to_string(path).map_err(|_| String::from(path))?;
+    let path = Path::new(path);
+    let source = if source.trim().is_empty() {
+        path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default()
+    } else {
+        String::from(source.trim())
+    };
+    let is_epd = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("epd"));
+    let (puzzles, skipped) = if is_epd {
+        epd_puzzles(&text, &source)
+    } else {
+        pgn_puzzles(&text, &source, lang)
+    };
+    let added = db::save_my_puzzles(&puzzles, &source).ok_or_else(db::database_url)?;
+    Ok((source, added, skipped))
+}
+
+// A whole set exported by export::to_pgn, to be loaded as the puzzle list.
+pub fn load_exported_pgn(path: &str, lang: &lang::Language) -> Result<Vec<config::Puzzle>, String> {
+    let text = std::fs::read_to_string(path).map_err(|_| String::from(path))?;
+    Ok(pgn::parse_pgn(&text).iter().filter(|game| is_exported_puzzle(game)).filter_map(|game| exported_puzzle(game, lang)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use offline_chess_puzzles::session::{PuzzleSession, Verdict};
+
+    #[test]
+    fn game_ending_on_the_opponents_move() {
+        let text = "[Event \"Test\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1\"]\n\n1. e4 Kd7 2. e5 Ke6 *\n";
+        let (puzzles, skipped) = pgn_puzzles(text, "test", &lang::Language::English);
+        assert_eq!(skipped, 0);
+        assert_eq!(puzzles.len(), 1);
+        assert_eq!(puzzles[0].moves, "0000 e2e4 e8d7 e4e5");
+
+        let mut session = PuzzleSession::new(&puzzles[0].fen, &puzzles[0].moves).unwrap();
+        assert!(matches!(session.submit_uci("e2e4"), Verdict::Correct { .. }));
+        assert_eq!(session.submit_uci("e4e5"), Verdict::Solved);
+    }
+
+    #[test]
+    fn epd_records() {
+        let text = "4k3/8/8/8/8/8/4P3/4K3 w - - bm e4; id \"one\";\n\
+            4k3/8/8/8/8/8/4P3/4K3 w - - am e4;\n\
+            4k3/8/8/8/8/8/4P3/4K3 w - - bm e3 e4;\n\
+            4k3/8/8/8/8/8/4P3/4K3 w - - am e3; bm e4;\n";
+        let (puzzles, skipped) = epd_puzzles(text, "test");
+        assert_eq!(skipped, 2);
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0].puzzle_id, "test-one");
+        assert_eq!(puzzles[1].puzzle_id, "test-4");
+        assert!(puzzles.iter().all(|puzzle| puzzle.moves == "0000 e2e4" && puzzle.themes.is_empty()));
+    }
+
+    #[test]
+    fn game_ending_on_the_solvers_move() {
+        let text = "[FEN \"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1\"]\n\n1. e4 Kd7 2. e5 *\n";
+        let (puzzles, _) = pgn_puzzles(text, "test", &lang::Language::English);
+        assert_eq!(puzzles[0].moves, "0000 e2e4 e8d7 e4e5");
+    }
+}
@@ -0,0 +1,2 @@
+// The parts that don't depend on the UI, so they can be used by other front-ends and tests
+pub mod session;
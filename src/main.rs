@@ -1,24 +1,24 @@
 #![windows_subsystem = "windows"]
 
 use download_db::download_lichess_db;
-use eval::{Engine, EngineStatus};
+use eval::{Engine, EngineError, EngineStatus};
 use iced::advanced::widget::Id as GenericId;
 use iced::widget::svg::Handle;
 use iced::widget::text::LineHeight;
 use styles::PieceTheme;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::io::BufReader;
 use std::path::Path;
 use std::fs::File as StdFile;
 use std::str::FromStr;
 use tokio::sync::mpsc::{self, Sender};
-use iced::widget::{button, center, container, responsive, row, text, text_input, Button, Column, Container, Radio, Row, Svg, Text};
-use iced::{Element, Rectangle, Size, Subscription, Theme};
+use iced::widget::{button, center, column, container, mouse_area, responsive, row, stack, text, text_input, Button, Column, Container, Radio, Row, Space, Svg, Text};
+use iced::{Element, Size, Subscription, Theme};
 use iced::{alignment, Task, Alignment, Length};
-use iced::window::{self, Screenshot};
+use iced::{keyboard, window};
 use iced::event::{self, Event};
 use std::borrow::Cow;
-use image::RgbaImage;
 use rfd::AsyncFileDialog;
 
 use iced_aw::{TabLabel, Tabs};
@@ -42,11 +42,24 @@ use settings::{SettingsMessage, SettingsTab};
 
 mod puzzles;
 use puzzles::{PuzzleMessage, PuzzleTab, GameStatus};
+use editor::{EditorMessage, EditorTab};
+use offline_chess_puzzles::session::{self, MoveError, PuzzleSession, Verdict};
 
 mod eval;
 mod export;
 mod lang;
 mod openings;
+mod overlay;
+mod verify;
+mod generate;
+mod pgn;
+mod import;
+mod diagram;
+mod editor;
+mod cli;
+mod tui;
+mod shortcuts;
+mod accessibility;
 
 pub mod models;
 pub mod schema;
@@ -61,6 +74,8 @@ extern crate serde_derive;
 const HEADER_SIZE: f32 = 32.0;
 const TAB_PADDING: u16 = 16;
 const LICHESS_DB_URL: &str = "https://database.lichess.org/lichess_db_puzzle.csv.zst";
+const EVAL_BAR_WIDTH: f32 = 14.0;
+const RANK_LABEL_WIDTH: f32 = 16.0;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct PositionGUI {
@@ -73,6 +88,7 @@ pub enum TabId {
     Search,
     Settings,
     CurrentPuzzle,
+    Editor,
 }
 
 #[derive(Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord)]
@@ -85,6 +101,40 @@ impl PieceWithColor {
     fn index(&self) -> usize {
         *self as usize
     }
+
+    fn new(piece: Piece, color: Color) -> Self {
+        match (piece, color) {
+            (Piece::Pawn, Color::White) => PieceWithColor::WhitePawn,
+            (Piece::Rook, Color::White) => PieceWithColor::WhiteRook,
+            (Piece::Knight, Color::White) => PieceWithColor::WhiteKnight,
+            (Piece::Bishop, Color::White) => PieceWithColor::WhiteBishop,
+            (Piece::Queen, Color::White) => PieceWithColor::WhiteQueen,
+            (Piece::King, Color::White) => PieceWithColor::WhiteKing,
+            (Piece::Pawn, Color::Black) => PieceWithColor::BlackPawn,
+            (Piece::Rook, Color::Black) => PieceWithColor::BlackRook,
+            (Piece::Knight, Color::Black) => PieceWithColor::BlackKnight,
+            (Piece::Bishop, Color::Black) => PieceWithColor::BlackBishop,
+            (Piece::Queen, Color::Black) => PieceWithColor::BlackQueen,
+            (Piece::King, Color::Black) => PieceWithColor::BlackKing,
+        }
+    }
+}
+
+// A piece sliding to its square, drawn over the board. The move is already made.
+#[derive(Clone, Copy, Debug)]
+struct MoveAnimation {
+    from: Square,
+    to: Square,
+    started: iced::time::Instant,
+    duration: std::time::Duration,
+}
+
+impl MoveAnimation {
+    // From 0 to 1, slowing down at the end
+    fn progress(&self, now: iced::time::Instant) -> f32 {
+        let linear = (now.duration_since(self.started).as_secs_f32() / self.duration.as_secs_f32()).min(1.);
+        1. - (1. - linear) * (1. - linear)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -94,6 +144,8 @@ pub enum Message {
     Search(SearchMesssage),
     Settings(SettingsMessage),
     PuzzleInfo(PuzzleMessage),
+    Editor(EditorMessage),
+    AnalyzePosition(Board),
     SelectMode(config::GameMode),
     TabSelected(TabId),
     ShowHint,
@@ -103,18 +155,38 @@ pub enum Message {
     RedoPuzzle,
     DropPiece(Square, iced::Point, iced::Rectangle),
     HandleDropZones(Square, Vec<(iced::advanced::widget::Id, iced::Rectangle)>),
-    ScreenshotCreated(Screenshot),
-    SaveScreenshot(Option<(Screenshot, String)>),
+    SaveDiagram(Option<String>),
+    ExportDiagrams(Option<String>),
+    DiagramsExported(Result<usize, String>),
     ExportPDF(Option<String>),
     LoadPuzzle(Option<Vec<config::Puzzle>>),
     ExportPGN(Option<String>),
+    ExportAnki(Option<String>),
+    AnkiExported(Result<usize, String>),
+    ExportHTML(Option<String>),
+    ExportCSV(Option<String>),
+    OpenPuzzlesPGN(Option<String>),
     ChangeSettings(Option<config::OfflinePuzzlesConfig>),
     EventOccurred(iced::Event),
     StartEngine,
     EngineStopped(bool),
-    UpdateEval((Option<String>, Option<String>)),
+    UpdateEval((Option<String>, Option<String>, Option<Vec<String>>)),
     EngineReady(mpsc::Sender<String>),
+    EngineError(EngineError),
+    SendUciCommand(String),
+    StartVerification,
+    CancelVerification,
+    VerifyProgress(usize, usize),
+    VerifyFinished(Result<Vec<verify::VerifyIssue>, EngineError>),
+    ExportVerifyReport(Option<String>),
+    GenerateFromPgn(Option<String>),
+    CancelGeneration,
+    GenerateProgress(usize, usize, usize),
+    GenerateFinished(Result<usize, generate::GenerateError>),
     EngineFileChosen(Option<String>),
+    EngineBestMove(String),
+    SavePlayedGame,
+    ExportGamePGN(Option<String>),
     FavoritePuzzle,
     MinimizeUI,
     SaveMaximizedStatusAndExit(bool),
@@ -123,6 +195,16 @@ pub enum Message {
     DownloadProgress(String),
     PuzzleInputIndexChange(String),
     JumpToPuzzle,
+    MoveInputChange(String),
+    SubmitMoveInput,
+    Peek,
+    BlindfoldTick(iced::time::Instant),
+    ReadPosition,
+    MarkStart(Square),
+    MarkEnd(Square),
+    ClearMarks,
+    OpponentReply(usize),
+    AnimationTick(iced::time::Instant),
 }
 
 struct SoundPlayback {
@@ -254,14 +336,47 @@ struct OfflinePuzzles {
     hint_square: Option<Square>,
     puzzle_status: String,
     puzzle_number_ui: String,
+    move_input: String,
+    move_input_error: String,
+    // When the pieces started being shown, for the blindfold mode that hides them after a while
+    blindfold_shown_at: Option<iced::time::Instant>,
+    peeking: bool,
+    // The square under the keyboard's cursor
+    cursor_square: Option<Square>,
+    // The opponent's last move in words, like "Knight g1 to f3"
+    opponent_move_text: String,
+    // The arrows and circles drawn on the current puzzle, by overlay::position_key()
+    marks: HashMap<String, overlay::Marks>,
+    marks_puzzle_id: String,
+    // Where the right button was pressed, an arrow goes from there
+    mark_start: Option<Square>,
+    modifiers: keyboard::Modifiers,
+    // The opponent's reply waiting for the delay to pass, the ids tell the late ones
+    // (from a puzzle that was left) from the current one
+    pending_reply: Option<ChessMove>,
+    reply_id: usize,
+    animation: Option<MoveAnimation>,
 
     analysis: Game,
     analysis_history: Vec<Board>,
+    // The positions at the start of the history that can't be taken back (the puzzle's moves)
+    analysis_base_len: usize,
     engine_state: EngineStatus,
     engine_eval: String,
     engine: Engine,
     engine_sender: Option<Sender<String>>,
     engine_move: String,
+    engine_bar: Option<f32>,
+    engine_pv: Vec<String>,
+    engine_error: Option<EngineError>,
+    verify_job: Option<verify::VerifyJob>,
+    verify_runs: usize,
+    generate_job: Option<generate::GenerateJob>,
+
+    play_start: Board,
+    play_moves: Vec<ChessMove>,
+    play_side: Color,
+    play_status: String,
 
     downloading_db: bool,
     download_progress: String,
@@ -269,6 +384,7 @@ struct OfflinePuzzles {
     search_tab: SearchTab,
     settings_tab: SettingsTab,
     puzzle_tab: PuzzleTab,
+    editor_tab: EditorTab,
     game_mode: config::GameMode,
     sound_playback: Option<SoundPlayback>,
     lang: lang::Language,
@@ -298,6 +414,7 @@ impl OfflinePuzzles {
 
             analysis: Game::new(),
             analysis_history: vec![Board::default()],
+            analysis_base_len: 1,
             engine_state: EngineStatus::TurnedOff,
             engine_eval: String::new(),
             engine: Engine::new(
@@ -307,14 +424,39 @@ impl OfflinePuzzles {
             ),
             engine_sender: None,
             engine_move: String::new(),
+            engine_bar: None,
+            engine_pv: Vec::new(),
+            engine_error: None,
+            verify_job: None,
+            verify_runs: 0,
+            generate_job: None,
+
+            play_start: Board::default(),
+            play_moves: Vec::new(),
+            play_side: Color::White,
+            play_status: String::new(),
 
             downloading_db: false,
             download_progress: String::new(),
             puzzle_status: lang::tr(&config::SETTINGS.lang, "use_search"),
             puzzle_number_ui: String::from("1"),
+            move_input: String::new(),
+            move_input_error: String::new(),
+            blindfold_shown_at: None,
+            peeking: false,
+            cursor_square: None,
+            opponent_move_text: String::new(),
+            marks: HashMap::new(),
+            marks_puzzle_id: String::new(),
+            mark_start: None,
+            modifiers: keyboard::Modifiers::default(),
+            pending_reply: None,
+            reply_id: 0,
+            animation: None,
             search_tab: SearchTab::new(),
             settings_tab: SettingsTab::new(),
             puzzle_tab: PuzzleTab::new(),
+            editor_tab: EditorTab::new(),
             active_tab: TabId::Search,
 
             game_mode: config::GameMode::Puzzle,
@@ -327,28 +469,47 @@ impl OfflinePuzzles {
         }
     }
 
-    fn verify_and_make_move(&mut self, from: Square, to: Square) {
+    // Returns the task of the opponent's delayed reply, in puzzles
+    fn verify_and_make_move(&mut self, from: Square, to: Square, promotion: Piece) -> Task<Message> {
+        // The opponent hasn't answered the previous move yet
+        if self.game_mode == config::GameMode::Puzzle && self.pending_reply.is_some() {
+            return Task::none();
+        }
         let side =
         match self.game_mode {
-            config::GameMode::Analysis => { self.analysis.side_to_move() }
+            config::GameMode::Analysis | config::GameMode::PlayEngine => { self.analysis.side_to_move() }
             config::GameMode::Puzzle => { self.board.side_to_move() }
+            config::GameMode::Editor => { self.editor_board().side_to_move() }
         };
         let color =
             match self.game_mode {
-                config::GameMode::Analysis => { self.analysis.current_position().color_on(to) }
+                config::GameMode::Analysis | config::GameMode::PlayEngine => { self.analysis.current_position().color_on(to) }
                 config::GameMode::Puzzle => { self.board.color_on(to) }
+                config::GameMode::Editor => { self.editor_board().color_on(to) }
             };
         // If the user clicked on another piece of his own side,
         // just replace the previous selection and exit
         if self.puzzle_tab.game_status == GameStatus::Playing && color == Some(side) {
             self.from_square = Some(to);
-            return;
+            return Task::none();
         }
         self.from_square = None;
 
-        if self.game_mode == config::GameMode::Analysis {
+        if self.game_mode == config::GameMode::PlayEngine {
+            if !self.is_player_turn() {
+                return Task::none();
+            }
+            let move_made_notation =
+                get_notation_string(self.analysis.current_position(), promotion, from, to);
+
+            let move_made = ChessMove::new(
+                Square::from_str(&String::from(&move_made_notation[..2])).unwrap(),
+                Square::from_str(&String::from(&move_made_notation[2..4])).unwrap(), PuzzleTab::check_promotion(&move_made_notation));
+
+            self.make_play_move(move_made);
+        } else if self.game_mode == config::GameMode::Analysis {
             let move_made_notation =
-                get_notation_string(self.analysis.current_position(), self.search_tab.piece_to_promote_to, from, to);
+                get_notation_string(self.analysis.current_position(), promotion, from, to);
 
             let move_made = ChessMove::new(
                 Square::from_str(&String::from(&move_made_notation[..2])).unwrap(),
@@ -356,6 +517,7 @@ impl OfflinePuzzles {
 
             if self.analysis.make_move(move_made) {
                 self.analysis_history.push(self.analysis.current_position());
+                self.engine_pv.clear();
                 self.engine.position = self.analysis.current_position().to_string();
                 if let Some(sender) = &self.engine_sender {
                     if let Err(e) = sender.blocking_send(san_correct_ep(self.analysis.current_position().to_string())) {
@@ -368,108 +530,188 @@ impl OfflinePuzzles {
                     }
                 }
             }
-        } else if !self.puzzle_tab.puzzles.is_empty() {
-            let movement;
+        } else if self.game_mode == config::GameMode::Editor {
             let move_made_notation =
-                get_notation_string(self.board, self.search_tab.piece_to_promote_to, from, to);
+                get_notation_string(self.editor_board(), promotion, from, to);
 
             let move_made = ChessMove::new(
                 Square::from_str(&String::from(&move_made_notation[..2])).unwrap(),
                 Square::from_str(&String::from(&move_made_notation[2..4])).unwrap(), PuzzleTab::check_promotion(&move_made_notation));
 
-            let is_mate = self.board.legal(move_made) && self.board.make_move_new(move_made).status() == BoardStatus::Checkmate;
-
-            let correct_moves : Vec<&str> = self.puzzle_tab.puzzles[self.puzzle_tab.current_puzzle].moves.split_whitespace().collect::<Vec<&str>>();
-            let correct_move = ChessMove::new(
-                Square::from_str(&String::from(&correct_moves[self.puzzle_tab.current_puzzle_move][..2])).unwrap(),
-                Square::from_str(&String::from(&correct_moves[self.puzzle_tab.current_puzzle_move][2..4])).unwrap(), PuzzleTab::check_promotion(correct_moves[self.puzzle_tab.current_puzzle_move]));
-
-            // If the move is correct we can apply it to the board
-            if is_mate || (move_made == correct_move) {
+            if self.editor_tab.add_move(move_made) && self.settings_tab.saved_configs.play_sound {
+                if let Some(audio) = &self.sound_playback {
+                    audio.play_audio(SoundPlayback::ONE_PIECE_SOUND);
+                }
+            }
+        } else if let Some(session) = &mut self.puzzle_tab.session {
+            let move_made_notation =
+                get_notation_string(self.board, promotion, from, to);
 
-                self.board = self.board.make_move_new(move_made);
-                self.analysis_history.push(self.board);
+            let move_made = ChessMove::new(
+                Square::from_str(&String::from(&move_made_notation[..2])).unwrap(),
+                Square::from_str(&String::from(&move_made_notation[2..4])).unwrap(), PuzzleTab::check_promotion(&move_made_notation));
 
-                self.puzzle_tab.current_puzzle_move += 1;
+            match session.submit_move(move_made) {
+                Verdict::Solved => {
+                    let peeks = session.peeks();
+                    self.board = session.board();
+                    self.analysis_history.push(self.board);
+                    self.puzzle_tab.current_puzzle_move = session.next_move_index();
 
-                if self.puzzle_tab.current_puzzle_move == correct_moves.len() {
                     if self.settings_tab.saved_configs.play_sound {
                         if let Some(audio) = &self.sound_playback {
                             audio.play_audio(SoundPlayback::ONE_PIECE_SOUND);
                         }
                     }
-                    if self.puzzle_tab.current_puzzle < self.puzzle_tab.puzzles.len() - 1 {
-                        if self.settings_tab.saved_configs.auto_load_next {
-                            self.load_puzzle(true);
-                        } else {
-                            self.puzzle_tab.game_status = GameStatus::PuzzleEnded;
-                            self.puzzle_status = lang::tr(&self.lang, "correct_puzzle");
+                    self.puzzle_solved(peeks);
+                } Verdict::Correct { reply } => {
+                    let before_reply = self.board.make_move_new(move_made);
+                    self.analysis_history.push(before_reply);
+                    self.analysis_history.push(session.board());
+                    self.puzzle_tab.current_puzzle_move = session.next_move_index();
+                    self.puzzle_status = lang::tr(&self.lang, "correct_move");
+                    self.opponent_move_text = accessibility::describe_move(&before_reply, reply, &self.lang);
+
+                    let delay = std::time::Duration::from_millis(self.settings_tab.reply_delay_ms.parse().unwrap_or(0));
+                    if delay.is_zero() {
+                        if self.settings_tab.saved_configs.play_sound {
+                            if let Some(audio) = &self.sound_playback {
+                                audio.play_audio(SoundPlayback::TWO_PIECE_SOUND);
+                            }
                         }
+                        self.show_reply(reply);
                     } else {
-                        if self.settings_tab.saved_configs.auto_load_next {
-                            self.board = Board::default();
-                            // quite meaningless but allows the user to use the takeback button
-                            // to analyze a full game in analysis mode after the puzzles ended.
-                            self.analysis_history = vec![self.board];
-                            self.puzzle_tab.current_puzzle_move = 1;
-                            self.puzzle_tab.game_status = GameStatus::NoPuzzles;
-                        } else {
-                            self.puzzle_tab.game_status = GameStatus::PuzzleEnded;
+                        if self.settings_tab.saved_configs.play_sound {
+                            if let Some(audio) = &self.sound_playback {
+                                audio.play_audio(SoundPlayback::ONE_PIECE_SOUND);
+                            }
                         }
-                        self.last_move_from = None;
-                        self.last_move_to = None;
-                        self.puzzle_status = lang::tr(&self.lang, "all_puzzles_done");
+                        // The solver's move stays alone on the board for a moment, so the reply is easy to follow
+                        self.board = before_reply;
+                        self.last_move_from = Some(move_made.get_source());
+                        self.last_move_to = Some(move_made.get_dest());
+                        self.pending_reply = Some(reply);
+                        self.reply_id += 1;
+                        let reply_id = self.reply_id;
+                        return Task::perform(tokio::time::sleep(delay), move |_| Message::OpponentReply(reply_id));
                     }
-                } else {
-                    if self.settings_tab.saved_configs.play_sound {
-                        if let Some(audio) = &self.sound_playback {
-                            audio.play_audio(SoundPlayback::TWO_PIECE_SOUND);
-                        }
+                } Verdict::Wrong | Verdict::Illegal => {
+                    if self.board.side_to_move() == Color::White {
+                        self.puzzle_status = lang::tr(&self.lang, "wrong_move_white_play");
+                    } else {
+                        self.puzzle_status = lang::tr(&self.lang, "wrong_move_black_play");
                     }
-                    movement = ChessMove::new(
-                        Square::from_str(&String::from(&correct_moves[self.puzzle_tab.current_puzzle_move][..2])).unwrap(),
-                        Square::from_str(&String::from(&correct_moves[self.puzzle_tab.current_puzzle_move][2..4])).unwrap(), PuzzleTab::check_promotion(correct_moves[self.puzzle_tab.current_puzzle_move]));
-
-                    self.last_move_from = Some(movement.get_source());
-                    self.last_move_to = Some(movement.get_dest());
-
-                    self.board = self.board.make_move_new(movement);
-                    self.analysis_history.push(self.board);
+                    self.announce(&self.puzzle_status);
+                }
+            }
+        }
+        Task::none()
+    }
 
-                    self.puzzle_tab.current_puzzle_move += 1;
-                    self.puzzle_status = lang::tr(&self.lang, "correct_move");
+    // The status and the next puzzle (if it's loaded right away) after the last move of the solution
+    fn puzzle_solved(&mut self, peeks: usize) {
+        // Peeking in blindfold mode counts against the attempt
+        let verdict = if peeks > 0 {
+            lang::tr(&self.lang, "solved_with_peeks") + &peeks.to_string()
+        } else {
+            lang::tr(&self.lang, "correct_puzzle")
+        };
+        self.announce(&verdict);
+        if self.puzzle_tab.current_puzzle < self.puzzle_tab.puzzles.len() - 1 {
+            if self.settings_tab.saved_configs.auto_load_next {
+                self.load_puzzle(true);
+                if peeks > 0 {
+                    self.puzzle_status = verdict + " " + &self.puzzle_status;
                 }
             } else {
-                #[allow(clippy::collapsible_else_if)]
-                if self.board.side_to_move() == Color::White {
-                    self.puzzle_status = lang::tr(&self.lang, "wrong_move_white_play");
-                } else {
-                    self.puzzle_status = lang::tr(&self.lang, "wrong_move_black_play");
-                }
+                self.puzzle_tab.game_status = GameStatus::PuzzleEnded;
+                self.puzzle_status = verdict;
+            }
+        } else {
+            if self.settings_tab.saved_configs.auto_load_next {
+                self.board = Board::default();
+                // quite meaningless but allows the user to use the takeback button
+                // to analyze a full game in analysis mode after the puzzles ended.
+                self.analysis_history = vec![self.board];
+                self.puzzle_tab.current_puzzle_move = 1;
+                self.puzzle_tab.game_status = GameStatus::NoPuzzles;
+            } else {
+                self.puzzle_tab.game_status = GameStatus::PuzzleEnded;
             }
+            self.last_move_from = None;
+            self.last_move_to = None;
+            self.puzzle_status = lang::tr(&self.lang, "all_puzzles_done");
+            self.announce(&self.puzzle_status);
         }
     }
 
+    // The opponent's reply to the solver's move, already played in the session
+    fn show_reply(&mut self, reply: ChessMove) {
+        let mut solved_with_peeks = None;
+        if let Some(session) = &self.puzzle_tab.session {
+            self.board = session.board();
+            // A solution ending in the opponent's move is over once it's played
+            solved_with_peeks = session.is_solved().then(|| session.peeks());
+        }
+        self.last_move_from = Some(reply.get_source());
+        self.last_move_to = Some(reply.get_dest());
+        self.animate(reply);
+        self.announce(&(self.puzzle_status.clone() + " " + &lang::tr(&self.lang, "opponent_played") + &self.opponent_move_text));
+        if let Some(peeks) = solved_with_peeks {
+            self.puzzle_solved(peeks);
+        }
+    }
+
+    // Slides the piece of a move that's already on the board, unless the animations are off
+    fn animate(&mut self, movement: ChessMove) {
+        let duration = std::time::Duration::from_millis(self.settings_tab.animation_ms.parse().unwrap_or(0));
+        self.animation = if self.settings_tab.animate_moves && !duration.is_zero() {
+            Some(MoveAnimation { from: movement.get_source(), to: movement.get_dest(), started: iced::time::Instant::now(), duration })
+        } else {
+            None
+        };
+    }
+
     fn load_puzzle(&mut self, inc_counter: bool) {
         self.hint_square = None;
+        self.pending_reply = None;
+        self.animation = None;
+        self.peeking = false;
+        self.blindfold_shown_at = if self.settings_tab.blindfold == config::Blindfold::HideAfter {
+            Some(iced::time::Instant::now())
+        } else {
+            None
+        };
         self.puzzle_tab.current_puzzle_move = 1;
         if inc_counter {
             self.inc_puzzle_counter();
         }
-        let puzzle_moves: Vec<&str> = self.puzzle_tab.puzzles[self.puzzle_tab.current_puzzle].moves.split_whitespace().collect();
-
-        // The opponent's last move (before the puzzle starts)
-        // is in the "moves" field of the cvs, so we need to apply it.
-        self.board = Board::from_str(&self.puzzle_tab.puzzles[self.puzzle_tab.current_puzzle].fen).unwrap();
-
-        let movement = ChessMove::new(
-            Square::from_str(&String::from(&puzzle_moves[0][..2])).unwrap(),
-            Square::from_str(&String::from(&puzzle_moves[0][2..4])).unwrap(), PuzzleTab::check_promotion(puzzle_moves[0]));
-
-        self.last_move_from = Some(movement.get_source());
-        self.last_move_to = Some(movement.get_dest());
-
-        self.board = self.board.make_move_new(movement);
+        let puzzle = &self.puzzle_tab.puzzles[self.puzzle_tab.current_puzzle];
+        self.marks_puzzle_id = puzzle.puzzle_id.clone();
+        self.marks = db::get_marks(&puzzle.puzzle_id).into_iter()
+            .map(|(position, comment)| (position, overlay::Marks::from_pgn_comment(&comment)))
+            .collect();
+        let Some(session) = PuzzleSession::new(&puzzle.fen, &puzzle.moves) else {
+            self.puzzle_tab.session = None;
+            self.puzzle_tab.game_status = GameStatus::PuzzleEnded;
+            self.puzzle_status = lang::tr(&self.lang, "invalid_puzzle");
+            self.opponent_move_text.clear();
+            self.announce(&self.puzzle_status);
+            return;
+        };
+        self.opponent_move_text = match (session.last_move(), Board::from_str(&puzzle.fen)) {
+            (Some(movement), Ok(start)) => accessibility::describe_move(&start, movement, &self.lang),
+            _ => String::new(),
+        };
+        // The opponent's last move (before the puzzle starts) is already
+        // applied, imported puzzles may start without it
+        self.board = session.board();
+        self.last_move_from = session.last_move().map(|movement| movement.get_source());
+        self.last_move_to = session.last_move().map(|movement| movement.get_dest());
+        if let Some(movement) = session.last_move() {
+            self.animate(movement);
+        }
+        self.puzzle_tab.session = Some(session);
         self.analysis_history = vec![self.board];
 
         if self.board.side_to_move() == Color::White {
@@ -477,13 +719,245 @@ impl OfflinePuzzles {
         } else {
             self.puzzle_status = lang::tr(&self.lang, "black_to_move");
         }
+        if self.opponent_move_text.is_empty() {
+            self.announce(&self.puzzle_status);
+        } else {
+            self.announce(&(lang::tr(&self.lang, "opponent_played") + &self.opponent_move_text + ". " + &self.puzzle_status));
+        }
 
         self.puzzle_tab.current_puzzle_side = self.board.side_to_move();
         self.puzzle_tab.current_puzzle_fen = san_correct_ep(self.board.to_string());
         self.puzzle_tab.game_status = GameStatus::Playing;
+        if self.game_mode == config::GameMode::PlayEngine {
+            self.stop_play_engine();
+        }
         self.game_mode = config::GameMode::Puzzle;
     }
 
+    // The position the solution is being entered on, or the one being set up
+    fn editor_board(&self) -> Board {
+        self.editor_tab.current_board().unwrap_or_default()
+    }
+
+    // The keys typed in text inputs don't get here, the subscription only has the ignored events
+    fn handle_shortcut(&mut self, key: String) -> Task<Message> {
+        if self.settings_tab.rebinding.is_some() {
+            return self.settings_tab.update(SettingsMessage::KeyPressed(key));
+        }
+        let Some(action) = self.settings_tab.key_bindings.action(&key) else {
+            return Task::none();
+        };
+        let is_puzzle = self.game_mode == config::GameMode::Puzzle;
+        let has_puzzle = is_puzzle && self.puzzle_tab.game_status != GameStatus::NoPuzzles;
+        match action {
+            shortcuts::Action::CursorUp => self.move_cursor(0, 1),
+            shortcuts::Action::CursorDown => self.move_cursor(0, -1),
+            shortcuts::Action::CursorLeft => self.move_cursor(-1, 0),
+            shortcuts::Action::CursorRight => self.move_cursor(1, 0),
+            shortcuts::Action::Select => {
+                if let Some(square) = self.cursor_square {
+                    return Task::done(Message::SelectSquare(square));
+                }
+            }
+            shortcuts::Action::Hint if is_puzzle && self.puzzle_tab.game_status == GameStatus::Playing => {
+                return Task::done(Message::ShowHint);
+            }
+            shortcuts::Action::NextPuzzle if has_puzzle && self.puzzle_tab.current_puzzle < self.puzzle_tab.puzzles.len() - 1 => {
+                return Task::done(Message::ShowNextPuzzle);
+            }
+            shortcuts::Action::PreviousPuzzle if has_puzzle && self.puzzle_tab.current_puzzle > 0 => {
+                return Task::done(Message::ShowPreviousPuzzle);
+            }
+            shortcuts::Action::Redo if has_puzzle => return Task::done(Message::RedoPuzzle),
+            shortcuts::Action::Favorite if has_puzzle => return Task::done(Message::FavoritePuzzle),
+            shortcuts::Action::Takeback => {
+                let can_takeback = match self.game_mode {
+                    config::GameMode::Analysis => self.analysis_history.len() > self.analysis_base_len,
+                    config::GameMode::PlayEngine => !self.play_moves.is_empty(),
+                    _ => false,
+                };
+                if can_takeback {
+                    return Task::done(Message::GoBackMove);
+                }
+            }
+            shortcuts::Action::Flip => {
+                return Task::done(Message::Settings(SettingsMessage::CheckFlipBoard(!self.settings_tab.flip_board)));
+            }
+            shortcuts::Action::ToggleEngine if self.game_mode == config::GameMode::Analysis => {
+                return Task::done(Message::StartEngine);
+            }
+            shortcuts::Action::ReadPosition => return Task::done(Message::ReadPosition),
+            _ => (),
+        }
+        Task::none()
+    }
+
+    // In screen directions, so it depends on which side is at the bottom.
+    // The first press just shows the cursor, at the bottom left corner.
+    fn move_cursor(&mut self, right: i32, up: i32) {
+        let bottom_side = if self.game_mode == config::GameMode::Editor { Color::White } else { self.puzzle_tab.current_puzzle_side };
+        let is_white = (bottom_side == Color::White) ^ self.settings_tab.flip_board;
+        let direction = if is_white { 1 } else { -1 };
+        self.cursor_square = Some(match self.cursor_square {
+            None if is_white => Square::A1,
+            None => Square::H8,
+            Some(square) => {
+                let file = (square.get_file().to_index() as i32 + right * direction).clamp(0, 7);
+                let rank = (square.get_rank().to_index() as i32 + up * direction).clamp(0, 7);
+                Square::make_square(Rank::from_index(rank as usize), File::from_index(file as usize))
+            }
+        });
+    }
+
+    // What the blindfold mode hides right now, it's only for puzzles and they're shown again once solved
+    fn blindfold(&self) -> config::Blindfold {
+        if self.game_mode != config::GameMode::Puzzle || self.puzzle_tab.game_status != GameStatus::Playing
+                || self.peeking || self.blindfold_shown_at.is_some() {
+            config::Blindfold::Off
+        } else {
+            self.settings_tab.blindfold
+        }
+    }
+
+    fn can_move(&self) -> bool {
+        match self.game_mode {
+            config::GameMode::Puzzle => self.puzzle_tab.game_status == GameStatus::Playing && self.pending_reply.is_none(),
+            config::GameMode::Analysis => true,
+            config::GameMode::PlayEngine => self.is_player_turn(),
+            config::GameMode::Editor => !self.editor_tab.is_setting_up(),
+        }
+    }
+
+    // Says it with the text-to-speech command, when there's one
+    fn announce(&self, text: &str) {
+        if !self.settings_tab.tts_command.trim().is_empty() {
+            accessibility::speak(&self.settings_tab.tts_command, text);
+        }
+    }
+
+    // The opponent's last move (in puzzles) and the pieces of each side, unless they're hidden
+    fn position_text(&self, with_pieces: bool) -> String {
+        let mut lines = Vec::new();
+        if self.game_mode == config::GameMode::Puzzle && !self.opponent_move_text.is_empty() {
+            lines.push(lang::tr(&self.lang, "opponent_played") + &self.opponent_move_text);
+        }
+        if with_pieces {
+            let (board, _, _) = self.shown_position();
+            lines.push(accessibility::describe_position(&board, &self.lang));
+        }
+        lines.join("\n")
+    }
+
+    // The board as it's shown: the position, the last move's squares and if White is at the bottom
+    fn shown_position(&self) -> (Board, Vec<Square>, bool) {
+        let (board, last_move, bottom_side) = match self.game_mode {
+            config::GameMode::Puzzle => {
+                let last_move = self.last_move_from.zip(self.last_move_to).map(|(from, to)| ChessMove::new(from, to, None));
+                (self.board, last_move, self.puzzle_tab.current_puzzle_side)
+            } config::GameMode::Analysis => {
+                let last_move = self.last_move_from.zip(self.last_move_to).map(|(from, to)| ChessMove::new(from, to, None));
+                (self.analysis.current_position(), last_move, self.puzzle_tab.current_puzzle_side)
+            } config::GameMode::PlayEngine => {
+                (self.analysis.current_position(), self.play_moves.last().copied(), self.puzzle_tab.current_puzzle_side)
+            } config::GameMode::Editor => {
+                (self.editor_board(), self.editor_tab.solution.last().copied(), Color::White)
+            }
+        };
+        let highlights = last_move.map(|last_move| vec![last_move.get_source(), last_move.get_dest()]).unwrap_or_default();
+        (board, highlights, (bottom_side == Color::White) ^ self.settings_tab.flip_board)
+    }
+
+    fn is_player_turn(&self) -> bool {
+        self.game_mode == config::GameMode::PlayEngine &&
+            self.analysis.current_position().status() == BoardStatus::Ongoing &&
+            self.analysis.side_to_move() == self.play_side
+    }
+
+    fn start_play_engine(&mut self, board: Board) {
+        self.analysis = Game::new_with_board(board);
+        // A takeback stops at the position the game started from
+        self.analysis_history = vec![board];
+        self.play_start = board;
+        self.play_moves = Vec::new();
+        self.play_side = board.side_to_move();
+        self.from_square = None;
+        self.engine_error = None;
+        self.engine_eval = String::new();
+        self.engine_move = String::new();
+        self.engine_bar = None;
+        self.engine_pv.clear();
+        if Path::new(&self.engine.engine_path).exists() {
+            // A different limit or position gives the engine subscription a new identity,
+            // so if an analysis engine is running it's replaced by a new one.
+            self.engine.search_up_to = self.settings_tab.play_engine_limit.clone();
+            self.engine.position = san_correct_ep(board.to_string());
+            self.engine_sender = None;
+            self.engine_state = EngineStatus::Started;
+            self.play_status = lang::tr(&self.lang, "play_your_move");
+        } else {
+            self.play_status = lang::tr(&self.lang, "play_no_engine");
+        }
+    }
+
+    fn stop_play_engine(&mut self) {
+        // Dropping the subscription kills the engine process
+        self.engine_state = EngineStatus::TurnedOff;
+        self.engine_sender = None;
+        self.engine.search_up_to = self.settings_tab.saved_configs.engine_limit.clone();
+        self.engine_eval = String::new();
+        self.engine_move = String::new();
+        self.engine_bar = None;
+        self.engine_pv.clear();
+    }
+
+    // Applies a move of either side in the game against the engine,
+    // and asks the engine for its reply if the game isn't over.
+    fn make_play_move(&mut self, chess_move: ChessMove) {
+        if !self.analysis.make_move(chess_move) {
+            return;
+        }
+        self.analysis_history.push(self.analysis.current_position());
+        self.play_moves.push(chess_move);
+        if self.settings_tab.saved_configs.play_sound {
+            if let Some(audio) = &self.sound_playback {
+                audio.play_audio(SoundPlayback::ONE_PIECE_SOUND);
+            }
+        }
+        let position = self.analysis.current_position();
+        match position.status() {
+            BoardStatus::Checkmate => {
+                if position.side_to_move() == self.play_side {
+                    self.play_status = lang::tr(&self.lang, "play_you_lost");
+                } else {
+                    self.play_status = lang::tr(&self.lang, "play_you_won");
+                }
+            } BoardStatus::Stalemate => {
+                self.play_status = lang::tr(&self.lang, "play_draw");
+            } BoardStatus::Ongoing => {
+                if position.side_to_move() == self.play_side {
+                    self.play_status = lang::tr(&self.lang, "play_your_move");
+                } else {
+                    self.play_status = lang::tr(&self.lang, "play_engine_thinking");
+                    if let Some(sender) = &self.engine_sender {
+                        if let Err(e) = sender.blocking_send(san_correct_ep(position.to_string())) {
+                            eprintln!("Lost contact with the engine: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn play_result(&self) -> &str {
+        let position = self.analysis.current_position();
+        match position.status() {
+            BoardStatus::Checkmate => {
+                if position.side_to_move() == Color::White { "0-1" } else { "1-0" }
+            } BoardStatus::Stalemate => "1/2-1/2",
+            BoardStatus::Ongoing => "*",
+        }
+    }
+
     fn inc_puzzle_counter(&mut self) {
         self.puzzle_tab.current_puzzle += 1;
         self.puzzle_number_ui = (self.puzzle_tab.current_puzzle + 1).to_string();
@@ -507,26 +981,30 @@ impl OfflinePuzzles {
 
     fn update(&mut self, message: self::Message) -> Task<Message> {
         match (self.from_square, message) {
-            (None, Message::SelectSquare(pos)) => {
+            (_, Message::SelectSquare(pos)) if self.game_mode == config::GameMode::Editor && self.editor_tab.is_setting_up() => {
+                self.editor_tab.place_piece(pos);
+                Task::none()
+            } (None, Message::SelectSquare(pos)) => {
                 let side =
                     match self.game_mode {
-                        config::GameMode::Analysis => { self.analysis.side_to_move() }
+                        config::GameMode::Analysis | config::GameMode::PlayEngine => { self.analysis.side_to_move() }
                         config::GameMode::Puzzle => { self.board.side_to_move() }
+                        config::GameMode::Editor => { self.editor_board().side_to_move() }
                     };
                 let color =
                     match self.game_mode {
-                        config::GameMode::Analysis => { self.analysis.current_position().color_on(pos) }
+                        config::GameMode::Analysis | config::GameMode::PlayEngine => { self.analysis.current_position().color_on(pos) }
                         config::GameMode::Puzzle => { self.board.color_on(pos) }
+                        config::GameMode::Editor => { self.editor_board().color_on(pos) }
                     };
 
-                if (self.puzzle_tab.game_status == GameStatus::Playing || self.game_mode == config::GameMode::Analysis) && color == Some(side) {
+                if self.can_move() && color == Some(side) {
                     self.hint_square = None;
                     self.from_square = Some(pos);
                 }
                 Task::none()
             } (Some(from), Message::SelectSquare(to)) if from != to => {
-                self.verify_and_make_move(from, to);
-                Task::none()
+                self.verify_and_make_move(from, to, self.search_tab.piece_to_promote_to)
             } (Some(_), Message::SelectSquare(to)) => {
                 self.from_square = Some(to);
                 Task::none()
@@ -536,26 +1014,45 @@ impl OfflinePuzzles {
             } (_, Message::Settings(message)) => {
                 self.settings_tab.update(message)
             } (_, Message::SelectMode(message)) => {
+                let previous_mode = self.game_mode;
                 self.game_mode = message;
-                if message == config::GameMode::Analysis {
+                if previous_mode == config::GameMode::PlayEngine && message != config::GameMode::PlayEngine {
+                    self.stop_play_engine();
+                }
+                if message == config::GameMode::PlayEngine {
+                    // Continue from whatever position is on the board
+                    let board = if previous_mode == config::GameMode::Puzzle {
+                        self.board
+                    } else {
+                        self.analysis.current_position()
+                    };
+                    self.start_play_engine(board);
+                } else if message == config::GameMode::Analysis {
                     self.analysis = Game::new_with_board(self.board);
+                    // Rebuilt from the puzzle, the history may be of a position analyzed before
+                    self.analysis_history = self.puzzle_tab.session.as_ref().map_or_else(Vec::new, PuzzleSession::positions);
+                    if self.analysis_history.last() != Some(&self.board) {
+                        self.analysis_history.push(self.board);
+                    }
+                    self.analysis_base_len = self.analysis_history.len();
                 } else {
                     if self.engine_state != EngineStatus::TurnedOff {
                         if let Some(sender) = &self.engine_sender {
-                            sender.blocking_send(String::from(eval::STOP_COMMAND)).expect("Error stopping engine.");
+                            if let Err(e) = sender.blocking_send(String::from(eval::STOP_COMMAND)) {
+                                eprintln!("Lost contact with the engine: {}", e);
+                            }
                         }
                     }
-                    self.analysis_history.truncate(self.puzzle_tab.current_puzzle_move);
+                    if message == config::GameMode::Editor {
+                        self.from_square = None;
+                        self.active_tab = TabId::Editor;
+                    }
                 }
                 Task::none()
             } (_, Message::ShowHint) => {
-                let moves = self.puzzle_tab.puzzles[self.puzzle_tab.current_puzzle].moves.split_whitespace().collect::<Vec<&str>>();
-                if !moves.is_empty() && moves.len() > self.puzzle_tab.current_puzzle_move {
-                    self.hint_square = Some(Square::from_str(&moves[self.puzzle_tab.current_puzzle_move][..2]).unwrap());
-                } else {
-                    self.hint_square = None;
+                if self.pending_reply.is_none() {
+                    self.hint_square = self.puzzle_tab.session.as_ref().and_then(PuzzleSession::hint);
                 }
-
                 Task::none()
             } (_, Message::ShowNextPuzzle) => {
                 self.inc_puzzle_counter();
@@ -568,26 +1065,114 @@ impl OfflinePuzzles {
                 }
                 Task::none()
             } (_, Message::GoBackMove) => {
-                if self.game_mode == config::GameMode::Analysis && self.analysis_history.len() > self.puzzle_tab.current_puzzle_move {
+                if self.game_mode == config::GameMode::Analysis && self.analysis_history.len() > self.analysis_base_len {
                     self.analysis_history.pop();
                     self.analysis = Game::new_with_board(*self.analysis_history.last().unwrap());
+                    self.engine_pv.clear();
                     if let Some(sender) = &self.engine_sender {
                         if let Err(e) = sender.blocking_send(san_correct_ep(self.analysis.current_position().to_string())) {
                             eprintln!("Lost contact with the engine: {}", e);
                         }
                     }
+                } else if self.game_mode == config::GameMode::PlayEngine && !self.play_moves.is_empty() {
+                    // Take back the engine's reply too, so it's the player's turn again
+                    let plies = if self.analysis.side_to_move() == self.play_side && self.play_moves.len() > 1 { 2 } else { 1 };
+                    for _ in 0..plies {
+                        self.play_moves.pop();
+                        self.analysis_history.pop();
+                    }
+                    self.analysis = Game::new_with_board(*self.analysis_history.last().unwrap());
+                    self.from_square = None;
+                    self.play_status = if self.analysis.side_to_move() == self.play_side {
+                        lang::tr(&self.lang, "play_your_move")
+                    } else {
+                        lang::tr(&self.lang, "play_engine_thinking")
+                    };
                 }
                 Task::none()
             } (_, Message::RedoPuzzle) => {
                 self.load_puzzle(false);
                 Task::none()
+            } (_, Message::Peek) => {
+                if !self.peeking {
+                    if let Some(session) = &mut self.puzzle_tab.session {
+                        session.record_peek();
+                    }
+                }
+                self.peeking = !self.peeking;
+                Task::none()
+            } (_, Message::ReadPosition) => {
+                // Hearing the hidden pieces is like seeing them
+                if self.blindfold() != config::Blindfold::Off {
+                    if let Some(session) = &mut self.puzzle_tab.session {
+                        session.record_peek();
+                    }
+                }
+                self.announce(&self.position_text(true));
+                Task::none()
+            } (_, Message::MarkStart(square)) => {
+                self.mark_start = Some(square);
+                Task::none()
+            } (_, Message::MarkEnd(square)) => {
+                // Positions being set up in the editor aren't marked
+                if let Some(from) = self.mark_start.take().filter(|_| self.game_mode != config::GameMode::Editor) {
+                    let (board, _, _) = self.shown_position();
+                    let position = overlay::position_key(&board);
+                    let color = overlay::MarkColor::from_modifiers(self.modifiers);
+                    let marks = self.marks.entry(position.clone()).or_default();
+                    if from == square {
+                        marks.toggle_circle(square, color);
+                    } else {
+                        marks.toggle_arrow(from, square, color);
+                    }
+                    // Without a puzzle they're only kept until the next one is loaded
+                    if !self.marks_puzzle_id.is_empty() && !db::save_marks(&self.marks_puzzle_id, &position, &marks.to_pgn_comment()) {
+                        eprintln!("Error saving the marks");
+                    }
+                }
+                Task::none()
+            } (_, Message::ClearMarks) => {
+                let (board, _, _) = self.shown_position();
+                let position = overlay::position_key(&board);
+                if self.marks.remove(&position).is_some() && !self.marks_puzzle_id.is_empty() && !db::save_marks(&self.marks_puzzle_id, &position, "") {
+                    eprintln!("Error removing the marks");
+                }
+                Task::none()
+            } (_, Message::OpponentReply(reply_id)) => {
+                if reply_id == self.reply_id {
+                    if let Some(reply) = self.pending_reply.take() {
+                        if self.settings_tab.saved_configs.play_sound {
+                            if let Some(audio) = &self.sound_playback {
+                                audio.play_audio(SoundPlayback::ONE_PIECE_SOUND);
+                            }
+                        }
+                        self.show_reply(reply);
+                    }
+                }
+                Task::none()
+            } (_, Message::AnimationTick(now)) => {
+                if self.animation.is_some_and(|animation| animation.progress(now) >= 1.) {
+                    self.animation = None;
+                }
+                Task::none()
+            } (_, Message::BlindfoldTick(now)) => {
+                let seconds = self.settings_tab.blindfold_seconds.parse::<u64>().unwrap_or(0);
+                if self.blindfold_shown_at.is_some_and(|shown_at| now.duration_since(shown_at).as_secs() >= seconds) {
+                    self.blindfold_shown_at = None;
+                }
+                Task::none()
             } (_, Message::LoadPuzzle(puzzles_vec)) => {
                 self.from_square = None;
                 self.search_tab.show_searching_msg = false;
+                if self.game_mode == config::GameMode::PlayEngine {
+                    self.stop_play_engine();
+                }
                 self.game_mode = config::GameMode::Puzzle;
                 if self.engine_state != EngineStatus::TurnedOff {
                     if let Some(sender) = &self.engine_sender {
-                        sender.blocking_send(String::from(eval::STOP_COMMAND)).expect("Error stopping engine.");
+                        if let Err(e) = sender.blocking_send(String::from(eval::STOP_COMMAND)) {
+                            eprintln!("Lost contact with the engine: {}", e);
+                        }
                     }
                 }
                 if let Some(puzzles_vec) = puzzles_vec {
@@ -602,6 +1187,7 @@ impl OfflinePuzzles {
                         self.board = Board::default();
                         self.last_move_from = None;
                         self.last_move_to = None;
+                        self.puzzle_tab.session = None;
                         self.puzzle_tab.game_status = GameStatus::NoPuzzles;
                         self.puzzle_status = lang::tr(&self.lang, "no_puzzle_found");
                     }
@@ -609,6 +1195,7 @@ impl OfflinePuzzles {
                     self.board = Board::default();
                     self.last_move_from = None;
                     self.last_move_to = None;
+                    self.puzzle_tab.session = None;
                     self.puzzle_tab.game_status = GameStatus::NoPuzzles;
                     self.puzzle_status = lang::tr(&self.lang, "no_puzzle_found");
                 }
@@ -622,6 +1209,7 @@ impl OfflinePuzzles {
                     self.search_tab.theme.lang = self.lang;
                     self.search_tab.opening.lang = self.lang;
                     self.puzzle_tab.lang = self.lang;
+                    self.editor_tab.lang = self.lang;
                     self.settings_tab.saved_configs = settings;
                     self.piece_imgs = get_image_handles(&self.settings_tab.piece_theme);
                     self.search_tab.promotion_piece_img = search_tab::gen_piece_vec(&self.settings_tab.piece_theme);
@@ -630,11 +1218,66 @@ impl OfflinePuzzles {
             }
              (_, Message::PuzzleInfo(message)) => {
                 self.puzzle_tab.update(message)
+            } (_, Message::Editor(message)) => {
+                self.from_square = None;
+                self.editor_tab.update(message)
+            } (_, Message::AnalyzePosition(board)) => {
+                if self.game_mode == config::GameMode::PlayEngine {
+                    self.stop_play_engine();
+                }
+                self.game_mode = config::GameMode::Analysis;
+                self.from_square = None;
+                self.analysis = Game::new_with_board(board);
+                // A takeback stops at the loaded position
+                self.analysis_history = vec![board];
+                self.analysis_base_len = 1;
+                self.engine_pv.clear();
+                self.engine.position = san_correct_ep(board.to_string());
+                if self.engine_state == EngineStatus::TurnedOff {
+                    Task::done(Message::StartEngine)
+                } else {
+                    if let Some(sender) = &self.engine_sender {
+                        if let Err(e) = sender.blocking_send(san_correct_ep(board.to_string())) {
+                            eprintln!("Lost contact with the engine: {}", e);
+                        }
+                    }
+                    Task::none()
+                }
             } (_, Message::Search(message)) => {
                 self.search_tab.update(message)
             } (_, Message::PuzzleInputIndexChange(puzzle_input)) => {
                 self.puzzle_number_ui = puzzle_input;
                 Task::none()
+            } (_, Message::MoveInputChange(move_input)) => {
+                self.move_input = move_input;
+                self.move_input_error.clear();
+                Task::none()
+            } (_, Message::SubmitMoveInput) => {
+                if !self.can_move() {
+                    return Task::none();
+                }
+                let (board, _, _) = self.shown_position();
+                match session::read_move(&board, &self.move_input, &config::piece_letters(&self.lang)) {
+                    Ok(movement) => {
+                        self.move_input.clear();
+                        self.from_square = None;
+                        self.hint_square = None;
+                        return self.verify_and_make_move(movement.get_source(), movement.get_dest(), movement.get_promotion().unwrap_or(Piece::Queen));
+                    } Err(MoveError::Unreadable) => {
+                        self.move_input_error = lang::tr(&self.lang, "move_unreadable");
+                    } Err(MoveError::Illegal) => {
+                        self.move_input_error = lang::tr(&self.lang, "move_illegal");
+                    } Err(MoveError::Ambiguous(moves)) => {
+                        let moves: Vec<String> = moves.iter().map(|movement| {
+                            config::coord_to_san(&board, movement.to_string(), &self.lang).unwrap_or(movement.to_string())
+                        }).collect();
+                        self.move_input_error = lang::tr(&self.lang, "move_ambiguous") + &moves.join(", ");
+                    }
+                }
+                if !self.move_input_error.is_empty() {
+                    self.announce(&self.move_input_error);
+                }
+                Task::none()
             } (_, Message::JumpToPuzzle) => {
                 // Test if puzzle index typed is valid
                 let puzzle_index = self.puzzle_number_ui.parse::<usize>();
@@ -646,39 +1289,91 @@ impl OfflinePuzzles {
                 }
                 self.load_puzzle(false);
                 Task::none()
-            } (_, Message::ScreenshotCreated(screenshot)) => {
-                Task::perform(screenshot_save_dialog(screenshot), Message::SaveScreenshot)
-            } (_, Message::SaveScreenshot(img_and_path)) => {
-                let (crop_height, crop_width) = if self.settings_tab.show_coordinates {
-                    (self.settings_tab.window_height - 118., self.settings_tab.window_height - 123.)
-                } else {
-                    (self.settings_tab.window_height - 128., self.settings_tab.window_height - 128.)
-                };
-                if let Some(img_and_path) = img_and_path {
-                    let screenshot = img_and_path.0;
-                    let path = img_and_path.1;
-                    let crop = screenshot.crop(Rectangle::<u32> {
-                        x: 0,
-                        y: 0,
-                        width: crop_width as u32,
-                        height: crop_height as u32,
-                    });
-                    if let Ok (screenshot) = crop {
-                        let img = RgbaImage::from_raw(screenshot.size.width, screenshot.size.height, screenshot.rgba.to_vec());
-                        if let Some(image) = img {
-                            let _ = image.save_with_format(path, image::ImageFormat::Jpeg);
-                        }
+            } (_, Message::SaveDiagram(file_path)) => {
+                if let Some(file_path) = file_path {
+                    let (board, highlights, white_at_bottom) = self.shown_position();
+                    let saved = diagram::DiagramRenderer::new(&self.settings_tab.piece_theme, &self.settings_tab.board_theme)
+                        .and_then(|renderer| renderer.save(&file_path, &board, &highlights, white_at_bottom));
+                    if let Err(file_path) = saved {
+                        self.puzzle_status = lang::tr(&self.lang, "error_saving_diagram") + &file_path;
                     }
                 }
                 Task::none()
+            } (_, Message::ExportDiagrams(folder)) => {
+                if let Some(folder) = folder {
+                    Task::perform(
+                        diagram::export_puzzles(self.puzzle_tab.puzzles.clone(), folder, self.puzzle_tab.diagram_format, self.settings_tab.piece_theme, self.settings_tab.board_theme),
+                        Message::DiagramsExported)
+                } else {
+                    Task::none()
+                }
+            } (_, Message::DiagramsExported(result)) => {
+                self.puzzle_status = match result {
+                    Ok(saved) => lang::tr(&self.lang, "diagrams_exported") + &saved.to_string(),
+                    Err(file_path) => lang::tr(&self.lang, "error_saving_diagram") + &file_path,
+                };
+                Task::none()
             } (_, Message::ExportPDF(file_path)) => {
                 if let Some(file_path) = file_path {
-                    export::to_pdf(&self.puzzle_tab.puzzles, self.settings_tab.export_pgs.parse::<i32>().unwrap(), &self.lang, file_path);
+                    let layout = export::PdfLayout {
+                        diagrams_per_page: self.settings_tab.pdf_diagrams_per_page,
+                        square_size: self.settings_tab.pdf_square_size.parse::<i32>().unwrap(),
+                        page_size: self.settings_tab.pdf_page_size,
+                        landscape: self.settings_tab.pdf_landscape,
+                        answer_key: self.settings_tab.pdf_answer_key,
+                        piece_theme: self.settings_tab.piece_theme,
+                        board_theme: self.settings_tab.board_theme,
+                    };
+                    self.puzzle_status = match export::to_pdf(&self.puzzle_tab.puzzles, self.settings_tab.export_pgs.parse::<i32>().unwrap(), &layout, &self.lang, file_path) {
+                        Ok(written) => lang::tr(&self.lang, "pdf_exported") + &written.to_string(),
+                        Err(file_path) => lang::tr(&self.lang, "error_exporting_pdf") + &file_path,
+                    };
+                }
+                Task::none()
+            } (_, Message::OpenPuzzlesPGN(file_path)) => {
+                match file_path.map(|file_path| import::load_exported_pgn(&file_path, &self.lang)) {
+                    Some(Ok(puzzles)) => Task::done(Message::LoadPuzzle(Some(puzzles))),
+                    Some(Err(file_path)) => {
+                        self.puzzle_status = lang::tr(&self.lang, "error_reading_pgn") + &file_path;
+                        Task::none()
+                    } None => Task::none(),
+                }
+            } (_, Message::ExportAnki(file_path)) => {
+                if let Some(file_path) = file_path {
+                    Task::perform(
+                        export::to_anki(self.puzzle_tab.puzzles.clone(), self.lang, file_path, self.settings_tab.piece_theme, self.settings_tab.board_theme),
+                        Message::AnkiExported)
+                } else {
+                    Task::none()
+                }
+            } (_, Message::AnkiExported(result)) => {
+                self.puzzle_status = match result {
+                    Ok(written) => lang::tr(&self.lang, "anki_exported") + &written.to_string(),
+                    Err(file_path) => lang::tr(&self.lang, "error_exporting_anki") + &file_path,
+                };
+                Task::none()
+            } (_, Message::ExportHTML(file_path)) => {
+                if let Some(file_path) = file_path {
+                    self.puzzle_status = match export::to_html(&self.puzzle_tab.puzzles, &self.lang, file_path, &self.settings_tab.piece_theme, &self.settings_tab.board_theme) {
+                        Ok(written) => lang::tr(&self.lang, "html_exported") + &written.to_string(),
+                        Err(file_path) => lang::tr(&self.lang, "error_exporting_html") + &file_path,
+                    };
+                }
+                Task::none()
+            } (_, Message::ExportCSV(file_path)) => {
+                if let Some(file_path) = file_path {
+                    self.puzzle_status = match export::to_csv(&self.puzzle_tab.puzzles, file_path) {
+                        Ok(written) => lang::tr(&self.lang, "csv_exported") + &written.to_string(),
+                        Err(file_path) => lang::tr(&self.lang, "error_exporting_csv") + &file_path,
+                    };
                 }
                 Task::none()
             } (_, Message::ExportPGN(file_path)) => {
                 if let Some(file_path) = file_path {
-                    export::to_pgn(&self.puzzle_tab.puzzles, &self.lang, file_path);
+                    self.puzzle_status = match export::to_pgn(&self.puzzle_tab.puzzles, &self.lang, file_path) {
+                        Ok(written) => lang::tr(&self.lang, "pgn_exported") + &written.to_string(),
+                        Err(file_path) => lang::tr(&self.lang, "error_exporting_pgn") + &file_path,
+                    };
                 }
                 Task::none()
             } (_, Message::EventOccurred(event)) => {
@@ -687,10 +1382,11 @@ impl OfflinePuzzles {
                         EngineStatus::TurnedOff => {
                             iced::window::is_maximized(self.window_id.unwrap()).map(Message::SaveMaximizedStatusAndExit)
                         } _ => {
-                            if let Some(sender) = &self.engine_sender {
-                                sender.blocking_send(String::from(eval::EXIT_APP_COMMAND)).expect("Error stopping engine.");
+                            // If the engine is already gone there's nobody to confirm the exit, so we close right away
+                            match &self.engine_sender {
+                                Some(sender) if sender.blocking_send(String::from(eval::EXIT_APP_COMMAND)).is_ok() => Task::none(),
+                                _ => iced::window::is_maximized(self.window_id.unwrap()).map(Message::SaveMaximizedStatusAndExit)
                             }
-                            Task::none()
                         }
                     }
                 } else if let Event::Window(window::Event::Resized(size)) = event {
@@ -699,6 +1395,15 @@ impl OfflinePuzzles {
                         self.settings_tab.window_height = size.height;
                     }
                     Task::none()
+                } else if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+                    // They pick the color of the marks drawn with the mouse
+                    self.modifiers = modifiers;
+                    Task::none()
+                } else if let Event::Keyboard(keyboard::Event::KeyPressed { modified_key, modifiers, .. }) = event {
+                    match shortcuts::key_name(&modified_key, modifiers) {
+                        Some(key) => self.handle_shortcut(key),
+                        None => Task::none(),
+                    }
                 } else {
                     Task::none()
                 }
@@ -719,10 +1424,16 @@ impl OfflinePuzzles {
                         if Path::new(&self.engine.engine_path).exists() {
                             self.engine.position = san_correct_ep(self.analysis.current_position().to_string());
                             self.engine_state = EngineStatus::Started;
+                            self.engine_error = None;
+                        } else {
+                            self.engine_error = Some(EngineError::NotFound(self.engine.engine_path.clone()));
                         }
                     } _ => {
                         if let Some(sender) = &self.engine_sender {
-                            sender.blocking_send(String::from(eval::STOP_COMMAND)).expect("Error stopping engine.");
+                            if let Err(e) = sender.blocking_send(String::from(eval::STOP_COMMAND)) {
+                                eprintln!("Lost contact with the engine: {}", e);
+                                self.engine_state = EngineStatus::TurnedOff;
+                            }
                             self.engine_sender = None;
                         }
                     }
@@ -736,17 +1447,138 @@ impl OfflinePuzzles {
                 } else {
                     self.engine_eval = String::new();
                     self.engine_move = String::new();
+                    self.engine_bar = None;
+                    self.engine_pv.clear();
                     Task::none()
                 }
             } (_, Message::EngineReady(sender)) => {
+                // The player may have moved while the engine was still starting
+                if self.game_mode == config::GameMode::PlayEngine && !self.play_moves.is_empty() &&
+                        self.analysis.current_position().status() == BoardStatus::Ongoing &&
+                        self.analysis.side_to_move() != self.play_side {
+                    if let Err(e) = sender.blocking_send(san_correct_ep(self.analysis.current_position().to_string())) {
+                        eprintln!("Lost contact with the engine: {}", e);
+                    }
+                }
                 self.engine_sender = Some(sender);
                 Task::none()
+            } (_, Message::EngineError(error)) => {
+                // The old sender belongs to the process that just failed
+                self.engine_sender = None;
+                if self.game_mode == config::GameMode::PlayEngine && error == EngineError::GaveUp {
+                    // The error below the board already says why the engine isn't replying
+                    self.play_status = String::new();
+                }
+                self.engine_error = Some(error);
+                Task::none()
+            } (_, Message::SendUciCommand(command)) => {
+                match &self.engine_sender {
+                    Some(sender) => {
+                        if let Err(e) = sender.blocking_send(eval::raw_command(&command)) {
+                            eprintln!("Lost contact with the engine: {}", e);
+                        }
+                    } None => {
+                        eval::log_line(eval::LogSource::Supervisor, &(String::from("engine not running, command not sent: ") + &command));
+                    }
+                }
+                Task::none()
+            } (_, Message::StartVerification) => {
+                if self.puzzle_tab.puzzles.is_empty() {
+                    return Task::none();
+                }
+                self.puzzle_tab.verify_report = None;
+                if Path::new(&self.settings_tab.engine_path).exists() {
+                    self.verify_runs += 1;
+                    self.verify_job = Some(verify::VerifyJob {
+                        id: self.verify_runs,
+                        engine_path: self.settings_tab.engine_path.clone(),
+                        depth: self.settings_tab.verify_depth.parse().unwrap_or(16),
+                        margin: self.settings_tab.verify_margin.parse().unwrap_or(50),
+                        puzzles: Arc::new(self.puzzle_tab.puzzles.clone()),
+                    });
+                    self.puzzle_tab.verifying = true;
+                    self.puzzle_tab.verify_status = lang::tr(&self.lang, "verifying") + &format!("0 / {}", self.puzzle_tab.puzzles.len());
+                } else {
+                    self.puzzle_tab.verify_status = lang::tr(&self.lang, "play_no_engine");
+                }
+                Task::none()
+            } (_, Message::CancelVerification) => {
+                // Dropping the subscription kills the engine process
+                self.verify_job = None;
+                self.puzzle_tab.verifying = false;
+                self.puzzle_tab.verify_status = String::new();
+                Task::none()
+            } (_, Message::VerifyProgress(done, total)) => {
+                self.puzzle_tab.verify_status = lang::tr(&self.lang, "verifying") + &format!("{done} / {total}");
+                Task::none()
+            } (_, Message::VerifyFinished(result)) => {
+                self.verify_job = None;
+                self.puzzle_tab.verifying = false;
+                match result {
+                    Ok(issues) => {
+                        self.puzzle_tab.verify_status = lang::tr(&self.lang, "verify_done") + &issues.len().to_string();
+                        self.puzzle_tab.verify_report = Some(issues);
+                    } Err(e) => {
+                        self.puzzle_tab.verify_status = e.localized(&self.lang);
+                    }
+                }
+                Task::none()
+            } (_, Message::ExportVerifyReport(file_path)) => {
+                if let (Some(file_path), Some(report)) = (file_path, &self.puzzle_tab.verify_report) {
+                    self.puzzle_tab.verify_status = match export::verify_report_to_csv(report, file_path) {
+                        Ok(written) => lang::tr(&self.lang, "verify_report_exported") + &written.to_string(),
+                        Err(file_path) => lang::tr(&self.lang, "error_exporting_verify_report") + &file_path,
+                    };
+                }
+                Task::none()
+            } (_, Message::GenerateFromPgn(pgn_path)) => {
+                if let Some(pgn_path) = pgn_path {
+                    if Path::new(&self.settings_tab.engine_path).exists() {
+                        // The id is shared with the verification jobs, it only has to be unique
+                        self.verify_runs += 1;
+                        self.generate_job = Some(generate::GenerateJob {
+                            id: self.verify_runs,
+                            engine_path: self.settings_tab.engine_path.clone(),
+                            depth: self.settings_tab.verify_depth.parse().unwrap_or(16),
+                            pgn_path,
+                            player: self.search_tab.player_name.trim().to_string(),
+                        });
+                        self.search_tab.generating = true;
+                        self.search_tab.generate_status = lang::tr(&self.lang, "analyzing_games");
+                    } else {
+                        self.search_tab.generate_status = lang::tr(&self.lang, "play_no_engine");
+                    }
+                }
+                Task::none()
+            } (_, Message::CancelGeneration) => {
+                // The puzzles found so far are already saved
+                self.generate_job = None;
+                self.search_tab.generating = false;
+                Task::none()
+            } (_, Message::GenerateProgress(done, total, added)) => {
+                self.search_tab.generate_status = lang::tr(&self.lang, "analyzing_games") + &format!("{done} / {total}") +
+                    "\n" + &lang::tr(&self.lang, "new_puzzles") + &added.to_string();
+                Task::none()
+            } (_, Message::GenerateFinished(result)) => {
+                self.generate_job = None;
+                self.search_tab.generating = false;
+                self.search_tab.generate_status = match result {
+                    Ok(added) => lang::tr(&self.lang, "new_puzzles") + &added.to_string(),
+                    Err(e) => e.localized(&self.lang),
+                };
+                self.search_tab.sources = db::get_my_puzzles_sources();
+                Task::none()
             } (_, Message::UpdateEval(eval)) => {
                 match self.engine_state {
                     EngineStatus::TurnedOff => {
                         Task::none()
+                    } _ if self.game_mode == config::GameMode::PlayEngine => {
+                        // No spoilers while playing against the engine
+                        Task::none()
                     } _ => {
-                        let (eval, best_move) = eval;
+                        let (eval, best_move, pv) = eval;
+                        // The engine scores are relative to the side to move
+                        let white_sign = if self.analysis.side_to_move() == Color::White { 1. } else { -1. };
                         if let Some(eval_str) = eval {
                             if eval_str.contains("Mate") {
                                 let tokens: Vec<&str> = eval_str.split_whitespace().collect();
@@ -754,23 +1586,33 @@ impl OfflinePuzzles {
                                 match distance_to_mate_num {
                                     1.. => {
                                         self.engine_eval = lang::tr(&self.lang, "mate_in") + &distance_to_mate_num.to_string();
+                                        self.engine_bar = Some(100. * white_sign);
                                     } 0 => {
                                         self.engine_eval = lang::tr(&self.lang, "mate");
                                         self.engine_move = String::from("");
+                                        // Mate on the board, so the side to move lost
+                                        self.engine_bar = Some(-100. * white_sign);
+                                        self.engine_pv.clear();
                                         return Task::none();
                                     } _ => {
                                         self.engine_eval = lang::tr(&self.lang, "mate_in") + &(-distance_to_mate_num).to_string();
+                                        self.engine_bar = Some(-100. * white_sign);
                                     }
                                 };
                             } else if self.analysis.side_to_move() == Color::White {
+                                self.engine_bar = eval_str.parse::<f32>().ok();
                                 self.engine_eval = eval_str;
                             } else {
                                 // Invert to keep the values relative to white,
                                 // like it's usually done in GUIs.
                                 let eval = (eval_str.parse::<f32>().unwrap() * -1.).to_string();
+                                self.engine_bar = eval.parse::<f32>().ok();
                                 self.engine_eval = eval.to_string().clone();
                             }
                         }
+                        if let Some(pv) = pv {
+                            self.engine_pv = pv;
+                        }
                         if let Some(best_move) = best_move {
                             if let Some(best_move) = config::coord_to_san(&self.analysis.current_position(), best_move, &self.lang) {
                                 self.engine_move = best_move;
@@ -779,6 +1621,29 @@ impl OfflinePuzzles {
                         Task::none()
                     }
                 }
+            } (_, Message::EngineBestMove(engine_move)) => {
+                if self.game_mode == config::GameMode::PlayEngine &&
+                        self.analysis.current_position().status() == BoardStatus::Ongoing &&
+                        self.analysis.side_to_move() != self.play_side && engine_move.len() >= 4 {
+                    if let (Ok(from), Ok(to)) = (Square::from_str(&engine_move[..2]), Square::from_str(&engine_move[2..4])) {
+                        let chess_move = ChessMove::new(from, to, PuzzleTab::check_promotion(&engine_move));
+                        if self.analysis.current_position().legal(chess_move) {
+                            self.make_play_move(chess_move);
+                            self.animate(chess_move);
+                        }
+                    }
+                }
+                Task::none()
+            } (_, Message::SavePlayedGame) => {
+                Task::perform(PuzzleTab::export(), Message::ExportGamePGN)
+            } (_, Message::ExportGamePGN(file_path)) => {
+                if let Some(file_path) = file_path {
+                    self.play_status = match export::game_to_pgn(&self.play_start, &self.play_moves, self.play_result(), &self.lang, file_path) {
+                        Ok(()) => lang::tr(&self.lang, "game_saved"),
+                        Err(e) => lang::tr(&self.lang, "error_saving_game") + &e,
+                    };
+                }
+                Task::none()
             } (_, Message::StartDBDownload) => {
                 self.downloading_db = true;
                 Task::none()
@@ -794,7 +1659,6 @@ impl OfflinePuzzles {
                 Task::none()
             } (_, Message::WindowInitialized(id)) => {
                 self.window_id = id;
-                self.puzzle_tab.window_id = id;
                 iced::window::maximize(self.window_id.unwrap(), self.settings_tab.maximized)
             } (_, Message::MinimizeUI) => {
                 if self.mini_ui {
@@ -812,7 +1676,8 @@ impl OfflinePuzzles {
                     iced::window::resize(self.window_id.unwrap(), new_size)
                 }
             } (_, Message::DropPiece(square, cursor_pos, _bounds)) => {
-                if self.puzzle_tab.game_status == GameStatus::Playing || self.game_mode == config::GameMode::Analysis {
+                if self.puzzle_tab.game_status == GameStatus::Playing || self.game_mode == config::GameMode::Analysis ||
+                        self.game_mode == config::GameMode::Editor || self.is_player_turn() {
                     iced_drop::zones_on_point(
                         move |zones| Message::HandleDropZones(square, zones),
                         cursor_pos,
@@ -823,10 +1688,14 @@ impl OfflinePuzzles {
                     Task::none()
                 }
             } (_, Message::HandleDropZones(from, zones)) => {
-                if !zones.is_empty() {
+                if self.game_mode == config::GameMode::Editor && self.editor_tab.is_setting_up() {
+                    // Dropping a piece outside of the board removes it
+                    let to = zones.first().and_then(|(id, _)| self.square_ids.get(id)).copied();
+                    self.editor_tab.move_piece(from, to);
+                } else if !zones.is_empty() {
                     let id: &GenericId = &zones[0].0.clone();
                     if let Some(to) = self.square_ids.get(id) {
-                        self.verify_and_make_move(from, *to);
+                        return self.verify_and_make_move(from, *to, self.search_tab.piece_to_promote_to);
                     }
                 }
                 Task::none()
@@ -835,23 +1704,29 @@ impl OfflinePuzzles {
     }
 
     fn subscription(&self) -> Subscription<Message> {
+        let mut subscriptions = vec![event::listen().map(Message::EventOccurred)];
         match self.engine_state {
             EngineStatus::TurnedOff => {
                 if self.downloading_db {
-                    Subscription::batch(vec![
-                        download_lichess_db(),
-                        event::listen().map(Message::EventOccurred)
-                    ])
-                } else {
-                    event::listen().map(Message::EventOccurred)
+                    subscriptions.push(download_lichess_db());
                 }
             } _ => {
-                Subscription::batch(vec![
-                    Engine::run_engine(self.engine.clone()),
-                    event::listen().map(Message::EventOccurred)
-                ])
+                subscriptions.push(Engine::run_engine(self.engine.clone()));
             }
         }
+        if let Some(verify_job) = &self.verify_job {
+            subscriptions.push(verify_job.clone().run());
+        }
+        if let Some(generate_job) = &self.generate_job {
+            subscriptions.push(generate_job.clone().run());
+        }
+        if self.blindfold_shown_at.is_some() {
+            subscriptions.push(iced::time::every(std::time::Duration::from_millis(250)).map(Message::BlindfoldTick));
+        }
+        if self.animation.is_some() {
+            subscriptions.push(iced::time::every(std::time::Duration::from_millis(16)).map(Message::AnimationTick));
+        }
+        Subscription::batch(subscriptions)
     }
 
     fn view(&self) -> Element<'_, Message, Theme, iced::Renderer> {
@@ -863,6 +1738,24 @@ impl OfflinePuzzles {
             } else {
                 db::is_favorite(&self.puzzle_tab.puzzles[self.puzzle_tab.current_puzzle].puzzle_id)
             };
+            let (last_move_from, last_move_to, status) = if self.game_mode == config::GameMode::PlayEngine {
+                match self.play_moves.last() {
+                    Some(last_move) => (Some(last_move.get_source()), Some(last_move.get_dest()), &self.play_status),
+                    None => (None, None, &self.play_status),
+                }
+            } else if self.game_mode == config::GameMode::Editor {
+                match self.editor_tab.solution.last() {
+                    Some(last_move) => (Some(last_move.get_source()), Some(last_move.get_dest()), &self.puzzle_status),
+                    None => (None, None, &self.puzzle_status),
+                }
+            } else {
+                (self.last_move_from, self.last_move_to, &self.puzzle_status)
+            };
+            let marks = if self.game_mode == config::GameMode::Editor {
+                None
+            } else {
+                self.marks.get(&overlay::position_key(&self.shown_position().0))
+            };
             let resp = responsive(move |size| {
                 gen_view(
                     self.game_mode,
@@ -871,31 +1764,49 @@ impl OfflinePuzzles {
                     self.settings_tab.show_coordinates,
                     &self.board,
                     &self.analysis.current_position(),
+                    &self.editor_tab.pieces(),
                     self.from_square,
-                    self.last_move_from,
-                    self.last_move_to,
+                    self.cursor_square,
+                    last_move_from,
+                    last_move_to,
                     self.hint_square,
                     self.settings_tab.saved_configs.piece_theme,
-                    &self.puzzle_status,
+                    status,
+                    &self.move_input,
+                    &self.move_input_error,
+                    self.blindfold(),
+                    // The peek button (while the pieces are hidden or being peeked at)
+                    (self.peeking || self.blindfold() != config::Blindfold::Off).then_some(self.peeking),
+                    self.settings_tab.describe_position.then(|| self.position_text(self.blindfold() == config::Blindfold::Off)),
+                    !self.settings_tab.tts_command.trim().is_empty(),
+                    marks,
+                    self.animation.map(|animation| (animation.from, animation.to, animation.progress(iced::time::Instant::now()))),
                     is_fav,
                     has_more_puzzles,
                     has_previous,
                     self.analysis_history.len(),
+                    self.analysis_base_len,
+                    self.play_moves.len(),
                     &self.puzzle_number_ui,
                     self.puzzle_tab.puzzles.len(),
-                    self.puzzle_tab.current_puzzle_move,
                     self.puzzle_tab.game_status,
                     &self.active_tab,
                     &self.engine_eval,
                     &self.engine_move,
 
                     self.engine_state != EngineStatus::TurnedOff,
+                    if self.settings_tab.show_eval_bar { self.engine_bar } else { None },
+                    &self.engine_pv,
+                    self.settings_tab.engine_arrows.parse::<usize>().unwrap_or(0),
+                    self.engine_error.as_ref().map(|e| e.localized(&self.lang)),
                     self.search_tab.tab_label(),
                     self.settings_tab.tab_label(),
                     self.puzzle_tab.tab_label(),
+                    self.editor_tab.tab_label(),
                     self.search_tab.view(),
                     self.settings_tab.view(),
                     self.puzzle_tab.view(),
+                    self.editor_tab.view(),
                     &self.lang,
                     size,
                     self.mini_ui,
@@ -950,11 +1861,6 @@ impl OfflinePuzzles {
     }
 }
 
-pub async fn screenshot_save_dialog(img: Screenshot) -> Option<(Screenshot, String)> {
-    let file_path = AsyncFileDialog::new().add_filter("jpg", &["jpg", "jpeg"]).save_file().await;
-    file_path.map(|file_path| (img, file_path.path().display().to_string()))
-}
-
 fn gen_view<'a>(
     game_mode: config::GameMode,
     current_puzzle_side: Color,
@@ -962,31 +1868,48 @@ fn gen_view<'a>(
     show_coordinates: bool,
     board: &Board,
     analysis: &Board,
+    editor_pieces: &[Option<(Piece, Color)>; 64],
     from_square: Option<Square>,
+    cursor_square: Option<Square>,
     last_move_from: Option<Square>,
     last_move_to: Option<Square>,
     hint_square: Option<Square>,
     piece_theme: styles::PieceTheme,
     puzzle_status: &'a str,
+    move_input: &'a str,
+    move_input_error: &'a str,
+    blindfold: config::Blindfold,
+    peek_button: Option<bool>,
+    position_text: Option<String>,
+    can_read_aloud: bool,
+    marks: Option<&overlay::Marks>,
+    animation: Option<(Square, Square, f32)>,
     is_fav: bool,
     has_more_puzzles: bool,
     has_previous: bool,
     analysis_history_len: usize,
+    analysis_base_len: usize,
+    play_moves_len: usize,
     puzzle_number_ui: &'a str,
     total_puzzles: usize,
-    current_puzzle_move: usize,
     game_status: GameStatus,
     active_tab: &TabId,
     engine_eval: &str,
     engine_move: &str,
 
     engine_started: bool,
+    engine_bar: Option<f32>,
+    engine_pv: &[String],
+    engine_arrows: usize,
+    engine_error: Option<String>,
     search_tab_label: TabLabel,
     settings_tab_label: TabLabel,
     puzzle_tab_label: TabLabel,
+    editor_tab_label: TabLabel,
     search_tab: Element<'a, Message, Theme, iced::Renderer>,
     settings_tab: Element<'a, Message, Theme, iced::Renderer>,
     puzzle_tab: Element<'a, Message, Theme, iced::Renderer>,
+    editor_tab: Element<'a, Message, Theme, iced::Renderer>,
     lang: &lang::Language,
     size: Size,
     mini_ui: bool,
@@ -996,22 +1919,28 @@ fn gen_view<'a>(
 
     let font = piece_theme == PieceTheme::FontAlpha;
     let mut board_col = Column::new().spacing(0).align_x(Alignment::Center);
+    let mut board_grid = Column::new().spacing(0);
+    let mut rank_labels = Column::new().spacing(0).width(RANK_LABEL_WIDTH);
     let mut board_row = Row::new().spacing(0).align_y(Alignment::Center);
 
-    let is_white = (current_puzzle_side == Color::White) ^ flip_board;
+    // Positions in the editor are always shown from White's side (unless flipped)
+    let bottom_side = if game_mode == config::GameMode::Editor { Color::White } else { current_puzzle_side };
+    let is_white = (bottom_side == Color::White) ^ flip_board;
 
+    // The position's description takes about three lines
+    let description_height = if position_text.is_some() { 60. } else { 0. };
     //Reserve more space below the board if we'll show the engine eval
     let board_height =
         if engine_eval.is_empty() {
             if show_coordinates {
-                (size.height - 145.) / 8.
+                (size.height - 185. - description_height) / 8.
             } else {
-                (size.height - 135.) / 8.
+                (size.height - 175. - description_height) / 8.
             }
         } else if show_coordinates {
-            (size.height - 175.) / 8.
+            (size.height - 215. - description_height) / 8.
         } else {
-            (size.height - 165.) / 8.
+            (size.height - 205. - description_height) / 8.
         };
 
     let ranks;
@@ -1023,20 +1952,36 @@ fn gen_view<'a>(
         ranks = (0..8).collect::<Vec<i32>>();
         files = (0..8).rev().collect::<Vec<i32>>();
     };
+    let mut moving_piece = None;
     for rank in ranks {
         for file in &files {
             let pos = Square::make_square(Rank::from_index(rank as usize), File::from_index(*file as usize));
 
             let (piece, color) =
                 match game_mode {
-                    config::GameMode::Analysis => {
+                    config::GameMode::Analysis | config::GameMode::PlayEngine => {
                         (analysis.piece_on(pos),
                         analysis.color_on(pos))
                     } config::GameMode::Puzzle => {
                         (board.piece_on(pos),
                         board.color_on(pos))
+                    } config::GameMode::Editor => {
+                        (editor_pieces[pos.to_index()].map(|(piece, _)| piece),
+                        editor_pieces[pos.to_index()].map(|(_, color)| color))
                     }
                 };
+            let (piece, color) = match piece {
+                Some(piece) if blindfold.hides(piece) => (None, None),
+                _ => (piece, color),
+            };
+            // The moving piece is drawn over the board until it gets to its square
+            let (piece, color) = match animation {
+                Some((_, to, _)) if to == pos && !font => {
+                    moving_piece = piece.zip(color);
+                    (None, None)
+                }
+                _ => (piece, color),
+            };
 
             let mut text;
             let light_square = (rank + file) % 2 != 0;
@@ -1047,11 +1992,18 @@ fn gen_view<'a>(
                     last_move_from == Some(pos) ||
                     last_move_to == Some(pos)   ||
                     hint_square == Some(pos)
+                } else if game_mode == config::GameMode::PlayEngine || game_mode == config::GameMode::Editor {
+                    from_square == Some(pos)    ||
+                    last_move_from == Some(pos) ||
+                    last_move_to == Some(pos)
                 } else {
                     from_square == Some(pos)
                 };
+            let is_cursor = cursor_square == Some(pos);
             if font {
-                let square_style: styles::ChessBtn = if selected {
+                let square_style: styles::ChessBtn = if is_cursor {
+                    styles::btn_style_cursor_paper
+                } else if selected {
                     styles::btn_style_light_square
                 } else {
                     styles::btn_style_paper
@@ -1087,7 +2039,7 @@ fn gen_view<'a>(
                 }
 
                 board_row =
-                    board_row.push(Button::new(
+                    board_row.push(markable_square(Button::new(
                         Text::new(text)
                         .width(board_height)
                         .height(board_height)
@@ -1098,7 +2050,7 @@ fn gen_view<'a>(
                     ))
                 .padding(0)
                 .on_press(Message::SelectSquare(pos))
-                .style(square_style)
+                .style(square_style), pos)
                 );
             } else {
                 let square_style: styles::ChessBtn;
@@ -1121,92 +2073,139 @@ fn gen_view<'a>(
                         container_style = styles::container_style_dark_square;
                     }
                 }
+                // The keyboard's cursor is a frame over the square's usual color
+                let (square_style, container_style): (styles::ChessBtn, styles::ChessboardContainer) = match (is_cursor, light_square) {
+                    (true, true) => (styles::btn_style_cursor_light_square, styles::container_style_cursor_light_square),
+                    (true, false) => (styles::btn_style_cursor_dark_square, styles::container_style_cursor_dark_square),
+                    _ => (square_style, container_style),
+                };
 
                 if let Some(piece) = piece {
-                    let piece_index = if color.unwrap() == Color::White {
-                        match piece {
-                            Piece::Pawn => PieceWithColor::WhitePawn.index(),
-                            Piece::Rook => PieceWithColor::WhiteRook.index(),
-                            Piece::Knight => PieceWithColor::WhiteKnight.index(),
-                            Piece::Bishop => PieceWithColor::WhiteBishop.index(),
-                            Piece::Queen => PieceWithColor::WhiteQueen.index(),
-                            Piece::King => PieceWithColor::WhiteKing.index(),
-                        }
-                    } else {
-                        match piece {
-                            Piece::Pawn => PieceWithColor::BlackPawn.index(),
-                            Piece::Rook => PieceWithColor::BlackRook.index(),
-                            Piece::Knight => PieceWithColor::BlackKnight.index(),
-                            Piece::Bishop => PieceWithColor::BlackBishop.index(),
-                            Piece::Queen => PieceWithColor::BlackQueen.index(),
-                            Piece::King => PieceWithColor::BlackKing.index(),
-                        }
-                    };
+                    let piece_index = PieceWithColor::new(piece, color.unwrap()).index();
 
-                    board_row = board_row.push(
+                    board_row = board_row.push(markable_square(
                         container(
                             iced_drop::droppable(
                                 Svg::new(imgs[piece_index].clone()).width(board_height)
                                 .height(board_height)
                             ).drag_hide(true).drag_center(true).on_drop(move |point, rect| Message::DropPiece(pos, point, rect)).on_click(Message::SelectSquare(pos))
-                        ).style(container_style).id(board_ids[pos.to_index()].clone())
+                        ).style(container_style).id(board_ids[pos.to_index()].clone()), pos)
                      );
                 } else {
-                    board_row = board_row.push(container(
+                    board_row = board_row.push(markable_square(container(
                             Button::new(Text::new(""))
                             .width(board_height)
                             .height(board_height)
                             .on_press(Message::SelectSquare(pos))
                             .style(square_style)
-                        ).id(board_ids[pos.to_index()].clone())
+                        ).id(board_ids[pos.to_index()].clone()), pos)
                     );
                 }
             }
         }
 
         if show_coordinates {
-            board_row = board_row.push(
+            rank_labels = rank_labels.push(
                 Container::new(
                     Text::new((rank + 1).to_string()).size(15)
                 ).align_y(iced::alignment::Vertical::Bottom)
                 .align_x(iced::alignment::Horizontal::Right)
                 .padding(3)
+                .width(RANK_LABEL_WIDTH)
                 .height(board_height)
             );
         }
-        board_col = board_col.push(board_row);
+        board_grid = board_grid.push(board_row);
         board_row = Row::new().spacing(0).align_y(Alignment::Center);
     }
+
+    let mut arrows = Vec::new();
+    if game_mode == config::GameMode::Analysis && engine_arrows > 0 {
+        // Walk the PV on a copy of the board, so we never draw a move that
+        // doesn't belong to the position (e.g. a stale PV after a takeback).
+        let mut pv_board = *analysis;
+        for (index, pv_move) in engine_pv.iter().take(engine_arrows).enumerate() {
+            if pv_move.len() < 4 {
+                break;
+            }
+            let (Ok(from), Ok(to)) = (Square::from_str(&pv_move[..2]), Square::from_str(&pv_move[2..4])) else {
+                break;
+            };
+            let chess_move = ChessMove::new(from, to, PuzzleTab::check_promotion(pv_move));
+            if !pv_board.legal(chess_move) {
+                break;
+            }
+            if index == 0 {
+                arrows.push(overlay::Arrow::new(from, to, overlay::engine_arrow_color(piece_theme), 0.8));
+            } else {
+                arrows.push(overlay::Arrow::new(from, to, overlay::PV_ARROW_COLOR, 0.6 / index as f32));
+            }
+            pv_board = pv_board.make_move_new(chess_move);
+        }
+    }
+    let mut circles = Vec::new();
+    if let Some(marks) = marks {
+        arrows.extend(marks.svg_arrows());
+        circles = marks.svg_circles();
+    }
+
+    let board_size = board_height * 8.;
+    if let Some(eval) = engine_bar {
+        board_row = board_row.push(gen_eval_bar(eval, is_white, board_size)).push(Space::new().width(3));
+    }
+    let mut board_stack = stack![board_grid];
+    if !arrows.is_empty() || !circles.is_empty() {
+        board_stack = board_stack.push(Svg::new(overlay::arrows_svg(&arrows, &circles, is_white)).width(board_size).height(board_size));
+    }
+    if let (Some((from, to, progress)), Some((piece, color))) = (animation, moving_piece) {
+        // The top left corner of the squares, on the screen
+        let corner = |square: Square| {
+            let (file, rank) = (square.get_file().to_index() as f32, square.get_rank().to_index() as f32);
+            if is_white {
+                (file * board_height, (7. - rank) * board_height)
+            } else {
+                ((7. - file) * board_height, rank * board_height)
+            }
+        };
+        let ((from_x, from_y), (to_x, to_y)) = (corner(from), corner(to));
+        board_stack = board_stack.push(
+            container(
+                Svg::new(imgs[PieceWithColor::new(piece, color).index()].clone()).width(board_height).height(board_height)
+            ).padding(iced::Padding {
+                top: from_y + (to_y - from_y) * progress,
+                right: 0.,
+                bottom: 0.,
+                left: from_x + (to_x - from_x) * progress,
+            })
+        );
+    }
+    board_row = board_row.push(board_stack);
+    if show_coordinates {
+        board_row = board_row.push(rank_labels);
+    }
+    board_col = board_col.push(board_row);
+
     if show_coordinates {
-        if is_white {
-            board_col = board_col.push(row![
-                Text::new("a").size(15).width(board_height),
-                Text::new("b").size(15).width(board_height),
-                Text::new("c").size(15).width(board_height),
-                Text::new("d").size(15).width(board_height),
-                Text::new("e").size(15).width(board_height),
-                Text::new("f").size(15).width(board_height),
-                Text::new("g").size(15).width(board_height),
-                Text::new("h").size(15).width(board_height),
-            ]);
+        // Keep the file letters aligned with the squares, since the board column is centered
+        let left_padding = if engine_bar.is_some() { EVAL_BAR_WIDTH + 3. } else { 0. };
+        let mut files_row = Row::new().spacing(0).push(Space::new().width(left_padding));
+        let file_names = if is_white {
+            ["a", "b", "c", "d", "e", "f", "g", "h"]
         } else {
-            board_col = board_col.push(row![
-                Text::new("h").size(15).width(board_height),
-                Text::new("g").size(15).width(board_height),
-                Text::new("f").size(15).width(board_height),
-                Text::new("e").size(15).width(board_height),
-                Text::new("d").size(15).width(board_height),
-                Text::new("c").size(15).width(board_height),
-                Text::new("b").size(15).width(board_height),
-                Text::new("a").size(15).width(board_height),
-            ]);
+            ["h", "g", "f", "e", "d", "c", "b", "a"]
+        };
+        for file_name in file_names {
+            files_row = files_row.push(Text::new(file_name).size(15).width(board_height));
         }
+        board_col = board_col.push(files_row.push(Space::new().width(RANK_LABEL_WIDTH)));
     }
 
     let game_mode_row = row![
         Text::new(lang::tr(lang, "mode")),
         Radio::new(lang::tr(lang, "mode_puzzle"), config::GameMode::Puzzle, Some(game_mode), Message::SelectMode),
-        Radio::new(lang::tr(lang, "mode_analysis"), config::GameMode::Analysis, Some(game_mode), Message::SelectMode)
+        Radio::new(lang::tr(lang, "mode_analysis"), config::GameMode::Analysis, Some(game_mode), Message::SelectMode),
+        Radio::new(lang::tr(lang, "mode_play_engine"), config::GameMode::PlayEngine, Some(game_mode), Message::SelectMode),
+        Radio::new(lang::tr(lang, "mode_editor"), config::GameMode::Editor, Some(game_mode), Message::SelectMode)
     ].spacing(10).padding(10).align_y(Alignment::Center);
 
     let fav_label = if is_fav {
@@ -1216,7 +2215,7 @@ fn gen_view<'a>(
     };
     let mut navigation_row = Row::new().padding(3).spacing(10);
     if game_mode == config::GameMode::Analysis {
-        if analysis_history_len > current_puzzle_move {
+        if analysis_history_len > analysis_base_len {
             navigation_row = navigation_row.push(Button::new(Text::new(lang::tr(lang, "takeback"))).on_press(Message::GoBackMove));
         } else {
             navigation_row = navigation_row.push(Button::new(Text::new(lang::tr(lang, "takeback"))));
@@ -1226,6 +2225,18 @@ fn gen_view<'a>(
         } else {
             navigation_row = navigation_row.push(Button::new(Text::new(lang::tr(lang, "start_engine"))).on_press(Message::StartEngine));
         }
+    } else if game_mode == config::GameMode::PlayEngine {
+        if play_moves_len > 0 {
+            navigation_row = navigation_row
+                .push(Button::new(Text::new(lang::tr(lang, "takeback"))).on_press(Message::GoBackMove))
+                .push(Button::new(Text::new(lang::tr(lang, "save_game_pgn"))).on_press(Message::SavePlayedGame));
+        } else {
+            navigation_row = navigation_row
+                .push(Button::new(Text::new(lang::tr(lang, "takeback"))))
+                .push(Button::new(Text::new(lang::tr(lang, "save_game_pgn"))));
+        }
+    } else if game_mode == config::GameMode::Editor {
+        // The editor's controls are in its tab
     } else {
         if has_previous {
             navigation_row = navigation_row.push(Button::new(Text::new(lang::tr(lang, "previous"))).on_press(Message::ShowPreviousPuzzle))
@@ -1252,6 +2263,10 @@ fn gen_view<'a>(
                 .push(Button::new(Text::new(lang::tr(lang, "redo"))).on_press(Message::RedoPuzzle))
                 .push(Button::new(Text::new(fav_label)).on_press(Message::FavoritePuzzle))
                 .push(Button::new(Text::new(lang::tr(lang, "hint"))).on_press(Message::ShowHint));
+            if let Some(peeking) = peek_button {
+                let peek_label = if peeking { lang::tr(lang, "hide_pieces") } else { lang::tr(lang, "peek") };
+                navigation_row = navigation_row.push(Button::new(Text::new(peek_label)).on_press(Message::Peek));
+            }
         }
     }
 
@@ -1271,7 +2286,30 @@ fn gen_view<'a>(
         btn_go
     ].spacing(10).align_y(Alignment::Center);
 
-    board_col = board_col.push(Text::new(puzzle_status)).push(game_mode_row).push(navigation_row).push(pagination_row);
+    // SAN (in English or in the app's language) or UCI, for those who'd rather type the moves
+    let mut move_input_row = row![
+        text_input(&lang::tr(lang, "move_input_placeholder"), move_input)
+            .on_input(Message::MoveInputChange)
+            .on_submit(Message::SubmitMoveInput)
+            .width(Length::Fixed(200.)),
+        Text::new(move_input_error).style(text::danger)
+    ].spacing(10).padding(3).align_y(Alignment::Center);
+    if marks.is_some_and(|marks| !marks.is_empty()) {
+        move_input_row = move_input_row.push(Button::new(Text::new(lang::tr(lang, "clear_marks"))).on_press(Message::ClearMarks));
+    }
+
+    board_col = board_col.push(Text::new(puzzle_status));
+    if position_text.is_some() || can_read_aloud {
+        let mut description_row = Row::new().spacing(10).padding(3).align_y(Alignment::Center);
+        if let Some(position_text) = position_text {
+            description_row = description_row.push(Text::new(position_text).size(14));
+        }
+        if can_read_aloud {
+            description_row = description_row.push(Button::new(Text::new(lang::tr(lang, "read_position"))).on_press(Message::ReadPosition));
+        }
+        board_col = board_col.push(description_row);
+    }
+    board_col = board_col.push(move_input_row).push(game_mode_row).push(navigation_row).push(pagination_row);
     if !engine_eval.is_empty() {
         board_col = board_col.push(
             row![
@@ -1280,6 +2318,9 @@ fn gen_view<'a>(
             ].padding(5).spacing(15)
         );
     }
+    if let Some(engine_error) = engine_error {
+        board_col = board_col.push(Text::new(engine_error).style(text::danger));
+    }
     if  mini_ui {
         let button_mini = Button::new(Text::new(">")).on_press(Message::MinimizeUI);
         row![board_col,button_mini].spacing(5).align_y(Alignment::Start).into()
@@ -1289,6 +2330,7 @@ fn gen_view<'a>(
                 .push(TabId::Search, search_tab_label, search_tab)
                 .push(TabId::Settings, settings_tab_label, settings_tab)
                 .push(TabId::CurrentPuzzle ,puzzle_tab_label, puzzle_tab)
+                .push(TabId::Editor, editor_tab_label, editor_tab)
                 .tab_bar_position(iced_aw::TabBarPosition::Top)
                 .tab_bar_style(styles::tab_style)
                 .set_active_tab(active_tab);
@@ -1297,6 +2339,40 @@ fn gen_view<'a>(
     }
 }
 
+// Right-click marks the square, and dragging with it draws an arrow to where it's released
+fn markable_square<'a>(content: impl Into<Element<'a, Message, Theme, iced::Renderer>>, square: Square) -> Element<'a, Message, Theme, iced::Renderer> {
+    mouse_area(content)
+        .on_right_press(Message::MarkStart(square))
+        .on_right_release(Message::MarkEnd(square))
+        .into()
+}
+
+// The share of the bar that is white follows the eval with a tanh curve,
+// so small advantages are visible but the bar never fills up completely.
+fn gen_eval_bar<'a>(eval: f32, is_white: bool, height: f32) -> Element<'a, Message, Theme, iced::Renderer> {
+    let white_share = 0.5 + 0.5 * (eval / 4.).tanh();
+    let white_portion = ((white_share * 1000.).round() as u16).clamp(1, 999);
+
+    let white_part = container(Space::new())
+        .width(Length::Fill)
+        .height(Length::FillPortion(white_portion))
+        .style(styles::container_style_eval_white);
+    let black_part = container(Space::new())
+        .width(Length::Fill)
+        .height(Length::FillPortion(1000 - white_portion))
+        .style(styles::container_style_eval_black);
+
+    let bar = if is_white {
+        column![black_part, white_part]
+    } else {
+        column![white_part, black_part]
+    };
+    container(bar)
+        .width(EVAL_BAR_WIDTH)
+        .height(height)
+        .into()
+}
+
 trait Tab {
     type Message;
 
@@ -1323,6 +2399,12 @@ trait Tab {
 }
 
 fn main() -> iced::Result {
+    db::ensure_schema();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     let window_settings = iced::window::Settings {
             size: Size {
                 width: config::SETTINGS.window_width as f32, //(config::SETTINGS.square_size * 8) as u32 + 450,
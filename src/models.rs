@@ -1,5 +1,5 @@
 use diesel::prelude::*;
-use crate::schema::favs;
+use crate::schema::{favs, my_puzzles, custom_puzzles, marks};
 
 /*
 #[derive(Queryable)]
@@ -30,3 +30,42 @@ pub struct NewFavorite<'a> {
     pub game_url: &'a str,
     pub opening_tags: &'a str,
 }
+
+#[derive(Insertable)]
+#[diesel(table_name = my_puzzles)]
+pub struct NewMyPuzzle<'a> {
+    pub puzzle_id: &'a str,
+    pub fen: &'a str,
+    pub moves: &'a str,
+    pub rating: i32,
+    pub rd: i32,
+    pub popularity: i32,
+    pub nb_plays: i32,
+    pub themes: &'a str,
+    pub game_url: &'a str,
+    pub opening_tags: &'a str,
+    pub source: &'a str,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = custom_puzzles)]
+pub struct NewCustomPuzzle<'a> {
+    pub puzzle_id: &'a str,
+    pub fen: &'a str,
+    pub moves: &'a str,
+    pub rating: i32,
+    pub rd: i32,
+    pub popularity: i32,
+    pub nb_plays: i32,
+    pub themes: &'a str,
+    pub game_url: &'a str,
+    pub opening_tags: &'a str,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = marks)]
+pub struct NewMarks<'a> {
+    pub puzzle_id: &'a str,
+    pub position: &'a str,
+    pub marks: &'a str,
+}
@@ -35,6 +35,11 @@ impl Openings {
         Openings::TarraschDefense, Openings::CatalanOpening, Openings::Reti, Openings::QueensIndianDefense,
         Openings::LondonSystem];
 
+    // From the lichess opening tag ("Sicilian_Defense"...), as used in the command line
+    pub fn from_field_name(name: &str) -> Option<Openings> {
+        Openings::ALL.iter().find(|opening| opening.get_field_name().eq_ignore_ascii_case(name)).copied()
+    }
+
     pub fn get_field_name(&self) -> &str {
         match self {
             Openings::Any => "",
@@ -0,0 +1,221 @@
+use std::str::FromStr;
+use chess::{Board, Square};
+use iced::widget::svg::Handle;
+
+use crate::styles::PieceTheme;
+
+pub const ENGINE_ARROW_COLOR: &str = "#15781b";
+pub const ENGINE_ARROW_PAPER_COLOR: &str = "#2d2d2d";
+pub const PV_ARROW_COLOR: &str = "#003088";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Arrow {
+    pub from: Square,
+    pub to: Square,
+    pub color: &'static str,
+    pub opacity: f32,
+}
+
+impl Arrow {
+    pub fn new(from: Square, to: Square, color: &'static str, opacity: f32) -> Self {
+        Self { from, to, color, opacity }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub square: Square,
+    pub color: &'static str,
+}
+
+// The colors of the arrows and circles drawn by the user, with the letters
+// of the [%cal] and [%csl] PGN comments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkColor {
+    Green,
+    Red,
+    Blue,
+    Yellow,
+}
+
+impl MarkColor {
+    // Like on lichess: Shift for red, Alt (or Ctrl) for blue and both for yellow
+    pub fn from_modifiers(modifiers: iced::keyboard::Modifiers) -> Self {
+        match (modifiers.shift(), modifiers.alt() || modifiers.control()) {
+            (false, false) => MarkColor::Green,
+            (true, false) => MarkColor::Red,
+            (false, true) => MarkColor::Blue,
+            (true, true) => MarkColor::Yellow,
+        }
+    }
+
+    fn letter(&self) -> char {
+        match self {
+            MarkColor::Green => 'G',
+            MarkColor::Red => 'R',
+            MarkColor::Blue => 'B',
+            MarkColor::Yellow => 'Y',
+        }
+    }
+
+    fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'G' => Some(MarkColor::Green),
+            'R' => Some(MarkColor::Red),
+            'B' => Some(MarkColor::Blue),
+            'Y' => Some(MarkColor::Yellow),
+            _ => None,
+        }
+    }
+
+    fn svg_color(&self) -> &'static str {
+        match self {
+            MarkColor::Green => ENGINE_ARROW_COLOR,
+            MarkColor::Red => "#882020",
+            MarkColor::Blue => PV_ARROW_COLOR,
+            MarkColor::Yellow => "#e68f00",
+        }
+    }
+}
+
+// The arrows and circles drawn on one position
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Marks {
+    pub arrows: Vec<(Square, Square, MarkColor)>,
+    pub circles: Vec<(Square, MarkColor)>,
+}
+
+impl Marks {
+    pub fn is_empty(&self) -> bool {
+        self.arrows.is_empty() && self.circles.is_empty()
+    }
+
+    // Drawing the same mark again removes it, with another color it's replaced
+    pub fn toggle_arrow(&mut self, from: Square, to: Square, color: MarkColor) {
+        let previous = self.arrows.iter().position(|(arrow_from, arrow_to, _)| *arrow_from == from && *arrow_to == to);
+        if let Some(index) = previous {
+            if self.arrows.remove(index).2 == color {
+                return;
+            }
+        }
+        self.arrows.push((from, to, color));
+    }
+
+    pub fn toggle_circle(&mut self, square: Square, color: MarkColor) {
+        let previous = self.circles.iter().position(|(circle_square, _)| *circle_square == square);
+        if let Some(index) = previous {
+            if self.circles.remove(index).1 == color {
+                return;
+            }
+        }
+        self.circles.push((square, color));
+    }
+
+    pub fn svg_arrows(&self) -> Vec<Arrow> {
+        self.arrows.iter().map(|(from, to, color)| Arrow::new(*from, *to, color.svg_color(), 0.8)).collect()
+    }
+
+    pub fn svg_circles(&self) -> Vec<Circle> {
+        self.circles.iter().map(|(square, color)| Circle { square: *square, color: color.svg_color() }).collect()
+    }
+
+    // Like "[%csl Gd5,Re4][%cal Gg1f3]", what's in the PGN comments and saved in the database
+    pub fn to_pgn_comment(&self) -> String {
+        let mut comment = String::new();
+        if !self.circles.is_empty() {
+            let circles: Vec<String> = self.circles.iter().map(|(square, color)| format!("{}{}", color.letter(), square)).collect();
+            comment.push_str(&format!("[%csl {}]", circles.join(",")));
+        }
+        if !self.arrows.is_empty() {
+            let arrows: Vec<String> = self.arrows.iter().map(|(from, to, color)| format!("{}{}{}", color.letter(), from, to)).collect();
+            comment.push_str(&format!("[%cal {}]", arrows.join(",")));
+        }
+        comment
+    }
+
+    // The marks in a PGN comment, the rest of the text and the unknown ones are skipped
+    pub fn from_pgn_comment(comment: &str) -> Self {
+        let mut marks = Marks::default();
+        for (command, is_arrow) in [("[%csl", false), ("[%cal", true)] {
+            let Some(start) = comment.find(command) else {
+                continue;
+            };
+            let rest = &comment[start + command.len()..];
+            let Some(end) = rest.find(']') else {
+                continue;
+            };
+            for mark in rest[..end].split(',').map(str::trim) {
+                let Some(color) = mark.chars().next().and_then(MarkColor::from_letter) else {
+                    continue;
+                };
+                let from = mark.get(1..3).and_then(|square| Square::from_str(square).ok());
+                let to = mark.get(3..5).and_then(|square| Square::from_str(square).ok());
+                match (is_arrow, from, to) {
+                    (true, Some(from), Some(to)) => marks.arrows.push((from, to, color)),
+                    (false, Some(square), None) => marks.circles.push((square, color)),
+                    _ => (),
+                }
+            }
+        }
+        marks
+    }
+}
+
+// The marks are saved for each position of a puzzle, the move counters of
+// the FEN don't matter (the same position can be reached in analysis).
+pub fn position_key(board: &Board) -> String {
+    board.to_string().split_whitespace().take(4).collect::<Vec<&str>>().join(" ")
+}
+
+// The colored arrows are hard to see on the black & white "paper" board,
+// so the font theme gets a dark one instead.
+pub fn engine_arrow_color(piece_theme: PieceTheme) -> &'static str {
+    if piece_theme == PieceTheme::FontAlpha {
+        ENGINE_ARROW_PAPER_COLOR
+    } else {
+        ENGINE_ARROW_COLOR
+    }
+}
+
+// The viewBox is 8x8, so one unit is one square and (0,0) is the top left corner
+// of the board as it's being displayed (which depends on the orientation).
+fn square_center(square: Square, is_white: bool) -> (f32, f32) {
+    let file = square.get_file().to_index() as f32;
+    let rank = square.get_rank().to_index() as f32;
+    if is_white {
+        (file + 0.5, 7.5 - rank)
+    } else {
+        (7.5 - file, rank + 0.5)
+    }
+}
+
+pub fn arrows_svg(arrows: &[Arrow], circles: &[Circle], is_white: bool) -> Handle {
+    let mut svg = String::from(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 8 8" width="800" height="800">"#);
+    for circle in circles {
+        let (x, y) = square_center(circle.square, is_white);
+        svg.push_str(&format!(
+            r#"<circle cx="{x:.3}" cy="{y:.3}" r="0.44" fill="none" stroke="{c}" stroke-width="0.07" opacity="0.8"/>"#,
+            c = circle.color,
+        ));
+    }
+    for arrow in arrows {
+        let (x1, y1) = square_center(arrow.from, is_white);
+        let (x2, y2) = square_center(arrow.to, is_white);
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0. {
+            continue;
+        }
+        let (ux, uy) = (dx / length, dy / length);
+        // Where the line ends and the head starts
+        let (bx, by) = (x2 - ux * 0.4, y2 - uy * 0.4);
+        let (nx, ny) = (-uy * 0.25, ux * 0.25);
+        svg.push_str(&format!(
+            r#"<g fill="{c}" stroke="{c}" opacity="{o:.2}"><line x1="{x1:.3}" y1="{y1:.3}" x2="{bx:.3}" y2="{by:.3}" stroke-width="0.18"/><polygon stroke="none" points="{x2:.3},{y2:.3} {p1x:.3},{p1y:.3} {p2x:.3},{p2y:.3}"/></g>"#,
+            c = arrow.color, o = arrow.opacity,
+            p1x = bx + nx, p1y = by + ny, p2x = bx - nx, p2y = by - ny,
+        ));
+    }
+    svg.push_str("</svg>");
+    Handle::from_memory(svg.into_bytes())
+}
@@ -0,0 +1,148 @@
+use std::str::FromStr;
+use chess::{Board, ChessMove};
+
+use offline_chess_puzzles::session;
+
+#[derive(Debug, Clone, Default)]
+pub struct PgnGame {
+    pub headers: Vec<(String, String)>,
+    // The moves as written in the file (SAN), variations are skipped
+    pub moves: Vec<String>,
+    // The comments and the number of moves played before each of them
+    pub comments: Vec<(usize, String)>,
+    pub result: String,
+}
+
+impl PgnGame {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn start_board(&self) -> Option<Board> {
+        match self.header("FEN") {
+            Some(fen) => Board::from_str(fen).ok(),
+            None => Some(Board::default()),
+        }
+    }
+
+    // Returns the starting position and the moves, stopping at the first
+    // one that can't be understood (so a broken game still gives us its beginning).
+    pub fn chess_moves(&self) -> Option<(Board, Vec<ChessMove>)> {
+        let start = self.start_board()?;
+        let mut board = start;
+        let mut moves = Vec::new();
+        for san in &self.moves {
+            match session::san_to_move(&board, san) {
+                Some(chess_move) => {
+                    board = board.make_move_new(chess_move);
+                    moves.push(chess_move);
+                }
+                None => break,
+            }
+        }
+        Some((start, moves))
+    }
+}
+
+fn parse_header(line: &str) -> Option<(String, String)> {
+    let inner = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((String::from(name), value.replace("\\\"", "\"").replace("\\\\", "\\")))
+}
+
+fn is_result(token: &str) -> bool {
+    matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*")
+}
+
+pub fn parse_pgn(text: &str) -> Vec<PgnGame> {
+    let mut games = Vec::new();
+    let mut game = PgnGame::default();
+    let mut in_movetext = false;
+    let mut comment = String::new();
+    let mut in_comment = false;
+    let mut variation_depth = 0;
+
+    for line in text.lines() {
+        if !in_comment && line.starts_with('%') {
+            continue;
+        }
+        if !in_comment && line.trim_start().starts_with('[') {
+            if let Some(header) = parse_header(line) {
+                // A header after the moves means a new game (even if the result was missing)
+                if in_movetext {
+                    games.push(std::mem::take(&mut game));
+                    in_movetext = false;
+                }
+                game.headers.push(header);
+                continue;
+            }
+        }
+        let mut token = String::new();
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            if in_comment {
+                if c == '}' {
+                    in_comment = false;
+                    if variation_depth == 0 {
+                        game.comments.push((game.moves.len(), comment.trim().to_string()));
+                    }
+                    comment.clear();
+                } else {
+                    comment.push(c);
+                }
+                continue;
+            }
+            match c {
+                '{' | '(' | ')' | ';' => {
+                    finish_token(&mut token, &mut game, &mut games, &mut in_movetext, variation_depth);
+                    match c {
+                        '{' => in_comment = true,
+                        '(' => variation_depth += 1,
+                        ')' => variation_depth = (variation_depth - 1).max(0),
+                        // Rest of the line is a comment
+                        _ => break,
+                    }
+                }
+                c if c.is_whitespace() => {
+                    finish_token(&mut token, &mut game, &mut games, &mut in_movetext, variation_depth);
+                }
+                _ => token.push(c),
+            }
+        }
+        finish_token(&mut token, &mut game, &mut games, &mut in_movetext, variation_depth);
+        if in_comment {
+            comment.push(' ');
+        }
+    }
+    if in_movetext || !game.headers.is_empty() {
+        games.push(game);
+    }
+    games
+}
+
+fn finish_token(token: &mut String, game: &mut PgnGame, games: &mut Vec<PgnGame>, in_movetext: &mut bool, variation_depth: i32) {
+    if token.is_empty() {
+        return;
+    }
+    let current = std::mem::take(token);
+    if variation_depth > 0 || current.starts_with('$') {
+        return;
+    }
+    if is_result(&current) {
+        game.result = current;
+        games.push(std::mem::take(game));
+        *in_movetext = false;
+        return;
+    }
+    // Move numbers may come glued to the move ("12.e4" or "12...e5")
+    let san = if current.starts_with("0-0") {
+        current.as_str()
+    } else {
+        current.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.')
+    };
+    if !san.is_empty() {
+        game.moves.push(String::from(san));
+    }
+    *in_movetext = true;
+}
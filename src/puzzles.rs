@@ -1,20 +1,29 @@
-use iced::widget::{Container, column as col, row, Scrollable, Text, TextInput, Button};
-use iced::window::Id;
+use iced::widget::{Container, Column, column as col, row, Scrollable, Text, TextInput, Button, PickList};
 use iced::{alignment, Alignment, Element, Length, Task, Theme};
 use chess::{Color, Piece};
 use iced_aw::TabLabel;
 use rfd::AsyncFileDialog;
+use offline_chess_puzzles::session::{self, PuzzleSession};
 
-use crate::{Message, Tab, config, lang};
+use crate::{Message, Tab, config, diagram::DiagramFormat, lang, styles, verify::{IssueKind, VerifyIssue}};
 
 #[derive(Debug, Clone)]
 pub enum PuzzleMessage {
     ChangeTextInputs(String),
     CopyText(String),
     OpenLink(String),
-    TakeScreenshot,
+    SaveDiagram,
+    SelectDiagramFormat(DiagramFormat),
+    ExportDiagrams,
     ExportToPDF,
-    ExportToPGN
+    ExportToPGN,
+    ExportToAnki,
+    ExportToHTML,
+    ExportToCSV,
+    OpenPGN,
+    VerifyWithEngine,
+    CancelVerification,
+    ExportVerifyReport,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -24,26 +33,34 @@ pub enum GameStatus {
 
 #[derive(Debug, Clone)]
 pub struct PuzzleTab {
-    pub window_id: Option<Id>,
     pub puzzles: Vec<config::Puzzle>,
     pub current_puzzle: usize,
     pub current_puzzle_move: usize,
+    pub session: Option<PuzzleSession>,
     pub current_puzzle_side: Color,
     pub game_status: GameStatus,
     pub current_puzzle_fen: String,
+    pub verifying: bool,
+    pub verify_status: String,
+    pub verify_report: Option<Vec<VerifyIssue>>,
+    pub diagram_format: DiagramFormat,
     pub lang: lang::Language,
 }
 
 impl PuzzleTab {
     pub fn new() -> Self {
         PuzzleTab {
-            window_id: None,
             puzzles: Vec::new(),
             current_puzzle: 0,
             current_puzzle_move: 1,
+            session: None,
             current_puzzle_side: Color::White,
             game_status: GameStatus::NoPuzzles,
             current_puzzle_fen: String::new(),
+            verifying: false,
+            verify_status: String::new(),
+            verify_report: None,
+            diagram_format: DiagramFormat::default(),
             lang: config::SETTINGS.lang,
         }
     }
@@ -57,12 +74,31 @@ impl PuzzleTab {
             } PuzzleMessage::OpenLink(link) => {
                 let _ = open::that_detached(link);
                 Task::none()
-            } PuzzleMessage::TakeScreenshot => {
-                iced::window::screenshot(self.window_id.unwrap()).map(Message::ScreenshotCreated)
+            } PuzzleMessage::SaveDiagram => {
+                Task::perform(PuzzleTab::save_diagram_dialog(), Message::SaveDiagram)
+            } PuzzleMessage::SelectDiagramFormat(format) => {
+                self.diagram_format = format;
+                Task::none()
+            } PuzzleMessage::ExportDiagrams => {
+                Task::perform(PuzzleTab::pick_folder(), Message::ExportDiagrams)
             } PuzzleMessage::ExportToPDF => {
                 Task::perform(PuzzleTab::export(), Message::ExportPDF)
             } PuzzleMessage::ExportToPGN => {
                 return Task::perform(PuzzleTab::export(), Message::ExportPGN);
+            } PuzzleMessage::ExportToAnki => {
+                Task::perform(PuzzleTab::export(), Message::ExportAnki)
+            } PuzzleMessage::ExportToHTML => {
+                Task::perform(PuzzleTab::export(), Message::ExportHTML)
+            } PuzzleMessage::ExportToCSV => {
+                Task::perform(PuzzleTab::export(), Message::ExportCSV)
+            } PuzzleMessage::OpenPGN => {
+                Task::perform(PuzzleTab::open_pgn(), Message::OpenPuzzlesPGN)
+            } PuzzleMessage::VerifyWithEngine => {
+                Task::done(Message::StartVerification)
+            } PuzzleMessage::CancelVerification => {
+                Task::done(Message::CancelVerification)
+            } PuzzleMessage::ExportVerifyReport => {
+                Task::perform(PuzzleTab::export(), Message::ExportVerifyReport)
             }
         }
     }
@@ -72,19 +108,25 @@ impl PuzzleTab {
         file_path.map(|file_path| file_path.path().display().to_string())
     }
 
+    pub async fn save_diagram_dialog() -> Option<String> {
+        let file_path = AsyncFileDialog::new().add_filter("svg", &["svg"]).add_filter("png", &["png"]).save_file().await;
+        file_path.map(|file_path| file_path.path().display().to_string())
+    }
+
+    pub async fn pick_folder() -> Option<String> {
+        let folder = AsyncFileDialog::new().pick_folder().await;
+        folder.map(|folder| folder.path().display().to_string())
+    }
+
+    pub async fn open_pgn() -> Option<String> {
+        let file_path = AsyncFileDialog::new().add_filter("PGN", &["pgn"]).pick_file().await;
+        file_path.map(|file_path| file_path.path().display().to_string())
+    }
+
     // Checks if the notation indicates a promotion and return the piece
     // if that's the case.
     pub fn check_promotion(notation: &str) -> Option<Piece> {
-        let mut promotion = None;
-        if notation.len() > 4 {
-            promotion = match &notation[4..5] {
-                "r" => Some(Piece::Rook),
-                "n" => Some(Piece::Knight),
-                "b" => Some(Piece::Bishop),
-                _ => Some(Piece::Queen),
-            }
-        }
-        promotion
+        session::promotion_piece(notation)
     }
 }
 
@@ -100,6 +142,27 @@ impl Tab for PuzzleTab {
     }
 
     fn content(&self) -> Element<'_, Message> {
+        let mut col_verify = Column::new().spacing(5).align_x(Alignment::Center);
+        if self.verifying {
+            col_verify = col_verify.push(Button::new(Text::new(lang::tr(&self.lang, "cancel_verification"))).padding(5).on_press(PuzzleMessage::CancelVerification));
+        } else {
+            col_verify = col_verify.push(Button::new(Text::new(lang::tr(&self.lang, "verify_puzzles"))).padding(5).on_press(PuzzleMessage::VerifyWithEngine));
+        }
+        if !self.verify_status.is_empty() {
+            col_verify = col_verify.push(Text::new(&self.verify_status));
+        }
+        if let Some(report) = &self.verify_report {
+            // The full list is in the exported report
+            for issue in report.iter().take(100) {
+                let line = match issue.kind {
+                    IssueKind::NotBest => format!("{} ({}): {} {} / {} {}",
+                        issue.puzzle_id, issue.ply, issue.solution_move, issue.solution_eval, issue.engine_move, issue.engine_eval),
+                    _ => format!("{} ({}): {}", issue.puzzle_id, issue.ply, lang::tr(&self.lang, "verify_invalid_data")),
+                };
+                col_verify = col_verify.push(Text::new(line).size(12));
+            }
+            col_verify = col_verify.push(Button::new(Text::new(lang::tr(&self.lang, "export_verify_report"))).padding(5).on_press(PuzzleMessage::ExportVerifyReport));
+        }
         let col_puzzle_info = if !self.puzzles.is_empty() && self.current_puzzle < self.puzzles.len() {
             Scrollable::new(col![
                 Text::new(lang::tr(&self.lang, "puzzle_link")),
@@ -134,16 +197,30 @@ impl Tab for PuzzleTab {
                     Button::new(Text::new(lang::tr(&self.lang, "copy"))).on_press(PuzzleMessage::CopyText(self.puzzles[self.current_puzzle].game_url.clone())),
                     Button::new(Text::new(lang::tr(&self.lang, "open"))).on_press(PuzzleMessage::OpenLink(self.puzzles[self.current_puzzle].game_url.clone())),
                 ],
-                Button::new(Text::new(lang::tr(&self.lang, "screenshot"))).on_press(PuzzleMessage::TakeScreenshot),
+                Button::new(Text::new(lang::tr(&self.lang, "screenshot"))).on_press(PuzzleMessage::SaveDiagram),
+                row![
+                    Button::new(Text::new(lang::tr(&self.lang, "export_diagrams"))).on_press(PuzzleMessage::ExportDiagrams),
+                    PickList::new(
+                        &DiagramFormat::ALL[..],
+                        Some(self.diagram_format),
+                        PuzzleMessage::SelectDiagramFormat
+                    ).style(styles::pick_list_style).menu_style(styles::menu_style),
+                ].spacing(5).align_y(Alignment::Center),
                 Button::new(Text::new(lang::tr(&self.lang, "export_pdf_btn"))).on_press(PuzzleMessage::ExportToPDF),
                 Button::new(Text::new(lang::tr(&self.lang, "export_pgn"))).padding(5).on_press(PuzzleMessage::ExportToPGN),
+                Button::new(Text::new(lang::tr(&self.lang, "export_anki"))).padding(5).on_press(PuzzleMessage::ExportToAnki),
+                Button::new(Text::new(lang::tr(&self.lang, "export_html"))).padding(5).on_press(PuzzleMessage::ExportToHTML),
+                Button::new(Text::new(lang::tr(&self.lang, "export_csv"))).padding(5).on_press(PuzzleMessage::ExportToCSV),
+                Button::new(Text::new(lang::tr(&self.lang, "open_pgn"))).padding(5).on_press(PuzzleMessage::OpenPGN),
+                col_verify,
             ].padding([0, 30]).spacing(10).align_x(Alignment::Center))
         } else {
             Scrollable::new(col![
                     Text::new(lang::tr(&self.lang, "no_puzzle"))
                     .align_x(alignment::Horizontal::Center)
-                    .width(Length::Fill)
-                ].spacing(10))
+                    .width(Length::Fill),
+                    Button::new(Text::new(lang::tr(&self.lang, "open_pgn"))).padding(5).on_press(PuzzleMessage::OpenPGN),
+                ].spacing(10).align_x(Alignment::Center))
         };
         let content: Element<PuzzleMessage, Theme, iced::Renderer> = Container::new(col_puzzle_info)
             .align_x(alignment::Horizontal::Center).height(Length::Fill).into();
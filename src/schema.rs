@@ -12,3 +12,42 @@ table! {
         opening_tags -> Text,
     }
 }
+
+table! {
+    my_puzzles (puzzle_id) {
+        puzzle_id -> Text,
+        fen -> Text,
+        moves -> Text,
+        rating -> Integer,
+        rd -> Integer,
+        popularity -> Integer,
+        nb_plays -> Integer,
+        themes -> Text,
+        game_url -> Text,
+        opening_tags -> Text,
+        source -> Text,
+    }
+}
+
+table! {
+    custom_puzzles (puzzle_id) {
+        puzzle_id -> Text,
+        fen -> Text,
+        moves -> Text,
+        rating -> Integer,
+        rd -> Integer,
+        popularity -> Integer,
+        nb_plays -> Integer,
+        themes -> Text,
+        game_url -> Text,
+        opening_tags -> Text,
+    }
+}
+
+table! {
+    marks (puzzle_id, position) {
+        puzzle_id -> Text,
+        position -> Text,
+        marks -> Text,
+    }
+}
@@ -1,14 +1,15 @@
 use iced::widget::svg::Handle;
-use iced::widget::{Container, Button, column as col, Text, Radio, row, Row, Svg, PickList, Slider, Scrollable, Space};
+use iced::widget::{Container, Button, column as col, Text, TextInput, Radio, row, Row, Svg, PickList, Slider, Scrollable, Space};
 use iced::widget::text::LineHeight;
 use iced::{alignment, Alignment, Element, Length, Task, Theme};
 use std::io::BufReader;
 
 use iced_aw::TabLabel;
+use rfd::AsyncFileDialog;
 use chess::{Piece, PROMOTION_PIECES};
 use crate::config::{load_config, SETTINGS_FILE, PIECES_DIRECTORY};
 use crate::styles::PieceTheme;
-use crate::{Tab, Message, config, styles, lang, db, openings};
+use crate::{Tab, Message, config, styles, lang, db, export, openings, import};
 
 use lang::{DisplayTranslated,PickListWrapper};
 use openings::{Openings, Variation};
@@ -25,6 +26,15 @@ pub enum SearchMesssage {
     SelectPiecePromotion(Piece),
     ClickSearch,
     SelectBase(SearchBase),
+    ChangePlayerName(String),
+    GeneratePuzzles,
+    SelectSource(PuzzleSource),
+    ChangeSourceName(String),
+    ImportPuzzles,
+    PuzzlesImported(Option<Result<(String, usize, usize), String>>),
+    ExportBaseCSV,
+    BaseCSVExported(Option<Result<usize, String>>),
+    CancelGeneration,
 }
 
 impl PickListWrapper<TacticalThemes> {
@@ -95,6 +105,11 @@ impl TacticalThemes {
         TacticalThemes::Master, TacticalThemes::MasterVsMaster, TacticalThemes::SuperGM
     ];
 
+    // From the lichess tag ("fork", "mateIn2"...), as used in the command line
+    pub fn from_tag_name(tag: &str) -> Option<TacticalThemes> {
+        TacticalThemes::ALL.iter().find(|theme| theme.get_tag_name().eq_ignore_ascii_case(tag)).copied()
+    }
+
     pub fn get_tr_key(&self) -> &str {
         match self {
             TacticalThemes::All => "themes_all",
@@ -198,7 +213,30 @@ pub enum OpeningSide {
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum SearchBase {
-    Lichess, Favorites
+    Lichess, Favorites, MyPuzzles, Custom
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleSource {
+    // None stands for all of them
+    pub name: Option<String>,
+    label: String,
+}
+
+impl PuzzleSource {
+    pub fn new(lang: lang::Language, name: Option<String>) -> Self {
+        let label = match &name {
+            Some(name) => name.clone(),
+            None => lang::tr(&lang, "all_sources"),
+        };
+        Self { name, label }
+    }
+}
+
+impl std::fmt::Display for PuzzleSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
 }
 
 pub fn gen_piece_vec(theme: &PieceTheme) -> Vec<Handle> {
@@ -229,6 +267,15 @@ pub struct SearchTab {
     pub lang: lang::Language,
     base: Option<SearchBase>,
     pub promotion_piece_img: Vec<Handle>,
+    pub player_name: String,
+    pub generating: bool,
+    pub generate_status: String,
+    // Sources of the puzzles in "my puzzles" and the one selected (None for all)
+    pub sources: Vec<String>,
+    source: Option<String>,
+    source_name: String,
+    import_status: String,
+    export_status: String,
 }
 
 impl SearchTab {
@@ -247,6 +294,14 @@ impl SearchTab {
             lang: config::SETTINGS.lang,
             base: Some(SearchBase::Lichess),
             promotion_piece_img: gen_piece_vec(&config::SETTINGS.piece_theme),
+            player_name: String::new(),
+            generating: false,
+            generate_status: String::new(),
+            sources: Vec::new(),
+            source: None,
+            source_name: String::new(),
+            import_status: String::new(),
+            export_status: String::new(),
         }
     }
 
@@ -284,7 +339,14 @@ impl SearchTab {
                     self.opening.item, self.variation.item.clone(), self.opening_side);
 
                 let config = load_config();
-                if self.base == Some(SearchBase::Favorites) {
+                if self.base == Some(SearchBase::MyPuzzles) {
+                    Task::perform(
+                        SearchTab::search_my_puzzles(self.theme.item, self.source.clone(), config.search_results_limit), Message::LoadPuzzle)
+                } else if self.base == Some(SearchBase::Custom) {
+                    Task::perform(
+                        SearchTab::search_custom(self.slider_min_rating_value, self.slider_max_rating_value,
+                            self.theme.item, config.search_results_limit), Message::LoadPuzzle)
+                } else if self.base == Some(SearchBase::Favorites) {
                     Task::perform(
                         SearchTab::search_favs(self.slider_min_rating_value,
                             self.slider_max_rating_value, self.slider_min_popularity,
@@ -299,6 +361,49 @@ impl SearchTab {
                 }
             } SearchMesssage::SelectBase(base) => {
                 self.base = Some(base);
+                if base == SearchBase::MyPuzzles {
+                    self.sources = db::get_my_puzzles_sources();
+                }
+                Task::none()
+            } SearchMesssage::ChangePlayerName(name) => {
+                self.player_name = name;
+                Task::none()
+            } SearchMesssage::GeneratePuzzles => {
+                Task::perform(SearchTab::open_pgn(), Message::GenerateFromPgn)
+            } SearchMesssage::CancelGeneration => {
+                Task::done(Message::CancelGeneration)
+            } SearchMesssage::SelectSource(source) => {
+                self.source = source.name;
+                Task::none()
+            } SearchMesssage::ChangeSourceName(name) => {
+                self.source_name = name;
+                Task::none()
+            } SearchMesssage::ImportPuzzles => {
+                Task::perform(SearchTab::import_puzzles(self.source_name.clone(), self.lang),
+                    |result| Message::Search(SearchMesssage::PuzzlesImported(result)))
+            } SearchMesssage::PuzzlesImported(result) => {
+                match result {
+                    Some(Ok((source, added, skipped))) => {
+                        self.import_status = lang::tr(&self.lang, "puzzles_imported") + &added.to_string() +
+                            "\n" + &lang::tr(&self.lang, "puzzles_skipped") + &skipped.to_string();
+                        self.sources = db::get_my_puzzles_sources();
+                        self.source = Some(source);
+                    } Some(Err(path)) => {
+                        self.import_status = lang::tr(&self.lang, "error_reading_puzzles") + &path;
+                    } None => (),
+                }
+                Task::none()
+            } SearchMesssage::ExportBaseCSV => {
+                Task::perform(SearchTab::export_base_csv(self.base.unwrap_or(SearchBase::Favorites), self.source.clone()),
+                    |result| Message::Search(SearchMesssage::BaseCSVExported(result)))
+            } SearchMesssage::BaseCSVExported(result) => {
+                match result {
+                    Some(Ok(written)) => {
+                        self.export_status = lang::tr(&self.lang, "csv_exported") + &written.to_string();
+                    } Some(Err(path)) => {
+                        self.export_status = lang::tr(&self.lang, "error_exporting_csv") + &path;
+                    } None => (),
+                }
                 Task::none()
             }
         }
@@ -327,6 +432,37 @@ impl SearchTab {
         }
     }
 
+    pub async fn open_pgn() -> Option<String> {
+        let pgn_file = AsyncFileDialog::new().add_filter("PGN", &["pgn"]).pick_file().await;
+        pgn_file.map(|pgn_file| pgn_file.path().display().to_string())
+    }
+
+    // None if no file was chosen
+    pub async fn import_puzzles(source: String, lang: lang::Language) -> Option<Result<(String, usize, usize), String>> {
+        let puzzles_file = AsyncFileDialog::new().add_filter("PGN / EPD", &["pgn", "epd"]).pick_file().await;
+        puzzles_file.map(|puzzles_file| import::import_file(&puzzles_file.path().display().to_string(), &source, &lang))
+    }
+
+    // The whole favorites table, or the puzzles of the selected source of "my puzzles",
+    // whatever the search filters. None if no file was chosen.
+    pub async fn export_base_csv(base: SearchBase, source: Option<String>) -> Option<Result<usize, String>> {
+        let file_path = AsyncFileDialog::new().add_filter("CSV", &["csv"]).save_file().await?;
+        let puzzles = if base == SearchBase::MyPuzzles {
+            db::get_my_puzzles(TacticalThemes::All, source, i64::MAX as usize).unwrap_or_default()
+        } else {
+            db::get_all_favorites()
+        };
+        Some(export::to_csv(&puzzles, file_path.path().display().to_string()))
+    }
+
+    pub async fn search_my_puzzles(theme: TacticalThemes, source: Option<String>, result_limit: usize) -> Option<Vec<config::Puzzle>> {
+        db::get_my_puzzles(theme, source, result_limit)
+    }
+
+    pub async fn search_custom(min_rating: i32, max_rating: i32, theme: TacticalThemes, result_limit: usize) -> Option<Vec<config::Puzzle>> {
+        db::get_custom_puzzles(min_rating, max_rating, theme, result_limit)
+    }
+
     pub async fn search_favs(min_rating: i32, max_rating: i32, min_popularity: i32, theme: TacticalThemes, opening: Openings, variation:Variation, op_side: Option<OpeningSide>, result_limit: usize) -> Option<Vec<config::Puzzle>> {
         db::get_favorites(min_rating, max_rating, min_popularity, theme, opening, variation, op_side, result_limit)
     }
@@ -437,6 +573,8 @@ impl Tab for SearchTab {
                 row![
                     Radio::new(lang::tr(&self.lang, "lichess_db"), SearchBase::Lichess, self.base, SearchMesssage::SelectBase),
                     Radio::new(lang::tr(&self.lang, "my_favories"), SearchBase::Favorites, self.base, SearchMesssage::SelectBase),
+                    Radio::new(lang::tr(&self.lang, "my_puzzles"), SearchBase::MyPuzzles, self.base, SearchMesssage::SelectBase),
+                    Radio::new(lang::tr(&self.lang, "custom_puzzles"), SearchBase::Custom, self.base, SearchMesssage::SelectBase),
                 ].spacing(10)
             ).align_x(alignment::Horizontal::Center).width(Length::Fill),
             row![
@@ -495,6 +633,53 @@ impl Tab for SearchTab {
             search_col = search_col.push(Text::new(lang::tr(&self.lang, "side"))).push(row_color);
         }
 
+        if self.base == Some(SearchBase::MyPuzzles) {
+            let btn_generate = if self.generating {
+                Button::new(Text::new(lang::tr(&self.lang, "cancel_generation"))).padding(5).on_press(SearchMesssage::CancelGeneration)
+            } else {
+                Button::new(Text::new(lang::tr(&self.lang, "generate_from_pgn"))).padding(5).on_press(SearchMesssage::GeneratePuzzles)
+            };
+            search_col = search_col.push(
+                row![
+                    Text::new(lang::tr(&self.lang, "player_name")),
+                    TextInput::new(&self.player_name, &self.player_name).on_input(SearchMesssage::ChangePlayerName).width(150),
+                ].spacing(5).align_y(Alignment::Center)
+            ).push(btn_generate);
+            if !self.generate_status.is_empty() {
+                search_col = search_col.push(Text::new(&self.generate_status));
+            }
+            let sources: Vec<PuzzleSource> = std::iter::once(None).chain(self.sources.iter().cloned().map(Some))
+                .map(|source| PuzzleSource::new(self.lang, source)).collect();
+            search_col = search_col.push(
+                row![
+                    Text::new(lang::tr(&self.lang, "puzzle_source")),
+                    PickList::new(
+                        sources,
+                        Some(PuzzleSource::new(self.lang, self.source.clone())),
+                        SearchMesssage::SelectSource
+                    ).style(styles::pick_list_style).menu_style(styles::menu_style),
+                ].spacing(5).align_y(Alignment::Center)
+            ).push(
+                row![
+                    Text::new(lang::tr(&self.lang, "source_name")),
+                    TextInput::new(&self.source_name, &self.source_name).on_input(SearchMesssage::ChangeSourceName).width(150),
+                ].spacing(5).align_y(Alignment::Center)
+            ).push(
+                Button::new(Text::new(lang::tr(&self.lang, "import_puzzles"))).padding(5).on_press(SearchMesssage::ImportPuzzles)
+            );
+            if !self.import_status.is_empty() {
+                search_col = search_col.push(Text::new(&self.import_status));
+            }
+        }
+        if self.base == Some(SearchBase::Favorites) || self.base == Some(SearchBase::MyPuzzles) {
+            search_col = search_col.push(
+                Button::new(Text::new(lang::tr(&self.lang, "export_base_csv"))).padding(5).on_press(SearchMesssage::ExportBaseCSV)
+            );
+            if !self.export_status.is_empty() {
+                search_col = search_col.push(Text::new(&self.export_status));
+            }
+        }
+
         let mut row_promotion = Row::new().spacing(5).align_y(Alignment::Center);
         if self.piece_theme_promotion == PieceTheme::FontAlpha {
             // Promotion piece selector
@@ -0,0 +1,498 @@
+// A puzzle being solved, without anything from the UI: load it, submit moves,
+// get the verdict and ask for hints. The app, the command line and tests use it.
+use std::str::FromStr;
+use chess::{Board, BoardStatus, ChessMove, Color, File, MoveGen, Piece, Rank, Square};
+
+// Used in the moves of puzzles that start without the opponent's move
+pub const NULL_MOVE: &str = "0000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    // Not a legal move in the position, the board is unchanged
+    Illegal,
+    // Legal but not the solution, the board is unchanged
+    Wrong,
+    // The expected move, the opponent answered with the reply
+    Correct { reply: ChessMove },
+    // The last move of the solution (or a mate), nothing else to play
+    Solved,
+}
+
+#[derive(Debug, Clone)]
+pub struct PuzzleSession {
+    // The position shown to the solver at the start, after the opponent's move
+    start: Board,
+    board: Board,
+    // Like in the lichess database: the opponent's move, then the solution
+    moves: Vec<String>,
+    next_move: usize,
+    last_move: Option<ChessMove>,
+    solver: Color,
+    solved: bool,
+    // Looks at the pieces in blindfold mode, a solve with them isn't a clean one
+    peeks: usize,
+}
+
+impl PuzzleSession {
+    // From the FEN and moves of the puzzle (lichess format). The opponent's move
+    // is already applied, so the board is the one shown to the solver.
+    pub fn new(fen: &str, moves: &str) -> Option<PuzzleSession> {
+        let mut board = Board::from_str(fen).ok()?;
+        let moves: Vec<String> = moves.split_whitespace().map(String::from).collect();
+        let mut last_move = None;
+        let setup_move = moves.first()?;
+        if setup_move != NULL_MOVE {
+            let movement = uci_to_move(&board, setup_move)?;
+            board = board.make_move_new(movement);
+            last_move = Some(movement);
+        }
+        // There's nothing to solve without at least one move after the setup
+        if moves.len() < 2 {
+            return None;
+        }
+        Some(PuzzleSession { start: board, board, moves, next_move: 1, last_move, solver: board.side_to_move(), solved: false, peeks: 0 })
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    // The side solving the puzzle, which doesn't change while moves are played
+    pub fn solver(&self) -> Color {
+        self.solver
+    }
+
+    pub fn last_move(&self) -> Option<ChessMove> {
+        self.last_move
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.solved
+    }
+
+    pub fn record_peek(&mut self) {
+        self.peeks += 1;
+    }
+
+    pub fn peeks(&self) -> usize {
+        self.peeks
+    }
+
+    // Index in the puzzle's moves of the next move to play, starting at 1 (after the opponent's move)
+    pub fn next_move_index(&self) -> usize {
+        self.next_move
+    }
+
+    // The positions from the start of the puzzle up to the current one
+    pub fn positions(&self) -> Vec<Board> {
+        let mut board = self.start;
+        let mut positions = vec![board];
+        for uci_move in &self.moves[1..self.next_move] {
+            if let Some(movement) = uci_to_move(&board, uci_move) {
+                board = board.make_move_new(movement);
+            }
+            positions.push(board);
+        }
+        // A mate that isn't the solution's move also ends the puzzle
+        if let Some(last) = positions.last_mut() {
+            *last = self.board;
+        }
+        positions
+    }
+
+    // The square of the piece to move next
+    pub fn hint(&self) -> Option<Square> {
+        if self.solved {
+            return None;
+        }
+        self.expected_move().map(|movement| movement.get_source())
+    }
+
+    // The expected move, for when the user gives up
+    pub fn solution_move(&self) -> Option<ChessMove> {
+        if self.solved {
+            return None;
+        }
+        self.expected_move()
+    }
+
+    // The move from the solution or any mate is accepted, and then the opponent's
+    // reply is played right away.
+    pub fn submit_move(&mut self, movement: ChessMove) -> Verdict {
+        if self.solved || !self.board.legal(movement) {
+            return Verdict::Illegal;
+        }
+        let Some(correct_move) = self.expected_move() else {
+            return Verdict::Illegal;
+        };
+        let after_move = self.board.make_move_new(movement);
+        let is_mate = after_move.status() == BoardStatus::Checkmate;
+        if movement != correct_move && !is_mate {
+            return Verdict::Wrong;
+        }
+        self.board = after_move;
+        self.last_move = Some(movement);
+        self.next_move += 1;
+        if is_mate || self.next_move == self.moves.len() {
+            self.solved = true;
+            return Verdict::Solved;
+        }
+        match self.expected_move() {
+            Some(reply) => {
+                self.board = self.board.make_move_new(reply);
+                self.last_move = Some(reply);
+                self.next_move += 1;
+                if self.next_move == self.moves.len() {
+                    // A solution ending in the opponent's move, nothing left for the solver
+                    self.solved = true;
+                }
+                Verdict::Correct { reply }
+            }
+            None => {
+                self.solved = true;
+                Verdict::Solved
+            }
+        }
+    }
+
+    // Like submit_move, with the move in UCI notation ("e2e4", "e7e8q")
+    pub fn submit_uci(&mut self, uci_move: &str) -> Verdict {
+        match uci_to_move(&self.board, uci_move) {
+            Some(movement) => self.submit_move(movement),
+            None => Verdict::Illegal,
+        }
+    }
+
+    fn expected_move(&self) -> Option<ChessMove> {
+        uci_to_move(&self.board, self.moves.get(self.next_move)?)
+    }
+}
+
+// The piece in the fifth character of a UCI move, if any
+pub fn promotion_piece(notation: &str) -> Option<Piece> {
+    let mut promotion = None;
+    if notation.len() > 4 {
+        promotion = match notation.get(4..5) {
+            Some("r") => Some(Piece::Rook),
+            Some("n") => Some(Piece::Knight),
+            Some("b") => Some(Piece::Bishop),
+            _ => Some(Piece::Queen),
+        }
+    }
+    promotion
+}
+
+// The piece letters of SAN in English, other languages pass their own to read_move()
+pub const ENGLISH_LETTERS: [(char, Piece); 5] = [
+    ('K', Piece::King), ('Q', Piece::Queen), ('R', Piece::Rook), ('B', Piece::Bishop), ('N', Piece::Knight)
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    // Not SAN nor UCI
+    Unreadable,
+    // Readable, but there's no such legal move in the position
+    Illegal,
+    // More than one legal move matches it, like "Nd2" with both knights able to go there
+    Ambiguous(Vec<ChessMove>),
+}
+
+// A move as typed by the user: UCI ("g1f3", "e7d8q") or SAN ("Nf3", "exd8=Q+"), with the
+// piece letters in English or in the given ones. When the two readings of the letters
+// give different moves (the "R" of the rook and the "R" of "Rei"), it's ambiguous.
+pub fn read_move(board: &Board, text: &str, letters: &[(char, Piece)]) -> Result<ChessMove, MoveError> {
+    let text = text.trim();
+    if is_uci(text) {
+        return uci_to_move(board, &text.to_lowercase()).ok_or(MoveError::Illegal);
+    }
+    let localized = san_candidates(board, text, letters);
+    let english = san_candidates(board, text, &ENGLISH_LETTERS);
+    if localized.is_none() && english.is_none() {
+        return Err(MoveError::Unreadable);
+    }
+    let mut candidates = localized.unwrap_or_default();
+    for movement in english.unwrap_or_default() {
+        if !candidates.contains(&movement) {
+            candidates.push(movement);
+        }
+    }
+    match candidates.len() {
+        0 => Err(MoveError::Illegal),
+        1 => Ok(candidates[0]),
+        _ => Err(MoveError::Ambiguous(candidates)),
+    }
+}
+
+fn is_uci(text: &str) -> bool {
+    let text = text.to_lowercase();
+    (text.len() == 4 || text.len() == 5) && text.is_ascii()
+        && Square::from_str(&text[0..2]).is_ok() && Square::from_str(&text[2..4]).is_ok()
+        && text.get(4..5).is_none_or(|promotion| "qrbn".contains(promotion))
+}
+
+// The legal moves matching the SAN, None if it can't be read as SAN with these letters
+fn san_candidates(board: &Board, san: &str, letters: &[(char, Piece)]) -> Option<Vec<ChessMove>> {
+    let san = san.trim_end_matches(['+', '#', '!', '?']);
+    let legal_moves = MoveGen::new_legal(board);
+    let castle_file = match san {
+        "O-O" | "0-0" => Some(File::G),
+        "O-O-O" | "0-0-0" => Some(File::C),
+        _ => None,
+    };
+    if let Some(file) = castle_file {
+        return Some(legal_moves.filter(|movement| {
+            board.piece_on(movement.get_source()) == Some(Piece::King)
+                && movement.get_source().get_file() == File::E
+                && movement.get_dest().get_file() == file
+        }).collect());
+    }
+
+    // The captures and "=" of promotions don't change which move it is
+    let mut chars: Vec<char> = san.chars().filter(|c| !matches!(c, 'x' | ':' | '=' | '-')).collect();
+    let piece = match chars.first().and_then(|first| letters.iter().find(|(letter, _)| letter == first)) {
+        Some((_, piece)) => {
+            chars.remove(0);
+            *piece
+        }
+        None => Piece::Pawn,
+    };
+    let promotion = match chars.last() {
+        Some(last) if !last.is_ascii_digit() => {
+            let (_, promotion) = letters.iter().find(|(letter, _)| letter.eq_ignore_ascii_case(last))?;
+            if piece != Piece::Pawn || *promotion == Piece::King {
+                return None;
+            }
+            chars.pop();
+            Some(*promotion)
+        }
+        _ => None,
+    };
+    if chars.len() < 2 {
+        return None;
+    }
+    let dest: String = chars.split_off(chars.len() - 2).into_iter().collect();
+    let dest = Square::from_str(&dest).ok()?;
+    // What's left is the file and/or rank of the piece that moves
+    let mut from_file = None;
+    let mut from_rank = None;
+    for c in chars {
+        match c {
+            'a'..='h' => from_file = Some(File::from_index(c as usize - 'a' as usize)),
+            '1'..='8' => from_rank = Some(Rank::from_index(c as usize - '1' as usize)),
+            _ => return None,
+        }
+    }
+    Some(legal_moves.filter(|movement| {
+        board.piece_on(movement.get_source()) == Some(piece)
+            && movement.get_dest() == dest
+            && from_file.is_none_or(|file| movement.get_source().get_file() == file)
+            && from_rank.is_none_or(|rank| movement.get_source().get_rank() == rank)
+            // Without the piece, promotions are to a queen
+            && movement.get_promotion() == promotion.or(movement.get_promotion().map(|_| Piece::Queen))
+    }).collect())
+}
+
+pub fn san_to_move(board: &Board, san: &str) -> Option<ChessMove> {
+    // The chess crate doesn't accept the check marks, annotations or the "=" of promotions
+    let cleaned: String = san.trim_end_matches(['+', '#', '!', '?']).chars().filter(|c| *c != '=').collect();
+    let cleaned = match cleaned.as_str() {
+        "0-0" => String::from("O-O"),
+        "0-0-0" => String::from("O-O-O"),
+        _ => cleaned,
+    };
+    let chess_move = ChessMove::from_san(board, &cleaned).ok()?;
+    if board.legal(chess_move) {
+        Some(chess_move)
+    } else {
+        None
+    }
+}
+
+// None if it's not a legal move in the position
+pub fn uci_to_move(board: &Board, uci_move: &str) -> Option<ChessMove> {
+    let from = Square::from_str(uci_move.get(0..2)?).ok()?;
+    let to = Square::from_str(uci_move.get(2..4)?).ok()?;
+    let movement = ChessMove::new(from, to, promotion_piece(uci_move));
+    if board.legal(movement) {
+        Some(movement)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    const SPANISH_LETTERS: [(char, Piece); 5] = [
+        ('R', Piece::King), ('D', Piece::Queen), ('T', Piece::Rook), ('A', Piece::Bishop), ('C', Piece::Knight)
+    ];
+    const FRENCH_LETTERS: [(char, Piece); 5] = [
+        ('R', Piece::King), ('D', Piece::Queen), ('T', Piece::Rook), ('F', Piece::Bishop), ('C', Piece::Knight)
+    ];
+
+    fn board(fen: &str) -> Board {
+        Board::from_str(fen).unwrap()
+    }
+
+    fn uci(board: &Board, uci_move: &str) -> ChessMove {
+        uci_to_move(board, uci_move).unwrap()
+    }
+
+    #[test]
+    fn new_applies_the_opponents_move() {
+        let session = PuzzleSession::new(START, "e2e4 e7e5 g1f3").unwrap();
+        assert_eq!(session.solver(), Color::Black);
+        assert_eq!(session.last_move(), Some(uci(&board(START), "e2e4")));
+        assert_eq!(session.board().piece_on(Square::E4), Some(Piece::Pawn));
+        assert_eq!(session.next_move_index(), 1);
+    }
+
+    #[test]
+    fn new_with_null_move() {
+        let session = PuzzleSession::new(START, "0000 e2e4").unwrap();
+        assert_eq!(session.solver(), Color::White);
+        assert_eq!(session.last_move(), None);
+        assert_eq!(session.board(), board(START));
+    }
+
+    #[test]
+    fn new_needs_a_solution() {
+        assert!(PuzzleSession::new(START, "e2e4").is_none());
+        assert!(PuzzleSession::new(START, "").is_none());
+        assert!(PuzzleSession::new(START, "e2e5 e7e5").is_none());
+        assert!(PuzzleSession::new("not a fen", "0000 e2e4").is_none());
+    }
+
+    #[test]
+    fn submit_move_verdicts() {
+        let mut session = PuzzleSession::new(START, "e2e4 e7e5 g1f3 b8c6").unwrap();
+        let before = session.board();
+        assert_eq!(session.submit_move(ChessMove::new(Square::E7, Square::E4, None)), Verdict::Illegal);
+        assert_eq!(session.submit_uci("d7d5"), Verdict::Wrong);
+        assert_eq!(session.board(), before);
+
+        let reply = uci(&before.make_move_new(uci(&before, "e7e5")), "g1f3");
+        assert_eq!(session.submit_uci("e7e5"), Verdict::Correct { reply });
+        assert_eq!(session.last_move(), Some(reply));
+        assert!(!session.is_solved());
+
+        assert_eq!(session.submit_uci("b8c6"), Verdict::Solved);
+        assert!(session.is_solved());
+        // Nothing else is accepted once it's solved
+        assert_eq!(session.submit_uci("d7d6"), Verdict::Illegal);
+    }
+
+    #[test]
+    fn solution_ending_on_the_opponents_move() {
+        let mut session = PuzzleSession::new(START, "0000 e2e4 e7e5").unwrap();
+        let reply = uci(&board(START).make_move_new(uci(&board(START), "e2e4")), "e7e5");
+        assert_eq!(session.submit_uci("e2e4"), Verdict::Correct { reply });
+        assert!(session.is_solved());
+        assert_eq!(session.hint(), None);
+        assert_eq!(session.submit_uci("g1f3"), Verdict::Illegal);
+    }
+
+    #[test]
+    fn any_mate_is_accepted() {
+        // Qxg7 is mate even if the solution has Rxg7
+        let mut session = PuzzleSession::new("7k/6pp/7Q/8/8/8/8/6RK w - - 0 1", "0000 g1g7").unwrap();
+        assert_eq!(session.submit_uci("h6g7"), Verdict::Solved);
+    }
+
+    #[test]
+    fn positions() {
+        let mut session = PuzzleSession::new(START, "e2e4 e7e5 g1f3 b8c6").unwrap();
+        let start = session.board();
+        assert_eq!(session.positions(), vec![start]);
+        session.submit_uci("e7e5");
+        let positions = session.positions();
+        assert_eq!(positions.len(), 3);
+        assert_eq!(positions[0], start);
+        assert_eq!(positions[2], session.board());
+    }
+
+    #[test]
+    fn hint() {
+        let mut session = PuzzleSession::new(START, "e2e4 e7e5 g1f3 b8c6").unwrap();
+        assert_eq!(session.hint(), Some(Square::E7));
+        session.submit_uci("e7e5");
+        assert_eq!(session.hint(), Some(Square::B8));
+        session.submit_uci("b8c6");
+        assert_eq!(session.hint(), None);
+        assert_eq!(session.solution_move(), None);
+    }
+
+    #[test]
+    fn read_san_and_uci() {
+        let start = board(START);
+        let knight_move = uci(&start, "g1f3");
+        assert_eq!(read_move(&start, "Nf3", &ENGLISH_LETTERS), Ok(knight_move));
+        assert_eq!(read_move(&start, "Ngf3", &ENGLISH_LETTERS), Ok(knight_move));
+        assert_eq!(read_move(&start, "g1f3", &ENGLISH_LETTERS), Ok(knight_move));
+        assert_eq!(read_move(&start, " G1F3 ", &ENGLISH_LETTERS), Ok(knight_move));
+        assert_eq!(read_move(&start, "e4", &ENGLISH_LETTERS), Ok(uci(&start, "e2e4")));
+        assert_eq!(read_move(&start, "Nf4", &ENGLISH_LETTERS), Err(MoveError::Illegal));
+        assert_eq!(read_move(&start, "e2e5", &ENGLISH_LETTERS), Err(MoveError::Illegal));
+        assert_eq!(read_move(&start, "hello", &ENGLISH_LETTERS), Err(MoveError::Unreadable));
+    }
+
+    #[test]
+    fn read_localized_letters() {
+        let position = board("4k3/8/8/8/8/8/8/R2QK3 w - - 0 1");
+        for letters in [SPANISH_LETTERS, FRENCH_LETTERS] {
+            assert_eq!(read_move(&position, "Rf2", &letters), Ok(uci(&position, "e1f2")));
+            assert_eq!(read_move(&position, "Ta7", &letters), Ok(uci(&position, "a1a7")));
+            assert_eq!(read_move(&position, "Dh5", &letters), Ok(uci(&position, "d1h5")));
+            // The English letters still work
+            assert_eq!(read_move(&position, "Qh5", &letters), Ok(uci(&position, "d1h5")));
+        }
+    }
+
+    #[test]
+    fn read_ambiguous() {
+        let two_knights = board("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1");
+        match read_move(&two_knights, "Nd2", &ENGLISH_LETTERS) {
+            Err(MoveError::Ambiguous(moves)) => assert_eq!(moves.len(), 2),
+            other => panic!("{other:?}"),
+        }
+        assert_eq!(read_move(&two_knights, "Nbd2", &ENGLISH_LETTERS), Ok(uci(&two_knights, "b1d2")));
+
+        // The "R" of the king in Spanish and the "R" of the rook in English
+        let king_and_rook = board("4k3/8/8/8/8/8/R7/4K3 w - - 0 1");
+        match read_move(&king_and_rook, "Re2", &SPANISH_LETTERS) {
+            Err(MoveError::Ambiguous(moves)) => {
+                assert!(moves.contains(&uci(&king_and_rook, "e1e2")));
+                assert!(moves.contains(&uci(&king_and_rook, "a2e2")));
+            }
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn read_promotion() {
+        let position = board("8/4P3/8/8/8/8/8/k3K3 w - - 0 1");
+        let to_queen = ChessMove::new(Square::E7, Square::E8, Some(Piece::Queen));
+        let to_knight = ChessMove::new(Square::E7, Square::E8, Some(Piece::Knight));
+        assert_eq!(read_move(&position, "e8=Q", &ENGLISH_LETTERS), Ok(to_queen));
+        assert_eq!(read_move(&position, "e8Q+", &ENGLISH_LETTERS), Ok(to_queen));
+        assert_eq!(read_move(&position, "e8", &ENGLISH_LETTERS), Ok(to_queen));
+        assert_eq!(read_move(&position, "e8=N", &ENGLISH_LETTERS), Ok(to_knight));
+        assert_eq!(read_move(&position, "e7e8n", &ENGLISH_LETTERS), Ok(to_knight));
+        assert_eq!(read_move(&position, "e8=D", &SPANISH_LETTERS), Ok(to_queen));
+        assert_eq!(read_move(&position, "e8=C", &FRENCH_LETTERS), Ok(to_knight));
+    }
+
+    #[test]
+    fn read_castling() {
+        let position = board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let short = ChessMove::new(Square::E1, Square::G1, None);
+        let long = ChessMove::new(Square::E1, Square::C1, None);
+        assert_eq!(read_move(&position, "O-O", &ENGLISH_LETTERS), Ok(short));
+        assert_eq!(read_move(&position, "0-0", &ENGLISH_LETTERS), Ok(short));
+        assert_eq!(read_move(&position, "O-O-O", &ENGLISH_LETTERS), Ok(long));
+        assert_eq!(read_move(&position, "0-0-0+", &ENGLISH_LETTERS), Ok(long));
+        assert_eq!(read_move(&position, "e1g1", &ENGLISH_LETTERS), Ok(short));
+    }
+}
@@ -1,11 +1,11 @@
-use iced::widget::{Button, Container, Checkbox, column, Column, Text, TextInput, row, PickList, Scrollable};
+use iced::widget::{text, Button, Container, Checkbox, column, Column, Text, TextInput, row, PickList, Radio, Scrollable};
 use iced::{alignment, Alignment, Element, Length, Task, Theme};
 
 use iced_aw::TabLabel;
 
 use rfd::AsyncFileDialog;
 
-use crate::{Message, Tab, config, styles, lang, lang::PickListWrapper};
+use crate::{Message, Tab, config, eval, export, shortcuts, styles, lang, lang::PickListWrapper};
 use crate::config::SETTINGS_FILE;
 
 #[derive(Debug, Clone)]
@@ -14,19 +14,45 @@ pub enum SettingsMessage {
     CheckAutoLoad(bool),
     CheckFlipBoard(bool),
     CheckShowCoords(bool),
+    CheckShowEvalBar(bool),
+    SelectBlindfold(config::Blindfold),
+    ChangeBlindfoldSeconds(String),
+    CheckDescribePosition(bool),
+    ChangeTtsCommand(String),
+    CheckAnimateMoves(bool),
+    ChangeAnimationMs(String),
+    ChangeReplyDelayMs(String),
+    ToggleShortcuts,
+    RebindKey(shortcuts::Action),
+    KeyPressed(String),
+    ResetShortcuts,
+    ChangeEngineArrows(String),
+    ChangeVerifyDepth(String),
+    ChangeVerifyMargin(String),
     SelectPieceTheme(styles::PieceTheme),
     SelectBoardTheme(styles::BoardTheme),
     SelectLanguage(PickListWrapper<lang::Language>),
     ChangePDFExportPgs(String),
+    SelectPDFDiagramsPerPage(usize),
+    ChangePDFSquareSize(String),
+    SelectPDFPageSize(export::PageSize),
+    CheckPDFLandscape(bool),
+    CheckPDFAnswerKey(bool),
     ChangePuzzleDbLocation(String),
     ChangeSearchResultLimit(String),
     ChangeEnginePath(String),
+    ChangePlayEngineLimit(String),
     SearchEnginePressed,
+    ToggleEngineLog,
+    ClearEngineLog,
+    ChangeUciCommand(String),
+    SendUciCommand,
     ChangePressed
 }
 
 pub struct SettingsTab {
     pub engine_path: String,
+    pub play_engine_limit: String,
     pub window_width: f32,
     pub window_height: f32,
     pub maximized: bool,
@@ -34,16 +60,38 @@ pub struct SettingsTab {
     pub board_theme: styles::BoardTheme,
     pub lang: PickListWrapper<lang::Language>,
     pub export_pgs: String,
+    pub pdf_diagrams_per_page: usize,
+    pub pdf_square_size: String,
+    pub pdf_page_size: export::PageSize,
+    pub pdf_landscape: bool,
+    pub pdf_answer_key: bool,
     theme: styles::BoardTheme,
     play_sound: bool,
     auto_load_next: bool,
     pub flip_board: bool,
     pub show_coordinates: bool,
+    pub show_eval_bar: bool,
+    pub blindfold: config::Blindfold,
+    pub blindfold_seconds: String,
+    pub key_bindings: shortcuts::KeyBindings,
+    // The action waiting for its new key
+    pub rebinding: Option<shortcuts::Action>,
+    show_shortcuts: bool,
+    pub describe_position: bool,
+    pub tts_command: String,
+    pub animate_moves: bool,
+    pub animation_ms: String,
+    pub reply_delay_ms: String,
+    pub engine_arrows: String,
+    pub verify_depth: String,
+    pub verify_margin: String,
 
     puzzle_db_location_value: String,
     search_results_limit_value: String,
 
     settings_status: String,
+    show_engine_log: bool,
+    uci_command: String,
     pub saved_configs: config::OfflinePuzzlesConfig,
 }
 
@@ -51,6 +99,7 @@ impl SettingsTab {
     pub fn new() -> Self {
         SettingsTab {
             engine_path: config::SETTINGS.engine_path.clone().unwrap_or_default(),
+            play_engine_limit: config::SETTINGS.play_engine_limit.clone(),
             window_width: config::SETTINGS.window_width,
             window_height: config::SETTINGS.window_width,
             maximized: config::SETTINGS.maximized,
@@ -58,14 +107,35 @@ impl SettingsTab {
             board_theme: config::SETTINGS.board_theme,
             lang: PickListWrapper::new_lang(config::SETTINGS.lang, config::SETTINGS.lang),
             export_pgs: config::SETTINGS.export_pgs.to_string(),
+            pdf_diagrams_per_page: config::SETTINGS.pdf_diagrams_per_page,
+            pdf_square_size: config::SETTINGS.pdf_square_size.to_string(),
+            pdf_page_size: config::SETTINGS.pdf_page_size,
+            pdf_landscape: config::SETTINGS.pdf_landscape,
+            pdf_answer_key: config::SETTINGS.pdf_answer_key,
             theme: styles::BoardTheme::Blue,
             play_sound: config::SETTINGS.play_sound,
             auto_load_next: config::SETTINGS.auto_load_next,
             flip_board: config::SETTINGS.flip_board,
             show_coordinates: config::SETTINGS.show_coordinates,
+            show_eval_bar: config::SETTINGS.show_eval_bar,
+            blindfold: config::SETTINGS.blindfold,
+            blindfold_seconds: config::SETTINGS.blindfold_seconds.to_string(),
+            key_bindings: config::SETTINGS.key_bindings.clone(),
+            rebinding: None,
+            show_shortcuts: false,
+            describe_position: config::SETTINGS.describe_position,
+            tts_command: config::SETTINGS.tts_command.clone(),
+            animate_moves: config::SETTINGS.animate_moves,
+            animation_ms: config::SETTINGS.animation_ms.to_string(),
+            reply_delay_ms: config::SETTINGS.reply_delay_ms.to_string(),
+            engine_arrows: config::SETTINGS.engine_arrows.to_string(),
+            verify_depth: config::SETTINGS.verify_depth.to_string(),
+            verify_margin: config::SETTINGS.verify_margin.to_string(),
             puzzle_db_location_value: String::from(&config::SETTINGS.puzzle_db_location),
             search_results_limit_value: config::SETTINGS.search_results_limit.to_string(),
             settings_status: String::new(),
+            show_engine_log: false,
+            uci_command: String::new(),
             saved_configs: config::load_config(),
         }
     }
@@ -93,9 +163,32 @@ impl SettingsTab {
                 self.engine_path = value;
                 Task::perform(SettingsTab::send_changes(self.play_sound, self.auto_load_next, self.flip_board, self.show_coordinates, self.piece_theme, self.board_theme, self.engine_path.clone(), self.lang.lang), Message::ChangeSettings)
             }
+            SettingsMessage::ChangePlayEngineLimit(value) => {
+                self.play_engine_limit = value;
+                Task::none()
+            }
             SettingsMessage::SearchEnginePressed => {
                 Task::perform(Self::open_engine_exe(), Message::EngineFileChosen)
             }
+            SettingsMessage::ToggleEngineLog => {
+                self.show_engine_log = !self.show_engine_log;
+                Task::none()
+            }
+            SettingsMessage::ClearEngineLog => {
+                eval::clear_engine_log();
+                Task::none()
+            }
+            SettingsMessage::ChangeUciCommand(value) => {
+                self.uci_command = value;
+                Task::none()
+            }
+            SettingsMessage::SendUciCommand => {
+                if self.uci_command.trim().is_empty() {
+                    Task::none()
+                } else {
+                    Task::done(Message::SendUciCommand(std::mem::take(&mut self.uci_command)))
+                }
+            }
             SettingsMessage::ChangeSearchResultLimit(value) => {
                 if value.is_empty() {
                     self.search_results_limit_value = String::from("0");
@@ -121,6 +214,97 @@ impl SettingsTab {
                 self.show_coordinates = value;
                 Task::none()
             }
+            SettingsMessage::CheckShowEvalBar(value) => {
+                self.show_eval_bar = value;
+                Task::none()
+            }
+            SettingsMessage::SelectBlindfold(value) => {
+                self.blindfold = value;
+                Task::none()
+            }
+            SettingsMessage::ToggleShortcuts => {
+                self.show_shortcuts = !self.show_shortcuts;
+                self.rebinding = None;
+                Task::none()
+            }
+            SettingsMessage::RebindKey(action) => {
+                self.rebinding = Some(action);
+                Task::none()
+            }
+            SettingsMessage::KeyPressed(key) => {
+                // Escape cancels it, so it can't be bound
+                if let Some(action) = self.rebinding.take() {
+                    if key != "Escape" {
+                        self.key_bindings.set(action, key);
+                    }
+                }
+                Task::none()
+            }
+            SettingsMessage::ResetShortcuts => {
+                self.key_bindings = shortcuts::KeyBindings::default();
+                self.rebinding = None;
+                Task::none()
+            }
+            SettingsMessage::ChangeBlindfoldSeconds(value) => {
+                if value.parse::<u64>().is_ok() {
+                    self.blindfold_seconds = value;
+                } else if value.is_empty() {
+                    self.blindfold_seconds = String::from("0");
+                }
+                Task::none()
+            }
+            SettingsMessage::CheckDescribePosition(value) => {
+                self.describe_position = value;
+                Task::none()
+            }
+            SettingsMessage::ChangeTtsCommand(value) => {
+                self.tts_command = value;
+                Task::none()
+            }
+            SettingsMessage::CheckAnimateMoves(value) => {
+                self.animate_moves = value;
+                Task::none()
+            }
+            SettingsMessage::ChangeAnimationMs(value) => {
+                if value.parse::<u64>().is_ok() {
+                    self.animation_ms = value;
+                } else if value.is_empty() {
+                    self.animation_ms = String::from("0");
+                }
+                Task::none()
+            }
+            SettingsMessage::ChangeReplyDelayMs(value) => {
+                if value.parse::<u64>().is_ok() {
+                    self.reply_delay_ms = value;
+                } else if value.is_empty() {
+                    self.reply_delay_ms = String::from("0");
+                }
+                Task::none()
+            }
+            SettingsMessage::ChangeEngineArrows(value) => {
+                if value.parse::<usize>().is_ok() {
+                    self.engine_arrows = value;
+                } else if value.is_empty() {
+                    self.engine_arrows = String::from("0");
+                }
+                Task::none()
+            }
+            SettingsMessage::ChangeVerifyDepth(value) => {
+                if value.parse::<u32>().is_ok() {
+                    self.verify_depth = value;
+                } else if value.is_empty() {
+                    self.verify_depth = String::from("0");
+                }
+                Task::none()
+            }
+            SettingsMessage::ChangeVerifyMargin(value) => {
+                if value.parse::<i32>().is_ok() {
+                    self.verify_margin = value;
+                } else if value.is_empty() {
+                    self.verify_margin = String::from("0");
+                }
+                Task::none()
+            }
             SettingsMessage::ChangePDFExportPgs(value) => {
                 if value.parse::<i32>().is_ok() {
                     self.export_pgs = value;
@@ -129,6 +313,30 @@ impl SettingsTab {
                 }
                 Task::none()
             },
+            SettingsMessage::SelectPDFDiagramsPerPage(value) => {
+                self.pdf_diagrams_per_page = value;
+                Task::none()
+            }
+            SettingsMessage::ChangePDFSquareSize(value) => {
+                if value.parse::<i32>().is_ok() {
+                    self.pdf_square_size = value;
+                } else if value.is_empty() {
+                    self.pdf_square_size = String::from("0");
+                }
+                Task::none()
+            }
+            SettingsMessage::SelectPDFPageSize(value) => {
+                self.pdf_page_size = value;
+                Task::none()
+            }
+            SettingsMessage::CheckPDFLandscape(value) => {
+                self.pdf_landscape = value;
+                Task::none()
+            }
+            SettingsMessage::CheckPDFAnswerKey(value) => {
+                self.pdf_answer_key = value;
+                Task::none()
+            }
             SettingsMessage::ChangePressed => {
                 let engine_path = if self.engine_path.is_empty() {
                     None
@@ -138,6 +346,7 @@ impl SettingsTab {
                 let config = config::OfflinePuzzlesConfig {
                     engine_path: engine_path,
                     engine_limit: self.saved_configs.engine_limit.clone(),
+                    play_engine_limit: self.play_engine_limit.clone(),
                     window_width: self.window_width,
                     window_height: self.window_height,
                     maximized: self.maximized,
@@ -148,9 +357,26 @@ impl SettingsTab {
                     auto_load_next: self.auto_load_next,
                     flip_board: self.flip_board,
                     show_coordinates: self.show_coordinates,
+                    show_eval_bar: self.show_eval_bar,
+                    engine_arrows: self.engine_arrows.parse().unwrap(),
+                    verify_depth: self.verify_depth.parse().unwrap(),
+                    verify_margin: self.verify_margin.parse().unwrap(),
                     board_theme: self.board_theme,
                     lang: self.lang.lang,
                     export_pgs: self.export_pgs.parse().unwrap(),
+                    pdf_diagrams_per_page: self.pdf_diagrams_per_page,
+                    pdf_square_size: self.pdf_square_size.parse().unwrap(),
+                    pdf_page_size: self.pdf_page_size,
+                    pdf_landscape: self.pdf_landscape,
+                    pdf_answer_key: self.pdf_answer_key,
+                    blindfold: self.blindfold,
+                    blindfold_seconds: self.blindfold_seconds.parse().unwrap(),
+                    key_bindings: self.key_bindings.clone(),
+                    describe_position: self.describe_position,
+                    tts_command: self.tts_command.clone(),
+                    animate_moves: self.animate_moves,
+                    animation_ms: self.animation_ms.parse().unwrap(),
+                    reply_delay_ms: self.reply_delay_ms.parse().unwrap(),
                     last_min_rating: self.saved_configs.last_min_rating,
                     last_max_rating: self.saved_configs.last_max_rating,
                     last_min_popularity: self.saved_configs.last_min_popularity,
@@ -194,6 +420,32 @@ impl SettingsTab {
         }
     }
 
+    fn shortcuts_view(&self) -> Element<'_, SettingsMessage, Theme, iced::Renderer> {
+        if !self.show_shortcuts {
+            return Button::new(Text::new(lang::tr(&self.lang.lang, "show_shortcuts"))).padding(5).on_press(SettingsMessage::ToggleShortcuts).into();
+        }
+        let mut col = Column::new().spacing(5).align_x(Alignment::Center).push(
+            row![
+                Button::new(Text::new(lang::tr(&self.lang.lang, "hide_shortcuts"))).padding(5).on_press(SettingsMessage::ToggleShortcuts),
+                Button::new(Text::new(lang::tr(&self.lang.lang, "reset_shortcuts"))).padding(5).on_press(SettingsMessage::ResetShortcuts),
+            ].spacing(10)
+        );
+        for action in shortcuts::Action::ALL {
+            let key = if self.rebinding == Some(action) {
+                lang::tr(&self.lang.lang, "press_a_key")
+            } else {
+                String::from(self.key_bindings.key(action))
+            };
+            col = col.push(
+                row![
+                    Text::new(lang::tr(&self.lang.lang, action.tr_key())).width(200),
+                    Button::new(Text::new(key)).width(150).padding(5).on_press(SettingsMessage::RebindKey(action)),
+                ].spacing(5).align_y(Alignment::Center)
+            );
+        }
+        col.into()
+    }
+
     pub async fn send_changes(play_sound: bool, auto_load: bool, flip: bool, coords: bool, pieces: styles::PieceTheme, theme: styles::BoardTheme, engine: String, lang: lang::Language) -> Option<config::OfflinePuzzlesConfig> {
         let engine = if engine.is_empty() {
             None
@@ -266,6 +518,58 @@ impl Tab for SettingsTab {
                 Text::new(lang::tr(&self.lang.lang, "show_coords")),
                 Checkbox::new(self.show_coordinates).on_toggle(SettingsMessage::CheckShowCoords).size(20),
             ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "show_eval_bar")),
+                Checkbox::new(self.show_eval_bar).on_toggle(SettingsMessage::CheckShowEvalBar).size(20),
+            ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "blindfold")),
+                Radio::new(lang::tr(&self.lang.lang, "blindfold_off"), config::Blindfold::Off, Some(self.blindfold), SettingsMessage::SelectBlindfold),
+                Radio::new(lang::tr(&self.lang.lang, "blindfold_hide_all"), config::Blindfold::HideAll, Some(self.blindfold), SettingsMessage::SelectBlindfold),
+                Radio::new(lang::tr(&self.lang.lang, "blindfold_pawns_only"), config::Blindfold::PawnsOnly, Some(self.blindfold), SettingsMessage::SelectBlindfold),
+            ].spacing(10).align_y(Alignment::Center),
+            row![
+                Radio::new(lang::tr(&self.lang.lang, "blindfold_hide_after"), config::Blindfold::HideAfter, Some(self.blindfold), SettingsMessage::SelectBlindfold),
+                TextInput::new(
+                    &self.blindfold_seconds,
+                    &self.blindfold_seconds,
+                ).on_input(SettingsMessage::ChangeBlindfoldSeconds).width(60),
+                Text::new(lang::tr(&self.lang.lang, "blindfold_seconds")),
+            ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "describe_position")),
+                Checkbox::new(self.describe_position).on_toggle(SettingsMessage::CheckDescribePosition).size(20),
+            ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "tts_command")),
+                TextInput::new(
+                    "espeak-ng",
+                    &self.tts_command,
+                ).on_input(SettingsMessage::ChangeTtsCommand).width(200),
+            ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "animate_moves")),
+                Checkbox::new(self.animate_moves).on_toggle(SettingsMessage::CheckAnimateMoves).size(20),
+                Text::new(lang::tr(&self.lang.lang, "animation_ms")),
+                TextInput::new(
+                    &self.animation_ms,
+                    &self.animation_ms,
+                ).on_input(SettingsMessage::ChangeAnimationMs).width(60),
+            ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "reply_delay_ms")),
+                TextInput::new(
+                    &self.reply_delay_ms,
+                    &self.reply_delay_ms,
+                ).on_input(SettingsMessage::ChangeReplyDelayMs).width(60),
+            ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "engine_arrows")),
+                TextInput::new(
+                    &self.engine_arrows,
+                    &self.engine_arrows,
+                ).on_input(SettingsMessage::ChangeEngineArrows).width(60),
+            ].spacing(5).align_y(Alignment::Center),
             row![
                 Text::new(lang::tr(&self.lang.lang, "pdf_number_of_pages")),
                 TextInput::new(
@@ -273,6 +577,33 @@ impl Tab for SettingsTab {
                     &self.export_pgs,
                 ).on_input(SettingsMessage::ChangePDFExportPgs).width(60),
             ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "pdf_diagrams_per_page")),
+                PickList::new(
+                    &export::DIAGRAMS_PER_PAGE[..],
+                    Some(self.pdf_diagrams_per_page),
+                    SettingsMessage::SelectPDFDiagramsPerPage
+                ).style(styles::pick_list_style).menu_style(styles::menu_style),
+                Text::new(lang::tr(&self.lang.lang, "pdf_square_size")),
+                TextInput::new(
+                    &self.pdf_square_size,
+                    &self.pdf_square_size,
+                ).on_input(SettingsMessage::ChangePDFSquareSize).width(60),
+            ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "pdf_page_size")),
+                PickList::new(
+                    &export::PageSize::ALL[..],
+                    Some(self.pdf_page_size),
+                    SettingsMessage::SelectPDFPageSize
+                ).style(styles::pick_list_style).menu_style(styles::menu_style),
+                Text::new(lang::tr(&self.lang.lang, "pdf_landscape")),
+                Checkbox::new(self.pdf_landscape).on_toggle(SettingsMessage::CheckPDFLandscape).size(20),
+            ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "pdf_answer_key_option")),
+                Checkbox::new(self.pdf_answer_key).on_toggle(SettingsMessage::CheckPDFAnswerKey).size(20),
+            ].spacing(5).align_y(Alignment::Center),
             row![
                 Text::new(lang::tr(&self.lang.lang, "get_first_puzzles1")),
                 TextInput::new(
@@ -289,10 +620,60 @@ impl Tab for SettingsTab {
                 ).on_input(SettingsMessage::ChangeEnginePath).width(200),
                 Button::new(Text::new("Select")).on_press(SettingsMessage::SearchEnginePressed),
             ],
+            row![
+                Text::new(lang::tr(&self.lang.lang, "play_engine_limit")),
+                TextInput::new(
+                    &self.play_engine_limit,
+                    &self.play_engine_limit,
+                ).on_input(SettingsMessage::ChangePlayEngineLimit).width(150),
+            ].spacing(5).align_y(Alignment::Center),
+            row![
+                Text::new(lang::tr(&self.lang.lang, "verify_depth")),
+                TextInput::new(
+                    &self.verify_depth,
+                    &self.verify_depth,
+                ).on_input(SettingsMessage::ChangeVerifyDepth).width(60),
+                Text::new(lang::tr(&self.lang.lang, "verify_margin")),
+                TextInput::new(
+                    &self.verify_margin,
+                    &self.verify_margin,
+                ).on_input(SettingsMessage::ChangeVerifyMargin).width(60),
+            ].spacing(5).align_y(Alignment::Center),
+            self.shortcuts_view(),
             Button::new(Text::new(lang::tr(&self.lang.lang, "save"))).padding(5).on_press(SettingsMessage::ChangePressed),
             Text::new(&self.settings_status).align_y(alignment::Vertical::Bottom),
 
         ].spacing(10).align_x(Alignment::Center);
+        let col_settings = if self.show_engine_log {
+            let log_lines = eval::engine_log().iter().fold(Column::new(), |col, entry| {
+                let style = match entry.source {
+                    eval::LogSource::Gui => text::primary,
+                    eval::LogSource::Engine => text::default,
+                    eval::LogSource::Stderr | eval::LogSource::Supervisor => text::danger,
+                };
+                col.push(Text::new(entry.format()).size(12).style(style))
+            });
+            col_settings.push(
+                row![
+                    Button::new(Text::new(lang::tr(&self.lang.lang, "hide_engine_log"))).padding(5).on_press(SettingsMessage::ToggleEngineLog),
+                    Button::new(Text::new(lang::tr(&self.lang.lang, "clear_engine_log"))).padding(5).on_press(SettingsMessage::ClearEngineLog),
+                ].spacing(10)
+            ).push(
+                Container::new(
+                    Scrollable::new(log_lines.padding(5)).anchor_bottom().width(Length::Fill)
+                ).height(250).width(Length::Fill).style(styles::container_style_log)
+            ).push(
+                row![
+                    TextInput::new(
+                        &lang::tr(&self.lang.lang, "uci_command"),
+                        &self.uci_command,
+                    ).on_input(SettingsMessage::ChangeUciCommand).on_submit(SettingsMessage::SendUciCommand),
+                    Button::new(Text::new(lang::tr(&self.lang.lang, "send_uci_command"))).padding(5).on_press(SettingsMessage::SendUciCommand),
+                ].spacing(5).align_y(Alignment::Center)
+            )
+        } else {
+            col_settings.push(Button::new(Text::new(lang::tr(&self.lang.lang, "show_engine_log"))).padding(5).on_press(SettingsMessage::ToggleEngineLog))
+        };
         let content: Element<SettingsMessage, Theme, iced::Renderer> = Container::new(
             Scrollable::new(
                 Column::new().padding([0, 30]).spacing(10).push(col_settings)
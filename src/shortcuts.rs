@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use iced::keyboard::{key::Named, Key, Modifiers};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    CursorUp,
+    CursorDown,
+    CursorLeft,
+    CursorRight,
+    // Selects the square under the cursor, or moves the selected piece there
+    Select,
+    Hint,
+    NextPuzzle,
+    PreviousPuzzle,
+    Redo,
+    Takeback,
+    Flip,
+    ToggleEngine,
+    Favorite,
+    ReadPosition,
+}
+
+impl Action {
+    pub const ALL: [Action; 14] = [
+        Action::CursorUp, Action::CursorDown, Action::CursorLeft, Action::CursorRight, Action::Select,
+        Action::Hint, Action::NextPuzzle, Action::PreviousPuzzle, Action::Redo, Action::Takeback,
+        Action::Flip, Action::ToggleEngine, Action::Favorite, Action::ReadPosition,
+    ];
+
+    pub fn tr_key(&self) -> &'static str {
+        match self {
+            Action::CursorUp => "shortcut_cursor_up",
+            Action::CursorDown => "shortcut_cursor_down",
+            Action::CursorLeft => "shortcut_cursor_left",
+            Action::CursorRight => "shortcut_cursor_right",
+            Action::Select => "shortcut_select",
+            Action::Hint => "shortcut_hint",
+            Action::NextPuzzle => "shortcut_next",
+            Action::PreviousPuzzle => "shortcut_previous",
+            Action::Redo => "shortcut_redo",
+            Action::Takeback => "shortcut_takeback",
+            Action::Flip => "shortcut_flip",
+            Action::ToggleEngine => "shortcut_engine",
+            Action::Favorite => "shortcut_favorite",
+            Action::ReadPosition => "shortcut_read_position",
+        }
+    }
+
+    fn default_key(&self) -> &'static str {
+        match self {
+            Action::CursorUp => "ArrowUp",
+            Action::CursorDown => "ArrowDown",
+            Action::CursorLeft => "ArrowLeft",
+            Action::CursorRight => "ArrowRight",
+            Action::Select => "Enter",
+            Action::Hint => "h",
+            Action::NextPuzzle => "n",
+            Action::PreviousPuzzle => "p",
+            Action::Redo => "r",
+            Action::Takeback => "Backspace",
+            Action::Flip => "f",
+            Action::ToggleEngine => "e",
+            Action::Favorite => "m",
+            Action::ReadPosition => "d",
+        }
+    }
+}
+
+// The key of each action, by the names from key_name(). Only the changed ones
+// are needed in the settings file, the others keep their default keys.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct KeyBindings {
+    keys: HashMap<Action, String>,
+}
+
+impl KeyBindings {
+    pub fn key(&self, action: Action) -> &str {
+        self.keys.get(&action).map_or(action.default_key(), String::as_str)
+    }
+
+    // A key can only do one thing, so it's taken from the action that had it
+    pub fn set(&mut self, action: Action, key: String) {
+        if let Some(previous) = self.action(&key) {
+            self.keys.insert(previous, String::new());
+        }
+        self.keys.insert(action, key);
+    }
+
+    pub fn action(&self, key: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| self.key(*action) == key)
+    }
+}
+
+// Like "ArrowUp", "h", "H" or "Ctrl+f", from the key with the modifiers applied (but Ctrl).
+// None for the modifier keys alone.
+pub fn key_name(key: &Key, modifiers: Modifiers) -> Option<String> {
+    let name = match key {
+        Key::Named(Named::Shift | Named::Control | Named::Alt | Named::Super) => return None,
+        Key::Named(named) => {
+            // The characters already change with shift, the named keys don't
+            if modifiers.shift() {
+                format!("Shift+{:?}", named)
+            } else {
+                format!("{:?}", named)
+            }
+        }
+        Key::Character(character) => character.to_string(),
+        Key::Unidentified => return None,
+    };
+    let mut prefix = String::new();
+    if modifiers.control() {
+        prefix.push_str("Ctrl+");
+    }
+    if modifiers.alt() {
+        prefix.push_str("Alt+");
+    }
+    Some(prefix + &name)
+}
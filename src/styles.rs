@@ -254,7 +254,7 @@ pub fn container_style_selected_dark_square(_theme: &iced::Theme) -> container::
     }
 }
 
-pub fn _container_style_paper(_theme: &iced::Theme) -> container::Style {
+pub fn container_style_paper(_theme: &iced::Theme) -> container::Style {
     //let palette = theme.palette();
     container::Style {
         background: Some(iced::Background::Color(rgb!(245., 245., 245.))),
@@ -268,6 +268,48 @@ pub fn _container_style_paper(_theme: &iced::Theme) -> container::Style {
     }
 }
 
+// The keyboard's cursor, a frame over the usual square
+const CURSOR_COLOR: iced::Color = rgb!(30, 110, 220);
+const CURSOR_BORDER: Border = Border {
+    color: CURSOR_COLOR,
+    width: 3.,
+    radius: iced::border::Radius { top_left: 0., top_right: 0., bottom_right: 0., bottom_left: 0. },
+};
+
+pub fn btn_style_cursor_light_square(theme: &iced::Theme, status: iced::widget::button::Status) -> button::Style {
+    button::Style { border: CURSOR_BORDER, ..btn_style_light_square(theme, status) }
+}
+
+pub fn btn_style_cursor_dark_square(theme: &iced::Theme, status: iced::widget::button::Status) -> button::Style {
+    button::Style { border: CURSOR_BORDER, ..btn_style_dark_square(theme, status) }
+}
+
+pub fn btn_style_cursor_paper(theme: &iced::Theme, status: iced::widget::button::Status) -> button::Style {
+    button::Style { border: CURSOR_BORDER, ..btn_style_paper(theme, status) }
+}
+
+pub fn container_style_cursor_light_square(theme: &iced::Theme) -> container::Style {
+    container::Style { border: CURSOR_BORDER, ..container_style_light_square(theme) }
+}
+
+pub fn container_style_cursor_dark_square(theme: &iced::Theme) -> container::Style {
+    container::Style { border: CURSOR_BORDER, ..container_style_dark_square(theme) }
+}
+
+pub fn container_style_eval_white(_theme: &iced::Theme) -> container::Style {
+    container::Style {
+        background: Some(iced::Background::Color(rgb!(245., 245., 245.))),
+        ..Default::default()
+    }
+}
+
+pub fn container_style_eval_black(_theme: &iced::Theme) -> container::Style {
+    container::Style {
+        background: Some(iced::Background::Color(rgb!(45., 45., 45.))),
+        ..Default::default()
+    }
+}
+
 pub fn tab_style(theme: &iced::Theme, status: iced_aw::style::Status) -> tab_bar::Style {
     let palette = theme.extended_palette();
     match status {
@@ -533,3 +575,16 @@ impl OCPPalette {
         tab_label: Color::BLACK,
     };
 }
+
+pub fn container_style_log(theme: &iced::Theme) -> container::Style {
+    let palette = theme.extended_palette();
+    container::Style {
+        background: Some(iced::Background::Color(palette.background.weak.color)),
+        border: Border {
+            color: palette.background.strong.color,
+            width: 1.,
+            radius: 2.0.into(),
+        },
+        ..Default::default()
+    }
+}
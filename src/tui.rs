@@ -0,0 +1,321 @@
+// Text mode to solve puzzles in a terminal (over SSH, for example), started with
+// "offline-chess-puzzles solve". Moves are typed in SAN or UCI, one command per line.
+use std::io::{self, BufRead, Write};
+use chess::{Color, Piece, Square, ALL_SQUARES};
+use offline_chess_puzzles::session::{self, MoveError, PuzzleSession, Verdict};
+
+use crate::{accessibility, config, db, lang, styles};
+
+const HELP: &str = "Type a move (Nf3, exd8=Q, g1f3, e7d8q) or one of the commands:
+  h, hint       Show the piece to move
+  s, solution   Show the move of the solution
+  n, next       Next puzzle
+  p, prev       Previous puzzle
+  g, goto <n>   Go to the puzzle number n
+  r, redo       Start the puzzle again
+  f, fav        Add or remove the puzzle from the favorites
+  flip          Flip the board
+  d, describe   List the pieces of each side
+  ?, help       Show this help
+  q, quit       Exit";
+
+struct Tui {
+    puzzles: Vec<config::Puzzle>,
+    current_puzzle: usize,
+    session: Option<PuzzleSession>,
+    hint_square: Option<Square>,
+    flipped: bool,
+    color: bool,
+    auto_load_next: bool,
+    lang: lang::Language,
+}
+
+pub fn run(puzzles: Vec<config::Puzzle>, color: bool) -> i32 {
+    if puzzles.is_empty() {
+        println!("{}", lang::tr(&config::SETTINGS.lang, "no_puzzle_found"));
+        return 0;
+    }
+    let mut tui = Tui {
+        puzzles,
+        current_puzzle: 0,
+        session: None,
+        hint_square: None,
+        flipped: config::SETTINGS.flip_board,
+        color,
+        auto_load_next: config::SETTINGS.auto_load_next,
+        lang: config::SETTINGS.lang,
+    };
+    tui.load_puzzle();
+    println!("{}", HELP);
+    tui.draw();
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        let _ = io::stdout().flush();
+        let Some(Ok(line)) = lines.next() else {
+            return 0;
+        };
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+        match command {
+            "q" | "quit" | "exit" => return 0,
+            "?" | "help" => println!("{}", HELP),
+            "h" | "hint" => {
+                tui.hint_square = tui.session.as_ref().and_then(PuzzleSession::hint);
+                tui.draw();
+            }
+            "s" | "solution" => tui.show_solution(),
+            "n" | "next" => tui.go_to(tui.current_puzzle + 1),
+            "p" | "prev" => tui.go_to(tui.current_puzzle.wrapping_sub(1)),
+            "g" | "goto" => match words.next().and_then(|number| number.parse::<usize>().ok()) {
+                Some(number) => tui.go_to(number.wrapping_sub(1)),
+                None => println!("goto <1-{}>", tui.puzzles.len()),
+            },
+            "r" | "redo" => {
+                tui.load_puzzle();
+                tui.draw();
+            }
+            "f" | "fav" => {
+                db::toggle_favorite(tui.puzzles[tui.current_puzzle].clone());
+                tui.draw();
+            }
+            "d" | "describe" => {
+                if let Some(session) = &tui.session {
+                    println!("{}", accessibility::describe_position(&session.board(), &tui.lang));
+                }
+            }
+            "flip" => {
+                tui.flipped = !tui.flipped;
+                tui.draw();
+            }
+            _ => tui.play(command),
+        }
+    }
+}
+
+impl Tui {
+    fn load_puzzle(&mut self) {
+        let puzzle = &self.puzzles[self.current_puzzle];
+        self.session = PuzzleSession::new(&puzzle.fen, &puzzle.moves);
+        self.hint_square = None;
+    }
+
+    fn go_to(&mut self, index: usize) {
+        if index < self.puzzles.len() {
+            self.current_puzzle = index;
+            self.load_puzzle();
+            self.draw();
+        } else {
+            println!("1-{}", self.puzzles.len());
+        }
+    }
+
+    fn play(&mut self, text: &str) {
+        let Some(session) = &mut self.session else {
+            println!("{}", lang::tr(&self.lang, "invalid_puzzle"));
+            return;
+        };
+        if session.is_solved() {
+            if self.current_puzzle < self.puzzles.len() - 1 {
+                println!("{}", lang::tr(&self.lang, "tui_puzzle_solved"));
+            } else {
+                println!("{}", lang::tr(&self.lang, "all_puzzles_done"));
+            }
+            return;
+        }
+        let board = session.board();
+        let wrong_move = if board.side_to_move() == Color::White {
+            lang::tr(&self.lang, "wrong_move_white_play")
+        } else {
+            lang::tr(&self.lang, "wrong_move_black_play")
+        };
+        let movement = match session::read_move(&board, text, &config::piece_letters(&self.lang)) {
+            Ok(movement) => movement,
+            Err(MoveError::Unreadable) => {
+                println!("{}", lang::tr(&self.lang, "move_unreadable"));
+                return;
+            }
+            Err(MoveError::Illegal) => {
+                println!("{}", lang::tr(&self.lang, "move_illegal"));
+                return;
+            }
+            Err(MoveError::Ambiguous(moves)) => {
+                let moves: Vec<String> = moves.iter().map(|movement| {
+                    config::coord_to_san(&board, movement.to_string(), &self.lang).unwrap_or(movement.to_string())
+                }).collect();
+                println!("{}{}", lang::tr(&self.lang, "move_ambiguous"), moves.join(", "));
+                return;
+            }
+        };
+        match session.submit_move(movement) {
+            Verdict::Illegal | Verdict::Wrong => println!("{}", wrong_move),
+            Verdict::Correct { .. } if !session.is_solved() => {
+                self.hint_square = None;
+                self.draw();
+                println!("{}", lang::tr(&self.lang, "correct_move"));
+            }
+            // A solution ending in the opponent's reply is over too
+            Verdict::Correct { .. } | Verdict::Solved => self.puzzle_solved(),
+        }
+    }
+
+    fn puzzle_solved(&mut self) {
+        self.hint_square = None;
+        self.draw();
+        if self.current_puzzle < self.puzzles.len() - 1 {
+            println!("{}", lang::tr(&self.lang, "correct_puzzle"));
+            if self.auto_load_next {
+                self.go_to(self.current_puzzle + 1);
+            }
+        } else {
+            println!("{}", lang::tr(&self.lang, "all_puzzles_done"));
+        }
+    }
+
+    fn show_solution(&self) {
+        if let Some(session) = &self.session {
+            if let Some(movement) = session.solution_move() {
+                let uci = movement.to_string();
+                println!("{}", config::coord_to_san(&session.board(), uci.clone(), &self.lang).unwrap_or(uci));
+            }
+        }
+    }
+
+    fn draw(&self) {
+        let puzzle = &self.puzzles[self.current_puzzle];
+        println!();
+        println!("{}{} / {}  (https://lichess.org/training/{}){}", lang::tr(&self.lang, "puzzle"),
+            self.current_puzzle + 1, self.puzzles.len(), puzzle.puzzle_id,
+            if db::is_favorite(&puzzle.puzzle_id) { " *" } else { "" });
+        if puzzle.rating > 0 {
+            println!("{}{}", lang::tr(&self.lang, "rating"), puzzle.rating);
+        }
+        let Some(session) = &self.session else {
+            println!("{}", lang::tr(&self.lang, "invalid_puzzle"));
+            return;
+        };
+        let board = session.board();
+        let highlights: Vec<Square> = session.last_move().map_or(vec![], |movement| vec![movement.get_source(), movement.get_dest()]);
+        let white_at_bottom = (session.solver() == Color::White) != self.flipped;
+        let palette = config::SETTINGS.board_theme.palette();
+
+        let mut squares: Vec<Square> = ALL_SQUARES.to_vec();
+        // From the top left corner of the screen
+        squares.sort_by_key(|square| {
+            let (rank, file) = (square.get_rank().to_index(), square.get_file().to_index());
+            if white_at_bottom { (7 - rank, file) } else { (rank, 7 - file) }
+        });
+        for row in squares.chunks(8) {
+            let rank = row[0].get_rank().to_index() + 1;
+            let mut line = format!("{} ", rank);
+            for square in row {
+                let light_square = (square.get_rank().to_index() + square.get_file().to_index()) % 2 != 0;
+                let glyph = match (board.piece_on(*square), board.color_on(*square)) {
+                    (Some(piece), Some(piece_color)) => piece_glyph(piece, piece_color, self.color),
+                    _ if self.color => ' ',
+                    _ if light_square => '·',
+                    _ => ':',
+                };
+                if self.color {
+                    let background = match (light_square, highlights.contains(square) || self.hint_square == Some(*square)) {
+                        (true, false) => palette.light_square,
+                        (false, false) => palette.dark_square,
+                        (true, true) => styles::SELECTED_LIGHT_SQUARE,
+                        (false, true) => styles::SELECTED_DARK_SQUARE,
+                    };
+                    let [r, g, b, _] = background.into_rgba8();
+                    let foreground = if board.color_on(*square) == Some(Color::White) { "255;255;255" } else { "0;0;0" };
+                    line.push_str(&format!("\x1b[48;2;{r};{g};{b}m\x1b[38;2;{foreground}m {glyph} \x1b[0m"));
+                } else if self.hint_square == Some(*square) {
+                    line.push_str(&format!("[{glyph}]"));
+                } else {
+                    line.push_str(&format!(" {glyph} "));
+                }
+            }
+            println!("{}", line);
+        }
+        let files = if white_at_bottom { "abcdefgh" } else { "hgfedcba" };
+        println!("  {}", files.chars().map(|file| format!(" {} ", file)).collect::<String>());
+        if let Some(last_move) = session.last_move() {
+            println!("{}", last_move);
+        }
+        if !session.is_solved() {
+            if board.side_to_move() == Color::White {
+                println!("{}", lang::tr(&self.lang, "white_to_move"));
+            } else {
+                println!("{}", lang::tr(&self.lang, "black_to_move"));
+            }
+        }
+    }
+}
+
+// With colors both sides use the filled pieces, otherwise the white ones are the outlined pieces
+fn piece_glyph(piece: Piece, color: Color, filled: bool) -> char {
+    let white = color == Color::White && !filled;
+    match (piece, white) {
+        (Piece::King, true) => '♔',
+        (Piece::Queen, true) => '♕',
+        (Piece::Rook, true) => '♖',
+        (Piece::Bishop, true) => '♗',
+        (Piece::Knight, true) => '♘',
+        (Piece::Pawn, true) => '♙',
+        (Piece::King, false) => '♚',
+        (Piece::Queen, false) => '♛',
+        (Piece::Rook, false) => '♜',
+        (Piece::Bishop, false) => '♝',
+        (Piece::Knight, false) => '♞',
+        (Piece::Pawn, false) => '♟',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn puzzle(moves: &str) -> config::Puzzle {
+        config::Puzzle {
+            fen: String::from("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"),
+            moves: String::from(moves),
+            ..config::Puzzle::default()
+        }
+    }
+
+    fn tui(auto_load_next: bool) -> Tui {
+        let mut tui = Tui {
+            // Ending in the opponent's reply, like some imported games
+            puzzles: vec![puzzle("0000 e2e4 e8d7"), puzzle("0000 e2e3")],
+            current_puzzle: 0,
+            session: None,
+            hint_square: None,
+            flipped: false,
+            color: false,
+            auto_load_next,
+            lang: lang::Language::English,
+        };
+        tui.load_puzzle();
+        tui
+    }
+
+    #[test]
+    fn solved_on_the_opponents_reply() {
+        let mut tui = tui(false);
+        tui.play("e4");
+        assert_eq!(tui.current_puzzle, 0);
+        assert!(tui.session.as_ref().is_some_and(PuzzleSession::is_solved));
+    }
+
+    #[test]
+    fn next_puzzle_after_the_opponents_reply() {
+        let mut tui = tui(true);
+        tui.play("e4");
+        assert_eq!(tui.current_puzzle, 1);
+        assert!(tui.session.as_ref().is_some_and(|session| !session.is_solved()));
+        tui.play("e3");
+        assert_eq!(tui.current_puzzle, 1);
+        assert!(tui.session.as_ref().is_some_and(PuzzleSession::is_solved));
+    }
+}
@@ -0,0 +1,152 @@
+use iced::futures::Stream;
+use iced::futures::channel::mpsc;
+use iced::stream;
+use iced::futures::sink::SinkExt;
+use iced::Subscription;
+
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::Arc;
+use chess::Board;
+use tokio::process::Child;
+
+use offline_chess_puzzles::session;
+
+use crate::{Message, config, san_correct_ep};
+use crate::eval::{self, EngineError, EngineOutput, score_to_string};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum IssueKind {
+    NotBest,
+    IllegalMove,
+    InvalidFen,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyIssue {
+    #[serde(rename = "PuzzleId")]
+    pub puzzle_id: String,
+    #[serde(rename = "Issue")]
+    pub kind: IssueKind,
+    // The position where the solution move is played
+    #[serde(rename = "FEN")]
+    pub fen: String,
+    #[serde(rename = "Ply")]
+    pub ply: usize,
+    #[serde(rename = "SolutionMove")]
+    pub solution_move: String,
+    #[serde(rename = "SolutionEval")]
+    pub solution_eval: String,
+    #[serde(rename = "EngineMove")]
+    pub engine_move: String,
+    #[serde(rename = "EngineEval")]
+    pub engine_eval: String,
+}
+
+impl VerifyIssue {
+    fn new(puzzle: &config::Puzzle, kind: IssueKind, fen: String, ply: usize, solution_move: &str) -> Self {
+        Self {
+            puzzle_id: puzzle.puzzle_id.clone(),
+            kind,
+            fen,
+            ply,
+            solution_move: String::from(solution_move),
+            solution_eval: String::new(),
+            engine_move: String::new(),
+            engine_eval: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VerifyJob {
+    pub id: usize,
+    pub engine_path: String,
+    pub depth: u32,
+    pub margin: i32,
+    pub puzzles: Arc<Vec<config::Puzzle>>,
+}
+
+// The puzzles don't need to be hashed, a new job always gets a new id.
+impl Hash for VerifyJob {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.engine_path.hash(state);
+        self.depth.hash(state);
+        self.margin.hash(state);
+    }
+}
+
+impl VerifyJob {
+    pub fn run(self) -> Subscription<Message> {
+        Subscription::run_with(
+            self,
+            VerifyJob::verify_stream,
+        )
+    }
+
+    fn verify_stream(job: &VerifyJob) -> impl Stream<Item = Message> {
+        let job = job.clone();
+        stream::channel(100,
+            async move |mut output| {
+                let result = job.verify_all(&mut output).await;
+                let _ = output.send(Message::VerifyFinished(result)).await;
+            }
+        )
+    }
+
+    async fn verify_all(&self, output: &mut mpsc::Sender<Message>) -> Result<Vec<VerifyIssue>, EngineError> {
+        let (mut child, mut lines) = eval::spawn_engine(&self.engine_path).await?;
+        let mut issues = Vec::new();
+        let total = self.puzzles.len();
+        for (index, puzzle) in self.puzzles.iter().enumerate() {
+            match verify_puzzle(&mut child, &mut lines, puzzle, self.depth, self.margin).await {
+                Ok(Some(issue)) => issues.push(issue),
+                Ok(None) => (),
+                Err(e) => {
+                    eval::quit_engine(&mut child).await;
+                    return Err(e);
+                }
+            }
+            let _ = output.send(Message::VerifyProgress(index + 1, total)).await;
+        }
+        eval::quit_engine(&mut child).await;
+        Ok(issues)
+    }
+}
+
+// Checks each of the solver's moves, returning the first one that isn't the
+// engine's choice or close enough to it.
+async fn verify_puzzle(child: &mut Child, lines: &mut EngineOutput, puzzle: &config::Puzzle, depth: u32, margin: i32) -> Result<Option<VerifyIssue>, EngineError> {
+    let Ok(mut board) = Board::from_str(&puzzle.fen) else {
+        return Ok(Some(VerifyIssue::new(puzzle, IssueKind::InvalidFen, puzzle.fen.clone(), 0, "")));
+    };
+    eval::send_command(child, "ucinewgame").await?;
+    eval::send_command(child, "isready").await?;
+    eval::wait_for(lines, "readyok").await?;
+    for (ply, uci_move) in puzzle.moves.split_whitespace().enumerate() {
+        let fen = san_correct_ep(board.to_string());
+        if ply == 0 && uci_move == config::NULL_MOVE {
+            continue;
+        }
+        let Some(chess_move) = session::uci_to_move(&board, uci_move) else {
+            return Ok(Some(VerifyIssue::new(puzzle, IssueKind::IllegalMove, fen, ply, uci_move)));
+        };
+        // The first move is the opponent's, the solver plays the odd plies
+        if ply % 2 == 1 {
+            let best = eval::search(child, lines, &fen, depth, None).await?;
+            if best.best_move.as_deref() != Some(uci_move) {
+                let solution = eval::search(child, lines, &fen, depth, Some(uci_move)).await?;
+                if best.score - solution.score > margin {
+                    let mut issue = VerifyIssue::new(puzzle, IssueKind::NotBest, fen, ply, uci_move);
+                    issue.solution_eval = score_to_string(solution.score);
+                    issue.engine_move = best.best_move.unwrap_or_default();
+                    issue.engine_eval = score_to_string(best.score);
+                    return Ok(Some(issue));
+                }
+            }
+        }
+        board = board.make_move_new(chess_move);
+    }
+    Ok(None)
+}
@@ -7,6 +7,8 @@ current_puzzle = 当前谜题
 mode = 模式:
 mode_puzzle = 谜题模式
 mode_analysis = 分析模式
+mode_play_engine = 与引擎对弈
+mode_editor = 编辑器
 previous = {"< "}上一个
 next = 下一个{" >"}
 redo = 重做谜题
@@ -23,6 +25,11 @@ use_search = 使用搜索
 puzzle = 谜题{" "}
 of = {" "}的{" "}
 go = 前往
+move_input_placeholder = 输入着法（Nf3, e2e4）
+move_unreadable = 不是SAN或UCI格式的着法。
+move_illegal = 此局面下的非法着法。
+move_ambiguous = 着法有歧义，可能是：{" "}
+tui_puzzle_solved = 谜题已解决，输入 n 进入下一个，或输入 r 重新开始。
 white_to_move = 白方走棋！
 black_to_move = 黑方走棋！
 correct_move = 正确！现在呢？
@@ -31,6 +38,27 @@ wrong_move_white_play = 糟糕！走错了... 白方走棋。
 wrong_move_black_play = 糟糕！走错了... 黑方走棋。
 all_puzzles_done = 此搜索的所有谜题已完成！
 no_puzzle_found = 抱歉，未找到谜题。
+invalid_puzzle = 此谜题无效（FEN或着法错误）。
+save_game_pgn = 保存对局 (PGN)
+play_your_move = 轮到你走棋！
+play_engine_thinking = 引擎思考中...
+play_you_won = 将杀，你赢了！
+play_you_lost = 将杀，引擎获胜。
+play_draw = 逼和，和棋。
+play_no_engine = 请先在设置中指定有效的引擎路径。
+game_saved = 对局已保存。
+error_saving_game = 保存对局出错：{" "}
+engine_error_not_found = 找不到引擎:{" "}
+engine_error_spawn = 无法启动引擎:{" "}
+engine_error_handshake = 引擎未响应UCI握手。
+engine_error_io = 与引擎通信出错:{" "}
+engine_error_crashed = 引擎崩溃，正在重新启动:{" "}
+engine_error_gave_up = 引擎反复崩溃，已被关闭。
+show_engine_log = 显示引擎控制台
+hide_engine_log = 隐藏引擎控制台
+clear_engine_log = 清空日志
+uci_command = UCI命令，例如 setoption name Threads value 4
+send_uci_command = 发送
 
 # 棋谱标记
 
@@ -52,6 +80,54 @@ download_btn = 下载
 
 lichess_db = Lichess数据库
 my_favories = 我的收藏
+my_puzzles = 我的谜题
+player_name = 你在对局中的名字:
+generate_from_pgn = 从PGN文件生成谜题
+cancel_generation = 停止生成
+analyzing_games = 正在分析对局:{" "}
+new_puzzles = 新谜题:{" "}
+error_reading_pgn = 无法读取PGN文件:{" "}
+error_saving_my_puzzles = 无法将谜题保存到数据库：{" "}
+puzzle_source = 来源:
+all_sources = 所有来源
+source_name = 导入集合的名称:
+import_puzzles = 从PGN/EPD文件导入谜题
+puzzles_imported = 已导入谜题:{" "}
+puzzles_skipped = 已跳过(无唯一解或无效):{" "}
+error_reading_puzzles = 无法读取谜题文件:{" "}
+custom_puzzles = 自定义谜题
+editor = 谜题编辑器
+editor_remove = 移除棋子
+editor_clear = 清空棋盘
+editor_initial = 初始局面
+load_fen = 载入
+editor_analyze = 用引擎分析
+fen_error_format = 无法读取FEN，格式应为: rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+editor_side_to_move = 走棋方:
+editor_castling = 易位权:
+editor_en_passant = 吃过路兵的列:
+editor_enter_solution = 输入答案
+editor_play_solution = 在棋盘上走出答案，从走棋方开始。
+editor_setup_move = 第一步是对手的(在谜题开始前走出)
+editor_solution = 答案:
+editor_undo = 撤销一步
+editor_back_to_setup = 编辑局面
+editor_themes = 主题:
+editor_add_theme = 添加主题
+editor_rating = 等级分:
+editor_save = 保存谜题
+editor_saved = 谜题已保存:{" "}
+editor_error_kings = 双方必须各有且仅有一个王。
+editor_error_pawns = 兵不能位于第一或最后一横线。
+editor_error_castling = 易位需要王和车在初始格。
+editor_error_en_passant = 该列上没有可以被吃过路兵的兵。
+editor_error_check = 刚走完棋的一方不能处于被将军状态。
+editor_error_invalid = 该局面无效。
+editor_error_game_over = 该局面没有合法着法。
+editor_error_no_solution = 请先在棋盘上走出答案。
+editor_error_solution_end = 答案必须以解题方的着法结束。
+editor_error_rating = 等级分必须是0到3500之间的数字。
+editor_error_saving = 无法保存谜题。
 min_rating = 最低等级分:{" "}
 max_rating = 最高等级分:{" "}
 min_popularity = 最低热度:{" "}
@@ -80,11 +156,91 @@ play_sound = 走棋时播放声音:
 auto_load = 自动加载下一个谜题:
 flip_board = 翻转棋盘:
 show_coords = 显示坐标:
+show_eval_bar = 显示评分条:
+blindfold = 盲棋：
+blindfold_off = 关闭
+blindfold_hide_all = 隐藏所有棋子
+blindfold_pawns_only = 只显示兵
+blindfold_hide_after = 隐藏于
+blindfold_seconds = 秒后
+show_shortcuts = 键盘快捷键
+hide_shortcuts = 隐藏快捷键
+reset_shortcuts = 恢复默认按键
+press_a_key = 请按键（Esc取消）
+shortcut_cursor_up = 光标上移
+shortcut_cursor_down = 光标下移
+shortcut_cursor_left = 光标左移
+shortcut_cursor_right = 光标右移
+shortcut_select = 选择/移动到光标处
+shortcut_hint = 提示
+shortcut_next = 下一题
+shortcut_previous = 上一题
+shortcut_redo = 重做
+shortcut_takeback = 悔棋
+shortcut_flip = 翻转棋盘
+shortcut_engine = 启动/停止引擎
+shortcut_favorite = 收藏
+shortcut_read_position = 朗读局面
+peek = 偷看
+hide_pieces = 隐藏棋子
+solved_with_peeks = 已解出，但偷看了：{" "}
+describe_position = 以文字描述局面：
+tts_command = 语音命令（留空 = 不朗读）：
+animate_moves = 动画显示走子：
+animation_ms = 时长（毫秒）：
+reply_delay_ms = 对手应着前的延迟（毫秒）：
+read_position = 朗读
+clear_marks = 清除箭头
+opponent_played = 对手走了：{" "}
+piece_name_king = 王
+piece_name_queen = 后
+piece_name_rook = 车
+piece_name_bishop = 象
+piece_name_knight = 马
+piece_name_pawn = 兵
+move_to = 到
+move_takes = 吃
+move_promotes = 升变为
+move_check = 将军
+move_checkmate = 将死
+castles_kingside = 王翼易位
+castles_queenside = 后翼易位
+engine_arrows = 引擎箭头数量 (0 = 无):
 pdf_number_of_pages = 导出PDF的页数:
+pdf_diagrams_per_page = 每页棋图数：
+pdf_square_size = 格子大小 (pt)：
+pdf_page_size = 纸张大小：
+pdf_landscape = 横向
+pdf_answer_key_option = 在 PDF 中附加答案
+pdf_answer_key = 答案
 get_first_puzzles1 = 获取前
 get_first_puzzles2 = {" "}个谜题
 export_pgn = 导出当前谜题为PGN文件
+pgn_exported = 已导出为 PGN 的谜题：{" "}
+error_exporting_pgn = 导出 PGN 文件出错：{" "}
+export_anki = 导出当前谜题到 Anki
+anki_exported = 已导出 Anki 笔记（请将 .media 文件夹中的图片复制到 Anki 的 collection.media）：{" "}
+error_exporting_anki = 导出到 Anki 出错：{" "}
+export_html = 导出当前谜题为 HTML 页面
+html_exported = HTML 页面中的谜题数：{" "}
+error_exporting_html = 导出 HTML 页面出错：{" "}
+export_csv = 导出当前谜题为 CSV（lichess 格式）
+export_base_csv = 全部导出为 CSV（lichess 格式）
+csv_exported = 已导出到 CSV 的谜题：{" "}
+error_exporting_csv = 导出 CSV 文件出错：{" "}
+open_pgn = 打开导出的PGN谜题
 engine_path = 引擎路径（含.exe文件名）:
+play_engine_limit = 对弈时的引擎强度 (go 命令):
+verify_depth = 验证和生成谜题的搜索深度:
+verify_margin = 容差(百分兵):
+verify_puzzles = 用引擎验证谜题
+cancel_verification = 取消验证
+verifying = 正在验证:{" "}
+verify_done = 解法存疑的谜题:{" "}
+verify_invalid_data = 无效的FEN或着法
+export_verify_report = 导出报告(CSV)
+verify_report_exported = 已导出到报告的问题：{" "}
+error_exporting_verify_report = 导出报告出错：{" "}
 save = 保存更改
 settings_saved = 设置已保存！
 error_saving = 保存配置文件时出错。
@@ -103,11 +259,18 @@ times_played = 对弈次数（在lichess上）:{" "}
 themes = 主题:
 url = 对局网址:{" "}
 screenshot = 保存为图片
+export_diagrams = 导出全部棋图
+diagrams_exported = 已导出棋图：{" "}
+error_saving_diagram = 保存棋图出错：{" "}
 export_pdf_btn = 导出当前谜题为PDF
+pdf_exported = 已导出为 PDF 的谜题：{" "}
+error_exporting_pdf = 导出 PDF 文件出错：{" "}
 no_puzzle = 未加载谜题
 
 pdf_black_to_move = ）黑方走棋。上一步:{" "}
 pdf_white_to_move = ）白方走棋。上一步:...{" "}
+pdf_black_to_play = ）黑方走棋。
+pdf_white_to_play = ）白方走棋。
 
 # 主题
 
@@ -6,6 +6,8 @@ current_puzzle = Current Puzzle
 mode = Mode:
 mode_puzzle = Puzzle
 mode_analysis = Analysis
+mode_play_engine = Play vs engine
+mode_editor = Editor
 previous = {"< "}Previous
 next = Next{" >"}
 redo = Redo Puzzle
@@ -22,6 +24,11 @@ use_search = Use the search
 puzzle = Puzzle{" "}
 of = {" "}of{" "}
 go = Go
+move_input_placeholder = Type a move (Nf3, e2e4)
+move_unreadable = Not a move in SAN or UCI.
+move_illegal = Illegal move in this position.
+move_ambiguous = Ambiguous move, it could be:{" "}
+tui_puzzle_solved = The puzzle is already solved, type n for the next one or r to try it again.
 white_to_move = White to move!
 black_to_move = Black to move!
 correct_move = Correct! What now?
@@ -30,6 +37,27 @@ wrong_move_white_play = Oops! Wrong move... White to play.
 wrong_move_black_play = Oops! Wrong move... Black to play.
 all_puzzles_done = All puzzles done for this search!
 no_puzzle_found = Sorry, no puzzle found.
+invalid_puzzle = This puzzle is invalid (wrong FEN or moves).
+save_game_pgn = Save game (PGN)
+play_your_move = Your move!
+play_engine_thinking = The engine is thinking...
+play_you_won = Checkmate, you won!
+play_you_lost = Checkmate, the engine won.
+play_draw = Stalemate, it's a draw.
+play_no_engine = Set a valid engine path in the settings first.
+game_saved = Game saved.
+error_saving_game = Error saving the game:{" "}
+engine_error_not_found = Engine not found:{" "}
+engine_error_spawn = Couldn't start the engine:{" "}
+engine_error_handshake = The engine didn't answer the UCI handshake.
+engine_error_io = Error communicating with the engine:{" "}
+engine_error_crashed = The engine crashed and is being restarted:{" "}
+engine_error_gave_up = The engine keeps crashing, so it was turned off.
+show_engine_log = Show engine console
+hide_engine_log = Hide engine console
+clear_engine_log = Clear log
+uci_command = UCI command, e.g. setoption name Threads value 4
+send_uci_command = Send
 
 #Notation
 rook = R
@@ -48,6 +76,54 @@ download_btn = Download
 #Search Tab
 lichess_db = Lichess DB
 my_favories = My Favorites
+my_puzzles = My puzzles
+player_name = Your name in the games:
+generate_from_pgn = Generate puzzles from a PGN file
+cancel_generation = Stop generating
+analyzing_games = Analyzing games:{" "}
+new_puzzles = New puzzles:{" "}
+error_reading_pgn = Couldn't read the PGN file:{" "}
+error_saving_my_puzzles = Couldn't save the puzzles in the database:{" "}
+puzzle_source = Source:
+all_sources = All sources
+source_name = Name for the imported set:
+import_puzzles = Import puzzles from a PGN/EPD file
+puzzles_imported = Puzzles imported:{" "}
+puzzles_skipped = Skipped (no single solution or invalid):{" "}
+error_reading_puzzles = Couldn't read the puzzles file:{" "}
+custom_puzzles = Custom puzzles
+editor = Puzzle Editor
+editor_remove = Remove piece
+editor_clear = Clear board
+editor_initial = Initial position
+load_fen = Load
+editor_analyze = Analyze with the engine
+fen_error_format = The FEN couldn't be read, it should look like: rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+editor_side_to_move = Side to move:
+editor_castling = Castling rights:
+editor_en_passant = En passant file:
+editor_enter_solution = Enter the solution
+editor_play_solution = Play the solution on the board, starting with the side to move.
+editor_setup_move = The first move is the opponent's (played before the puzzle starts)
+editor_solution = Solution:
+editor_undo = Undo move
+editor_back_to_setup = Edit the position
+editor_themes = Themes:
+editor_add_theme = Add a theme
+editor_rating = Rating:
+editor_save = Save puzzle
+editor_saved = Puzzle saved:{" "}
+editor_error_kings = Each side needs exactly one king.
+editor_error_pawns = Pawns can't be on the first or last rank.
+editor_error_castling = Castling needs the king and the rook on their initial squares.
+editor_error_en_passant = No pawn can be captured en passant on that file.
+editor_error_check = The side that just moved can't be in check.
+editor_error_invalid = This position isn't valid.
+editor_error_game_over = There are no legal moves in this position.
+editor_error_no_solution = Play the solution on the board first.
+editor_error_solution_end = The solution must end with a move of the side solving the puzzle.
+editor_error_rating = The rating must be a number between 0 and 3500.
+editor_error_saving = Couldn't save the puzzle.
 min_rating = Min. Rating:{" "}
 max_rating = Max. Rating:{" "}
 min_popularity = Minimum popularity:{" "}
@@ -76,11 +152,91 @@ play_sound = Play sound on moves:
 auto_load = Auto load next puzzle:
 flip_board = Flip board:
 show_coords = Show coordinates:
+show_eval_bar = Show evaluation bar:
+blindfold = Blindfold:
+blindfold_off = Off
+blindfold_hide_all = Hide all pieces
+blindfold_pawns_only = Show only pawns
+blindfold_hide_after = Hide after
+blindfold_seconds = seconds
+show_shortcuts = Keyboard shortcuts
+hide_shortcuts = Hide shortcuts
+reset_shortcuts = Restore the default keys
+press_a_key = Press a key (Esc cancels)
+shortcut_cursor_up = Cursor up
+shortcut_cursor_down = Cursor down
+shortcut_cursor_left = Cursor left
+shortcut_cursor_right = Cursor right
+shortcut_select = Select / move to the cursor
+shortcut_hint = Hint
+shortcut_next = Next puzzle
+shortcut_previous = Previous puzzle
+shortcut_redo = Redo puzzle
+shortcut_takeback = Take back
+shortcut_flip = Flip the board
+shortcut_engine = Start / stop the engine
+shortcut_favorite = Favorite
+shortcut_read_position = Read the position aloud
+peek = Peek
+hide_pieces = Hide pieces
+solved_with_peeks = Solved, but with peeks:{" "}
+describe_position = Describe the position as text:
+tts_command = Speech command (empty = no speech):
+animate_moves = Animate the moves:
+animation_ms = Duration (ms):
+reply_delay_ms = Delay before the opponent's reply (ms):
+read_position = Read aloud
+clear_marks = Clear arrows
+opponent_played = Opponent played:{" "}
+piece_name_king = King
+piece_name_queen = Queen
+piece_name_rook = Rook
+piece_name_bishop = Bishop
+piece_name_knight = Knight
+piece_name_pawn = Pawn
+move_to = to
+move_takes = takes
+move_promotes = promotes to
+move_check = check
+move_checkmate = checkmate
+castles_kingside = castles kingside
+castles_queenside = castles queenside
+engine_arrows = Engine arrows (0 = none):
 pdf_number_of_pages = No. of pages to export to PDF:
+pdf_diagrams_per_page = Diagrams per page:
+pdf_square_size = Square size (pt):
+pdf_page_size = Page size:
+pdf_landscape = Landscape
+pdf_answer_key_option = Add an answer key to the PDF
+pdf_answer_key = Answer key
 get_first_puzzles1 = Get the first
 get_first_puzzles2 =  {" "}puzzles
 export_pgn = Export current puzzles as PGN
+pgn_exported = Puzzles exported to PGN:{" "}
+error_exporting_pgn = Error exporting the PGN file:{" "}
+export_anki = Export current puzzles to Anki
+anki_exported = Anki notes exported (copy the images in the .media folder to Anki's collection.media):{" "}
+error_exporting_anki = Error exporting to Anki:{" "}
+export_html = Export current puzzles as an HTML page
+html_exported = Puzzles in the HTML page:{" "}
+error_exporting_html = Error exporting the HTML page:{" "}
+export_csv = Export current puzzles as CSV (lichess format)
+export_base_csv = Export all as CSV (lichess format)
+csv_exported = Puzzles exported to CSV:{" "}
+error_exporting_csv = Error exporting the CSV file:{" "}
+open_pgn = Open puzzles exported as PGN
 engine_path = Engine path (with .exe name):
+play_engine_limit = Engine strength when playing (go command):
+verify_depth = Depth to verify and generate puzzles:
+verify_margin = Tolerance (centipawns):
+verify_puzzles = Verify puzzles with the engine
+cancel_verification = Cancel verification
+verifying = Verifying:{" "}
+verify_done = Puzzles with questionable solutions:{" "}
+verify_invalid_data = invalid FEN or move
+export_verify_report = Export report (CSV)
+verify_report_exported = Issues exported to the report:{" "}
+error_exporting_verify_report = Error exporting the report:{" "}
 save = Save Changes
 settings_saved = Settings saved!
 error_saving = Error saving config file.
@@ -99,11 +255,18 @@ times_played = Times Played (on lichess):{" "}
 themes = Themes:
 url = Game url:{" "}
 screenshot = Save as Image
+export_diagrams = Export all diagrams
+diagrams_exported = Diagrams exported:{" "}
+error_saving_diagram = Error saving the diagram:{" "}
 export_pdf_btn = Export current puzzles to PDF
+pdf_exported = Puzzles exported to PDF:{" "}
+error_exporting_pdf = Error exporting the PDF file:{" "}
 no_puzzle = No puzzle loaded
 
 pdf_black_to_move = ) Black to move. Last move:{" "}
 pdf_white_to_move = ) White to move. Last move: ...{" "}
+pdf_black_to_play = ) Black to move.
+pdf_white_to_play = ) White to move.
 
 #THEMES
 #============================================================
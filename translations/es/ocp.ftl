@@ -6,6 +6,8 @@ current_puzzle = Ejercício actual
 mode = Modo:
 mode_puzzle = Ejercício
 mode_analysis = Análisis
+mode_play_engine = Jugar contra el engine
+mode_editor = Editor
 previous = {"< "}Anterior
 next = Próximo{" >"}
 redo = Rehacer
@@ -22,6 +24,11 @@ use_search = Haga la Búsqueda
 puzzle = Problema{" "}
 of = {" "}de{" "}
 go = Ir
+move_input_placeholder = Escribe una jugada (Cf3, e2e4)
+move_unreadable = No es una jugada en SAN o UCI.
+move_illegal = Jugada ilegal en esta posición.
+move_ambiguous = Jugada ambigua, puede ser:{" "}
+tui_puzzle_solved = El puzzle ya está resuelto, escribe n para el siguiente o r para intentarlo de nuevo.
 white_to_move = Blancas juegan!
 black_to_move = Negras juegan!
 correct_move = Correcto! Y ahora?
@@ -30,6 +37,27 @@ wrong_move_white_play = No! Ese no es el movimiento... Blancas juegan.
 wrong_move_black_play = No! Ese no es el movimiento... Negras juegan.
 all_puzzles_done = Ya ha hecho todos los ejercícios de esta búsqueda!
 no_puzzle_found = Lo siento, ningún ejercício encontrado.
+invalid_puzzle = Este problema no es válido (FEN o jugadas incorrectas).
+save_game_pgn = Guardar partida (PGN)
+play_your_move = ¡Su turno!
+play_engine_thinking = El engine está pensando...
+play_you_won = ¡Jaque mate, ha ganado!
+play_you_lost = Jaque mate, ganó el engine.
+play_draw = Rey ahogado, tablas.
+play_no_engine = Primero configure una ruta válida para el engine.
+game_saved = Partida guardada.
+error_saving_game = Error al guardar la partida:{" "}
+engine_error_not_found = Engine no encontrado:{" "}
+engine_error_spawn = No se pudo iniciar el engine:{" "}
+engine_error_handshake = El engine no respondió a los comandos UCI iniciales.
+engine_error_io = Error de comunicación con el engine:{" "}
+engine_error_crashed = El engine falló y se está reiniciando:{" "}
+engine_error_gave_up = El engine sigue fallando, así que fue apagado.
+show_engine_log = Mostrar consola del engine
+hide_engine_log = Ocultar consola del engine
+clear_engine_log = Limpiar log
+uci_command = Comando UCI, ej: setoption name Threads value 4
+send_uci_command = Enviar
 
 #Notation
 rook = T
@@ -48,6 +76,54 @@ download_btn = Descargar
 #Search Tab
 lichess_db = Base de datos de Lichess
 my_favories = Mis favoritos
+my_puzzles = Mis puzzles
+player_name = Tu nombre en las partidas:
+generate_from_pgn = Generar puzzles desde un archivo PGN
+cancel_generation = Dejar de generar
+analyzing_games = Analizando partidas:{" "}
+new_puzzles = Puzzles nuevos:{" "}
+error_reading_pgn = No se pudo leer el archivo PGN:{" "}
+error_saving_my_puzzles = No se pudieron guardar los puzzles en la base de datos:{" "}
+puzzle_source = Origen:
+all_sources = Todos los orígenes
+source_name = Nombre para el conjunto importado:
+import_puzzles = Importar puzzles desde un archivo PGN/EPD
+puzzles_imported = Puzzles importados:{" "}
+puzzles_skipped = Omitidos (sin una única solución o inválidos):{" "}
+error_reading_puzzles = No se pudo leer el archivo de puzzles:{" "}
+custom_puzzles = Puzzles propios
+editor = Editor de Puzzles
+editor_remove = Quitar pieza
+editor_clear = Vaciar tablero
+editor_initial = Posición inicial
+load_fen = Cargar
+editor_analyze = Analizar con el motor
+fen_error_format = No se pudo leer el FEN, debe ser así: rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+editor_side_to_move = Juegan:
+editor_castling = Derechos de enroque:
+editor_en_passant = Columna al paso:
+editor_enter_solution = Introducir la solución
+editor_play_solution = Juega la solución en el tablero, empezando por el bando que mueve.
+editor_setup_move = La primera jugada es del rival (se juega antes de empezar el puzzle)
+editor_solution = Solución:
+editor_undo = Deshacer jugada
+editor_back_to_setup = Editar la posición
+editor_themes = Temas:
+editor_add_theme = Añadir un tema
+editor_rating = Rating:
+editor_save = Guardar puzzle
+editor_saved = Puzzle guardado:{" "}
+editor_error_kings = Cada bando necesita exactamente un rey.
+editor_error_pawns = Los peones no pueden estar en la primera ni en la última fila.
+editor_error_castling = El enroque necesita el rey y la torre en sus casillas iniciales.
+editor_error_en_passant = Ningún peón puede ser capturado al paso en esa columna.
+editor_error_check = El bando que acaba de mover no puede estar en jaque.
+editor_error_invalid = Esta posición no es válida.
+editor_error_game_over = No hay jugadas legales en esta posición.
+editor_error_no_solution = Primero juega la solución en el tablero.
+editor_error_solution_end = La solución debe terminar con una jugada del bando que resuelve el puzzle.
+editor_error_rating = El rating debe ser un número entre 0 y 3500.
+editor_error_saving = No se pudo guardar el puzzle.
 min_rating = Rating Min.:{" "}
 max_rating = Rating Max.:{" "}
 min_popularity = Popularidad mínima:{" "}
@@ -76,11 +152,91 @@ play_sound = Sonido en los movimientos:
 auto_load = Cargar automaticamente el próx. ejercício:
 flip_board = Girar el tablero:
 show_coords = Coordenadas del tablero:
+show_eval_bar = Mostrar barra de evaluación:
+blindfold = A ciegas:
+blindfold_off = Desactivado
+blindfold_hide_all = Ocultar todas las piezas
+blindfold_pawns_only = Mostrar solo los peones
+blindfold_hide_after = Ocultar después de
+blindfold_seconds = segundos
+show_shortcuts = Atajos de teclado
+hide_shortcuts = Ocultar atajos
+reset_shortcuts = Restaurar las teclas predeterminadas
+press_a_key = Pulsa una tecla (Esc cancela)
+shortcut_cursor_up = Cursor arriba
+shortcut_cursor_down = Cursor abajo
+shortcut_cursor_left = Cursor a la izquierda
+shortcut_cursor_right = Cursor a la derecha
+shortcut_select = Seleccionar / mover al cursor
+shortcut_hint = Pista
+shortcut_next = Siguiente problema
+shortcut_previous = Problema anterior
+shortcut_redo = Repetir problema
+shortcut_takeback = Deshacer jugada
+shortcut_flip = Girar el tablero
+shortcut_engine = Iniciar / detener el motor
+shortcut_favorite = Favorito
+shortcut_read_position = Leer la posición en voz alta
+peek = Mirar
+hide_pieces = Ocultar piezas
+solved_with_peeks = Resuelto, pero mirando:{" "}
+describe_position = Describir la posición como texto:
+tts_command = Comando de voz (vacío = sin voz):
+animate_moves = Animar las jugadas:
+animation_ms = Duración (ms):
+reply_delay_ms = Espera antes de la respuesta del rival (ms):
+read_position = Leer en voz alta
+clear_marks = Borrar flechas
+opponent_played = El rival jugó:{" "}
+piece_name_king = Rey
+piece_name_queen = Dama
+piece_name_rook = Torre
+piece_name_bishop = Alfil
+piece_name_knight = Caballo
+piece_name_pawn = Peón
+move_to = a
+move_takes = captura
+move_promotes = corona en
+move_check = jaque
+move_checkmate = jaque mate
+castles_kingside = enroque corto
+castles_queenside = enroque largo
+engine_arrows = Flechas del engine (0 = ninguna):
 pdf_number_of_pages = N. de páginas para exportar en PDF:
+pdf_diagrams_per_page = Diagramas por página:
+pdf_square_size = Tamaño de casilla (pt):
+pdf_page_size = Tamaño de página:
+pdf_landscape = Horizontal
+pdf_answer_key_option = Añadir soluciones al PDF
+pdf_answer_key = Soluciones
 get_first_puzzles1 = Obtener los primeros
 get_first_puzzles2 =  {" "}ejercícios
 export_pgn = Exportar ejercicios actuales a PGN
+pgn_exported = Puzzles exportados a PGN:{" "}
+error_exporting_pgn = Error al exportar el archivo PGN:{" "}
+export_anki = Exportar los puzzles actuales a Anki
+anki_exported = Notas de Anki exportadas (copia las imágenes de la carpeta .media a collection.media de Anki):{" "}
+error_exporting_anki = Error al exportar a Anki:{" "}
+export_html = Exportar los puzzles actuales como página HTML
+html_exported = Puzzles en la página HTML:{" "}
+error_exporting_html = Error al exportar la página HTML:{" "}
+export_csv = Exportar los puzzles actuales como CSV (formato de lichess)
+export_base_csv = Exportar todo como CSV (formato de lichess)
+csv_exported = Puzzles exportados a CSV:{" "}
+error_exporting_csv = Error al exportar el archivo CSV:{" "}
+open_pgn = Abrir ejercicios exportados a PGN
 engine_path = Camino del motor de ajedrez (con el nombre del .exe):
+play_engine_limit = Fuerza del engine al jugar (comando go):
+verify_depth = Profundidad para verificar y generar puzzles:
+verify_margin = Tolerancia (centipeones):
+verify_puzzles = Verificar puzzles con el engine
+cancel_verification = Cancelar verificación
+verifying = Verificando:{" "}
+verify_done = Puzzles con soluciones dudosas:{" "}
+verify_invalid_data = FEN o jugada inválida
+export_verify_report = Exportar informe (CSV)
+verify_report_exported = Problemas exportados al informe:{" "}
+error_exporting_verify_report = Error al exportar el informe:{" "}
 save = Guardar Cambios
 settings_saved = Preferencias guardadas!
 error_saving = Error al guardar el fichero con las preferencias.
@@ -99,11 +255,18 @@ times_played = Nº de veces que fue jugado (en lichess):{" "}
 themes = Temas:
 url = URL del juego:{" "}
 screenshot = Guardar como imagen
+export_diagrams = Exportar todos los diagramas
+diagrams_exported = Diagramas exportados:{" "}
+error_saving_diagram = Error al guardar el diagrama:{" "}
 export_pdf_btn = Exportar ejercícios para PDF
+pdf_exported = Puzzles exportados a PDF:{" "}
+error_exporting_pdf = Error al exportar el archivo PDF:{" "}
 no_puzzle = Ningún ejercício cargado
 
 pdf_black_to_move = ) Juegan negras. Ultimo mov.:{" "}
 pdf_white_to_move = ) Juegan blancas. Ultimo mov.: ...{" "}
+pdf_black_to_play = ) Juegan negras.
+pdf_white_to_play = ) Juegan blancas.
 
 #THEMES
 #============================================================
@@ -8,6 +8,8 @@ current_puzzle = Puzzle actuel
 mode = Mode:
 mode_puzzle = Puzzle
 mode_analysis = Analyse
+mode_play_engine = Jouer contre le moteur
+mode_editor = Éditeur
 previous = {"< "}Précédent
 next = Suivant{" >"}
 redo = Recommencer ce puzzle
@@ -24,6 +26,11 @@ use_search = Utilisez la recherche
 puzzle = Puzzle{" "}
 of = {" "}sur{" "}
 go = Aller
+move_input_placeholder = Tapez un coup (Cf3, e2e4)
+move_unreadable = Ce n'est pas un coup en SAN ou UCI.
+move_illegal = Coup illégal dans cette position.
+move_ambiguous = Coup ambigu, il peut s'agir de :{" "}
+tui_puzzle_solved = Le problème est déjà résolu, tapez n pour le suivant ou r pour le refaire.
 white_to_move = Trait aux Blancs !
 black_to_move = Trait aux Noirs !
 correct_move = Correct ! Et maintenant ?
@@ -32,6 +39,27 @@ wrong_move_white_play = Oups ! Erreur... Trait aux Blancs.
 wrong_move_black_play = Oups ! Erreur... Trait aux Noirs.
 all_puzzles_done = Tous les puzzles ont été réalisés pour cette recherche !
 no_puzzle_found = Désolé, aucun puzzle n'a été trouvé.
+invalid_puzzle = Ce problème est invalide (FEN ou coups incorrects).
+save_game_pgn = Enregistrer la partie (PGN)
+play_your_move = À vous de jouer !
+play_engine_thinking = Le moteur réfléchit...
+play_you_won = Échec et mat, vous avez gagné !
+play_you_lost = Échec et mat, le moteur a gagné.
+play_draw = Pat, partie nulle.
+play_no_engine = Indiquez d'abord un chemin de moteur valide dans les paramètres.
+game_saved = Partie enregistrée.
+error_saving_game = Erreur lors de l'enregistrement de la partie :{" "}
+engine_error_not_found = Moteur introuvable :{" "}
+engine_error_spawn = Impossible de lancer le moteur :{" "}
+engine_error_handshake = Le moteur n'a pas répondu à l'initialisation UCI.
+engine_error_io = Erreur de communication avec le moteur :{" "}
+engine_error_crashed = Le moteur a planté et redémarre :{" "}
+engine_error_gave_up = Le moteur plante sans cesse, il a donc été arrêté.
+show_engine_log = Afficher la console du moteur
+hide_engine_log = Masquer la console du moteur
+clear_engine_log = Effacer le journal
+uci_command = Commande UCI, ex : setoption name Threads value 4
+send_uci_command = Envoyer
 
 #Notation
 rook = T
@@ -50,6 +78,54 @@ download_btn = Télécharger
 #Search Tab
 lichess_db = BdD Lichess
 my_favories = Mes favoris
+my_puzzles = Mes problèmes
+player_name = Votre nom dans les parties :
+generate_from_pgn = Générer des problèmes depuis un fichier PGN
+cancel_generation = Arrêter la génération
+analyzing_games = Analyse des parties :{" "}
+new_puzzles = Nouveaux problèmes :{" "}
+error_reading_pgn = Impossible de lire le fichier PGN :{" "}
+error_saving_my_puzzles = Impossible d'enregistrer les problèmes dans la base de données :{" "}
+puzzle_source = Source :
+all_sources = Toutes les sources
+source_name = Nom du lot importé :
+import_puzzles = Importer des problèmes depuis un fichier PGN/EPD
+puzzles_imported = Problèmes importés :{" "}
+puzzles_skipped = Ignorés (sans solution unique ou invalides) :{" "}
+error_reading_puzzles = Impossible de lire le fichier de problèmes :{" "}
+custom_puzzles = Problèmes personnalisés
+editor = Éditeur de problèmes
+editor_remove = Retirer la pièce
+editor_clear = Vider l'échiquier
+editor_initial = Position initiale
+load_fen = Charger
+editor_analyze = Analyser avec le moteur
+fen_error_format = Impossible de lire le FEN, il doit ressembler à : rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+editor_side_to_move = Trait aux :
+editor_castling = Droits de roque :
+editor_en_passant = Colonne en passant :
+editor_enter_solution = Saisir la solution
+editor_play_solution = Jouez la solution sur l'échiquier, en commençant par le camp au trait.
+editor_setup_move = Le premier coup est celui de l'adversaire (joué avant le début du problème)
+editor_solution = Solution :
+editor_undo = Annuler le coup
+editor_back_to_setup = Modifier la position
+editor_themes = Thèmes :
+editor_add_theme = Ajouter un thème
+editor_rating = Classement :
+editor_save = Enregistrer le problème
+editor_saved = Problème enregistré :{" "}
+editor_error_kings = Chaque camp doit avoir exactement un roi.
+editor_error_pawns = Les pions ne peuvent pas être sur la première ou la dernière rangée.
+editor_error_castling = Le roque nécessite le roi et la tour sur leurs cases initiales.
+editor_error_en_passant = Aucun pion ne peut être pris en passant sur cette colonne.
+editor_error_check = Le camp qui vient de jouer ne peut pas être en échec.
+editor_error_invalid = Cette position n'est pas valide.
+editor_error_game_over = Il n'y a aucun coup légal dans cette position.
+editor_error_no_solution = Jouez d'abord la solution sur l'échiquier.
+editor_error_solution_end = La solution doit se terminer par un coup du camp qui résout le problème.
+editor_error_rating = Le classement doit être un nombre entre 0 et 3500.
+editor_error_saving = Impossible d'enregistrer le problème.
 min_rating = Classement minimal:{" "}
 max_rating = Classement maximal:{" "}
 min_popularity = Popularité minimale:{" "}
@@ -78,11 +154,91 @@ play_sound = Activer le son sur les déplacements:
 auto_load = Passer immédiatement au puzzle suivant:
 flip_board = Tourner l'échiquier:
 show_coords = Montrer les coordonnées:
+show_eval_bar = Afficher la barre d'évaluation:
+blindfold = À l'aveugle :
+blindfold_off = Désactivé
+blindfold_hide_all = Masquer toutes les pièces
+blindfold_pawns_only = Afficher seulement les pions
+blindfold_hide_after = Masquer après
+blindfold_seconds = secondes
+show_shortcuts = Raccourcis clavier
+hide_shortcuts = Masquer les raccourcis
+reset_shortcuts = Rétablir les touches par défaut
+press_a_key = Appuyez sur une touche (Échap annule)
+shortcut_cursor_up = Curseur vers le haut
+shortcut_cursor_down = Curseur vers le bas
+shortcut_cursor_left = Curseur vers la gauche
+shortcut_cursor_right = Curseur vers la droite
+shortcut_select = Sélectionner / jouer sur le curseur
+shortcut_hint = Indice
+shortcut_next = Problème suivant
+shortcut_previous = Problème précédent
+shortcut_redo = Recommencer le problème
+shortcut_takeback = Reprendre le coup
+shortcut_flip = Retourner l'échiquier
+shortcut_engine = Démarrer / arrêter le moteur
+shortcut_favorite = Favori
+shortcut_read_position = Lire la position à voix haute
+peek = Jeter un œil
+hide_pieces = Masquer les pièces
+solved_with_peeks = Résolu, mais en jetant un œil :{" "}
+describe_position = Décrire la position en texte :
+tts_command = Commande de synthèse vocale (vide = aucune) :
+animate_moves = Animer les coups :
+animation_ms = Durée (ms) :
+reply_delay_ms = Délai avant la réponse de l'adversaire (ms) :
+read_position = Lire à voix haute
+clear_marks = Effacer les flèches
+opponent_played = L'adversaire a joué :{" "}
+piece_name_king = Roi
+piece_name_queen = Dame
+piece_name_rook = Tour
+piece_name_bishop = Fou
+piece_name_knight = Cavalier
+piece_name_pawn = Pion
+move_to = en
+move_takes = prend
+move_promotes = promu en
+move_check = échec
+move_checkmate = échec et mat
+castles_kingside = petit roque
+castles_queenside = grand roque
+engine_arrows = Flèches du moteur (0 = aucune):
 pdf_number_of_pages = Limite de pages pour le PDF:
+pdf_diagrams_per_page = Diagrammes par page :
+pdf_square_size = Taille des cases (pt) :
+pdf_page_size = Format de page :
+pdf_landscape = Paysage
+pdf_answer_key_option = Ajouter les solutions au PDF
+pdf_answer_key = Solutions
 get_first_puzzles1 = Accéder aux
 get_first_puzzles2 = {" "}premiers puzzles
 export_pgn = Exporter les puzzles actuels en PGN
+pgn_exported = Problèmes exportés en PGN :{" "}
+error_exporting_pgn = Erreur lors de l'export du fichier PGN :{" "}
+export_anki = Exporter les puzzles actuels vers Anki
+anki_exported = Notes Anki exportées (copiez les images du dossier .media dans le collection.media d'Anki) :{" "}
+error_exporting_anki = Erreur lors de l'export vers Anki :{" "}
+export_html = Exporter les puzzles actuels en page HTML
+html_exported = Puzzles dans la page HTML :{" "}
+error_exporting_html = Erreur lors de l'export de la page HTML :{" "}
+export_csv = Exporter les puzzles actuels en CSV (format lichess)
+export_base_csv = Tout exporter en CSV (format lichess)
+csv_exported = Puzzles exportés en CSV :{" "}
+error_exporting_csv = Erreur lors de l'export du fichier CSV :{" "}
+open_pgn = Ouvrir des puzzles exportés en PGN
 engine_path = Chemin d'accès du moteur (avec le nom du fichier .exe):
+play_engine_limit = Force du moteur en partie (commande go):
+verify_depth = Profondeur pour vérifier et générer les problèmes :
+verify_margin = Tolérance (centipions) :
+verify_puzzles = Vérifier les problèmes avec le moteur
+cancel_verification = Annuler la vérification
+verifying = Vérification :{" "}
+verify_done = Problèmes à la solution douteuse :{" "}
+verify_invalid_data = FEN ou coup invalide
+export_verify_report = Exporter le rapport (CSV)
+verify_report_exported = Problèmes exportés dans le rapport :{" "}
+error_exporting_verify_report = Erreur lors de l'export du rapport :{" "}
 save = Enregistrer les modifications
 settings_saved = Paramètres enregistrés !
 error_saving = Erreur lors de l'enregistrement du fichier de configuration.
@@ -101,11 +257,18 @@ times_played = Nombre de fois joué (sur Lichess):{" "}
 themes = Thèmes:
 url = URL de la partie:{" "}
 screenshot = Enregistrer en tant qu'image
+export_diagrams = Exporter tous les diagrammes
+diagrams_exported = Diagrammes exportés :{" "}
+error_saving_diagram = Erreur lors de l'enregistrement du diagramme :{" "}
 export_pdf_btn = Exporter en PDF les puzzles de la recherche
+pdf_exported = Problèmes exportés en PDF :{" "}
+error_exporting_pdf = Erreur lors de l'export du fichier PDF :{" "}
 no_puzzle = Aucun puzzle n'est chargé
 
 pdf_black_to_move = ) Trait aux Noirs. Dernier coup:{" "}
 pdf_white_to_move = ) Trait aux Blancs. Dernier coup: ...{" "}
+pdf_black_to_play = ) Trait aux Noirs.
+pdf_white_to_play = ) Trait aux Blancs.
 
 #THEMES
 #============================================================
@@ -6,6 +6,8 @@ current_puzzle = Problema Atual
 mode = Modo:
 mode_puzzle = Problema
 mode_analysis = Análise
+mode_play_engine = Jogar contra o engine
+mode_editor = Editor
 previous = {"< "}Anterior
 next = Próximo{" >"}
 redo = Refazer
@@ -22,6 +24,11 @@ use_search = Use a busca.
 puzzle = Problema{" "}
 of = {" "}de{" "}
 go = Ir
+move_input_placeholder = Digite um lance (Cf3, e2e4)
+move_unreadable = Não é um lance em SAN ou UCI.
+move_illegal = Lance ilegal nesta posição.
+move_ambiguous = Lance ambíguo, pode ser:{" "}
+tui_puzzle_solved = O puzzle já foi resolvido, digite n para o próximo ou r para tentar de novo.
 white_to_move = Brancas jogam!
 black_to_move = Pretas jogam!
 correct_move = Isso! E agora?
@@ -30,6 +37,27 @@ wrong_move_white_play = Eita, lance errado... Brancas jogam.
 wrong_move_black_play = Eita, lance errado... Pretas jogam.
 all_puzzles_done = Todos os problemas dessa busca já resolvidos!
 no_puzzle_found = Desculpe, nenhum problema encontrado.
+invalid_puzzle = Este problema é inválido (FEN ou lances incorretos).
+save_game_pgn = Salvar partida (PGN)
+play_your_move = Sua vez!
+play_engine_thinking = O engine está pensando...
+play_you_won = Xeque-mate, você venceu!
+play_you_lost = Xeque-mate, o engine venceu.
+play_draw = Afogamento, empate.
+play_no_engine = Primeiro configure um caminho válido para o engine.
+game_saved = Partida salva.
+error_saving_game = Erro ao salvar a partida:{" "}
+engine_error_not_found = Engine não encontrado:{" "}
+engine_error_spawn = Não foi possível iniciar o engine:{" "}
+engine_error_handshake = O engine não respondeu aos comandos UCI iniciais.
+engine_error_io = Erro na comunicação com o engine:{" "}
+engine_error_crashed = O engine travou e está sendo reiniciado:{" "}
+engine_error_gave_up = O engine continua travando, então foi desligado.
+show_engine_log = Mostrar console do engine
+hide_engine_log = Esconder console do engine
+clear_engine_log = Limpar log
+uci_command = Comando UCI, ex: setoption name Threads value 4
+send_uci_command = Enviar
 
 #Notation
 rook = T
@@ -48,6 +76,54 @@ download_btn = Baixar
 #Search Tab
 lichess_db = Banco de Dados do Lichess
 my_favories = Meus Favoritos
+my_puzzles = Meus puzzles
+player_name = Seu nome nas partidas:
+generate_from_pgn = Gerar puzzles de um arquivo PGN
+cancel_generation = Parar de gerar
+analyzing_games = Analisando partidas:{" "}
+new_puzzles = Novos puzzles:{" "}
+error_reading_pgn = Não foi possível ler o arquivo PGN:{" "}
+error_saving_my_puzzles = Não foi possível salvar os puzzles no banco de dados:{" "}
+puzzle_source = Origem:
+all_sources = Todas as origens
+source_name = Nome para o conjunto importado:
+import_puzzles = Importar puzzles de um arquivo PGN/EPD
+puzzles_imported = Puzzles importados:{" "}
+puzzles_skipped = Ignorados (sem uma única solução ou inválidos):{" "}
+error_reading_puzzles = Não foi possível ler o arquivo de puzzles:{" "}
+custom_puzzles = Puzzles próprios
+editor = Editor de Puzzles
+editor_remove = Remover peça
+editor_clear = Limpar tabuleiro
+editor_initial = Posição inicial
+load_fen = Carregar
+editor_analyze = Analisar com o motor
+fen_error_format = Não foi possível ler o FEN, ele deve ser assim: rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+editor_side_to_move = Lado a jogar:
+editor_castling = Direitos de roque:
+editor_en_passant = Coluna en passant:
+editor_enter_solution = Inserir a solução
+editor_play_solution = Jogue a solução no tabuleiro, começando pelo lado a jogar.
+editor_setup_move = O primeiro lance é do oponente (jogado antes do puzzle começar)
+editor_solution = Solução:
+editor_undo = Desfazer lance
+editor_back_to_setup = Editar a posição
+editor_themes = Temas:
+editor_add_theme = Adicionar um tema
+editor_rating = Rating:
+editor_save = Salvar puzzle
+editor_saved = Puzzle salvo:{" "}
+editor_error_kings = Cada lado precisa de exatamente um rei.
+editor_error_pawns = Peões não podem estar na primeira ou na última fileira.
+editor_error_castling = O roque precisa do rei e da torre nas casas iniciais.
+editor_error_en_passant = Nenhum peão pode ser capturado en passant nessa coluna.
+editor_error_check = O lado que acabou de jogar não pode estar em xeque.
+editor_error_invalid = Esta posição não é válida.
+editor_error_game_over = Não há lances legais nesta posição.
+editor_error_no_solution = Jogue a solução no tabuleiro primeiro.
+editor_error_solution_end = A solução deve terminar com um lance do lado que resolve o puzzle.
+editor_error_rating = O rating deve ser um número entre 0 e 3500.
+editor_error_saving = Não foi possível salvar o puzzle.
 min_rating = Rating Min.:{" "}
 max_rating = Rating Máx.:{" "}
 min_popularity = Popularidade mínima:{" "}
@@ -76,11 +152,91 @@ play_sound = Som nos lances:
 auto_load = Carregar próx. problema automaticamente:
 flip_board = Girar tabuleiro:
 show_coords = Coordenadas do tabuleiro:
+show_eval_bar = Mostrar barra de avaliação:
+blindfold = Às cegas:
+blindfold_off = Desligado
+blindfold_hide_all = Esconder todas as peças
+blindfold_pawns_only = Mostrar só os peões
+blindfold_hide_after = Esconder depois de
+blindfold_seconds = segundos
+show_shortcuts = Atalhos de teclado
+hide_shortcuts = Esconder atalhos
+reset_shortcuts = Restaurar as teclas padrão
+press_a_key = Pressione uma tecla (Esc cancela)
+shortcut_cursor_up = Cursor para cima
+shortcut_cursor_down = Cursor para baixo
+shortcut_cursor_left = Cursor para a esquerda
+shortcut_cursor_right = Cursor para a direita
+shortcut_select = Selecionar / mover para o cursor
+shortcut_hint = Dica
+shortcut_next = Próximo problema
+shortcut_previous = Problema anterior
+shortcut_redo = Refazer problema
+shortcut_takeback = Voltar lance
+shortcut_flip = Girar o tabuleiro
+shortcut_engine = Ligar / desligar o motor
+shortcut_favorite = Favoritar
+shortcut_read_position = Ler a posição em voz alta
+peek = Espiar
+hide_pieces = Esconder peças
+solved_with_peeks = Resolvido, mas espiando:{" "}
+describe_position = Descrever a posição em texto:
+tts_command = Comando de fala (vazio = sem fala):
+animate_moves = Animar os lances:
+animation_ms = Duração (ms):
+reply_delay_ms = Espera antes da resposta do adversário (ms):
+read_position = Ler em voz alta
+clear_marks = Limpar setas
+opponent_played = O adversário jogou:{" "}
+piece_name_king = Rei
+piece_name_queen = Dama
+piece_name_rook = Torre
+piece_name_bishop = Bispo
+piece_name_knight = Cavalo
+piece_name_pawn = Peão
+move_to = para
+move_takes = captura
+move_promotes = promove a
+move_check = xeque
+move_checkmate = xeque-mate
+castles_kingside = roque pequeno
+castles_queenside = roque grande
+engine_arrows = Setas do engine (0 = nenhuma):
 pdf_number_of_pages = N. de pags. para exportar em PDF:
+pdf_diagrams_per_page = Diagramas por página:
+pdf_square_size = Tamanho da casa (pt):
+pdf_page_size = Tamanho da página:
+pdf_landscape = Paisagem
+pdf_answer_key_option = Adicionar gabarito ao PDF
+pdf_answer_key = Gabarito
 get_first_puzzles1 = Obter os primeiros
 get_first_puzzles2 =  {" "}problemas
 export_pgn = Exportar problemas atuais para PGN
+pgn_exported = Puzzles exportados para PGN:{" "}
+error_exporting_pgn = Erro ao exportar o arquivo PGN:{" "}
+export_anki = Exportar puzzles atuais para o Anki
+anki_exported = Notas do Anki exportadas (copie as imagens da pasta .media para a collection.media do Anki):{" "}
+error_exporting_anki = Erro ao exportar para o Anki:{" "}
+export_html = Exportar puzzles atuais como página HTML
+html_exported = Puzzles na página HTML:{" "}
+error_exporting_html = Erro ao exportar a página HTML:{" "}
+export_csv = Exportar puzzles atuais como CSV (formato do lichess)
+export_base_csv = Exportar tudo como CSV (formato do lichess)
+csv_exported = Puzzles exportados para CSV:{" "}
+error_exporting_csv = Erro ao exportar o arquivo CSV:{" "}
+open_pgn = Abrir problemas exportados em PGN
 engine_path = Caminho para a engine (com o .exe):
+play_engine_limit = Força do engine ao jogar (comando go):
+verify_depth = Profundidade para verificar e gerar puzzles:
+verify_margin = Tolerância (centipeões):
+verify_puzzles = Verificar puzzles com o engine
+cancel_verification = Cancelar verificação
+verifying = Verificando:{" "}
+verify_done = Puzzles com soluções questionáveis:{" "}
+verify_invalid_data = FEN ou lance inválido
+export_verify_report = Exportar relatório (CSV)
+verify_report_exported = Problemas exportados para o relatório:{" "}
+error_exporting_verify_report = Erro ao exportar o relatório:{" "}
 save = Salvar Mudanças
 settings_saved = Configurações salvas!
 error_saving = Erro ao salvar configuração.
@@ -99,11 +255,18 @@ times_played = Nº de vezes jogado (no lichess):{" "}
 themes = Temas:
 url = Url do jogo:{" "}
 screenshot = Salvar como Imagem
+export_diagrams = Exportar todos os diagramas
+diagrams_exported = Diagramas exportados:{" "}
+error_saving_diagram = Erro ao salvar o diagrama:{" "}
 export_pdf_btn = Exportar puzzles para PDF
+pdf_exported = Puzzles exportados para PDF:{" "}
+error_exporting_pdf = Erro ao exportar o arquivo PDF:{" "}
 no_puzzle = Nenhum puzzle carregado
 
 pdf_black_to_move = ) Pretas jogam. Ultimo lance:{" "}
 pdf_white_to_move = ) Brancas jogam. Ultimo lance: ...{" "}
+pdf_black_to_play = ) Pretas jogam.
+pdf_white_to_play = ) Brancas jogam.
 
 #THEMES
 #============================================================